@@ -18,10 +18,13 @@
 
 #[cfg(feature = "parity-db")]
 mod parity_db_adapter;
+mod upgrade;
 mod utils;
 
 use std::{
+	collections::BTreeMap,
 	marker::PhantomData,
+	ops::Range,
 	path::{Path, PathBuf},
 	sync::Arc,
 };
@@ -30,10 +33,11 @@ use codec::{Decode, Encode};
 use parking_lot::Mutex;
 // Substrate
 pub use sc_client_db::DatabaseSource;
-use sp_core::H256;
+use sp_core::{H160, H256};
 pub use sp_database::Database;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, NumberFor, UniqueSaturatedInto};
 // Frontier
+use fp_evm::tracing::CallFrame;
 use fp_storage::{EthereumStorageSchema, PALLET_ETHEREUM_SCHEMA_CACHE};
 
 const DB_HASH_LEN: usize = 32;
@@ -47,21 +51,30 @@ pub struct DatabaseSettings {
 }
 
 pub(crate) mod columns {
-	pub const NUM_COLUMNS: u32 = 4;
+	pub const NUM_COLUMNS: u32 = 7;
 
 	pub const META: u32 = 0;
 	pub const BLOCK_MAPPING: u32 = 1;
 	pub const TRANSACTION_MAPPING: u32 = 2;
 	pub const SYNCED_MAPPING: u32 = 3;
+	pub const TRACE_CACHE: u32 = 4;
+	pub const LOG_INDEX: u32 = 5;
+	pub const CANONICAL_MAPPING: u32 = 6;
 }
 
 pub mod static_keys {
 	pub const CURRENT_SYNCING_TIPS: &[u8] = b"CURRENT_SYNCING_TIPS";
+	pub const NEXT_TRACED_BLOCK: &[u8] = b"NEXT_TRACED_BLOCK";
+	pub const FIRST_LOG_INDEXED_BLOCK: &[u8] = b"FIRST_LOG_INDEXED_BLOCK";
+	pub const NEXT_LOG_INDEXED_BLOCK: &[u8] = b"NEXT_LOG_INDEXED_BLOCK";
+	pub const NON_CANONICAL_MAPPINGS: &[u8] = b"NON_CANONICAL_MAPPINGS";
 }
 
 pub struct Backend<Block: BlockT> {
 	meta: Arc<MetaDb<Block>>,
 	mapping: Arc<MappingDb<Block>>,
+	trace: Arc<TraceDb<Block>>,
+	log_index: Arc<LogIndexDb<Block>>,
 }
 
 /// Returns the frontier database directory.
@@ -105,6 +118,14 @@ impl<Block: BlockT> Backend<Block> {
 				db: db.clone(),
 				_marker: PhantomData,
 			}),
+			trace: Arc::new(TraceDb {
+				db: db.clone(),
+				_marker: PhantomData,
+			}),
+			log_index: Arc::new(LogIndexDb {
+				db: db.clone(),
+				_marker: PhantomData,
+			}),
 		})
 	}
 
@@ -115,6 +136,14 @@ impl<Block: BlockT> Backend<Block> {
 	pub fn meta(&self) -> &Arc<MetaDb<Block>> {
 		&self.meta
 	}
+
+	pub fn trace(&self) -> &Arc<TraceDb<Block>> {
+		&self.trace
+	}
+
+	pub fn log_index(&self) -> &Arc<LogIndexDb<Block>> {
+		&self.log_index
+	}
 }
 
 pub struct MetaDb<Block: BlockT> {
@@ -197,6 +226,15 @@ pub struct TransactionMetadata<Block: BlockT> {
 	pub ethereum_index: u32,
 }
 
+/// Ethereum hashes of a block whose canonicality is tracked.
+#[derive(Clone, Encode, Debug, Decode, PartialEq)]
+pub struct BlockMapping<Block: BlockT> {
+	pub block_hash: Block::Hash,
+	pub block_number: NumberFor<Block>,
+	pub ethereum_block_hash: H256,
+	pub ethereum_transaction_hashes: Vec<H256>,
+}
+
 pub struct MappingDb<Block: BlockT> {
 	db: Arc<dyn Database<DbHash>>,
 	write_lock: Arc<Mutex<()>>,
@@ -240,6 +278,131 @@ impl<Block: BlockT> MappingDb<Block> {
 		}
 	}
 
+	/// Whether a block is part of the canonical chain, if its canonicality is tracked.
+	pub fn is_canonical(&self, block_hash: &Block::Hash) -> Result<Option<bool>, String> {
+		match self
+			.db
+			.get(crate::columns::CANONICAL_MAPPING, &block_hash.encode())
+		{
+			Some(raw) => Ok(Some(
+				bool::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	/// Blocks marked as non-canonical whose mappings are not pruned yet.
+	pub fn non_canonical_mappings(&self) -> Result<Vec<BlockMapping<Block>>, String> {
+		match self.db.get(
+			crate::columns::META,
+			crate::static_keys::NON_CANONICAL_MAPPINGS,
+		) {
+			Some(raw) => Ok(Vec::<BlockMapping<Block>>::decode(&mut &raw[..])
+				.map_err(|e| format!("{:?}", e))?),
+			None => Ok(Vec::new()),
+		}
+	}
+
+	/// Marks a block as canonical or not.
+	///
+	/// The non-canonical blocks are kept aside until they fall below finality, so that
+	/// [`MappingDb::prune_non_canonical`] can remove their transaction mappings.
+	pub fn write_canonicality(
+		&self,
+		mapping: BlockMapping<Block>,
+		canonical: bool,
+	) -> Result<(), String> {
+		let _lock = self.write_lock.lock();
+
+		let mut transaction = sp_database::Transaction::new();
+
+		transaction.set(
+			crate::columns::CANONICAL_MAPPING,
+			&mapping.block_hash.encode(),
+			&canonical.encode(),
+		);
+
+		let mut non_canonical = self.non_canonical_mappings()?;
+		let tracked = non_canonical
+			.iter()
+			.any(|retracted| retracted.block_hash == mapping.block_hash);
+		if canonical == tracked {
+			if canonical {
+				non_canonical.retain(|retracted| retracted.block_hash != mapping.block_hash);
+			} else {
+				non_canonical.push(mapping);
+			}
+			transaction.set(
+				crate::columns::META,
+				crate::static_keys::NON_CANONICAL_MAPPINGS,
+				&non_canonical.encode(),
+			);
+		}
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+
+	/// Removes the mappings of the non-canonical blocks up to the finalized block, which can't
+	/// become canonical anymore. Returns the number of pruned blocks.
+	pub fn prune_non_canonical(&self, finalized_number: NumberFor<Block>) -> Result<usize, String> {
+		let _lock = self.write_lock.lock();
+
+		let (pruned, kept): (Vec<_>, Vec<_>) = self
+			.non_canonical_mappings()?
+			.into_iter()
+			.partition(|mapping| mapping.block_number <= finalized_number);
+		if pruned.is_empty() {
+			return Ok(0);
+		}
+
+		let mut transaction = sp_database::Transaction::new();
+
+		for mapping in &pruned {
+			for ethereum_transaction_hash in &mapping.ethereum_transaction_hashes {
+				let mut metadata = self.transaction_metadata(ethereum_transaction_hash)?;
+				metadata.retain(|meta| meta.block_hash != mapping.block_hash);
+				if metadata.is_empty() {
+					transaction.remove(
+						crate::columns::TRANSACTION_MAPPING,
+						&ethereum_transaction_hash.encode(),
+					);
+				} else {
+					transaction.set(
+						crate::columns::TRANSACTION_MAPPING,
+						&ethereum_transaction_hash.encode(),
+						&metadata.encode(),
+					);
+				}
+			}
+			if self.block_hash(&mapping.ethereum_block_hash)? == Some(mapping.block_hash) {
+				transaction.remove(
+					crate::columns::BLOCK_MAPPING,
+					&mapping.ethereum_block_hash.encode(),
+				);
+			}
+			transaction.remove(
+				crate::columns::CANONICAL_MAPPING,
+				&mapping.block_hash.encode(),
+			);
+		}
+
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::NON_CANONICAL_MAPPINGS,
+			&kept.encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(pruned.len())
+	}
+
 	pub fn write_none(&self, block_hash: Block::Hash) -> Result<(), String> {
 		let _lock = self.write_lock.lock();
 
@@ -300,3 +463,381 @@ impl<Block: BlockT> MappingDb<Block> {
 		Ok(())
 	}
 }
+
+/// Call tree of an Ethereum transaction, as collected by the call tracer.
+#[derive(Clone, Encode, Debug, Decode, PartialEq)]
+pub struct TransactionTrace {
+	pub transaction_hash: H256,
+	pub root: Option<CallFrame>,
+}
+
+/// Cache of the block traces, filled in the background so that the `trace` RPC doesn't
+/// re-execute the history on every request.
+pub struct TraceDb<Block: BlockT> {
+	db: Arc<dyn Database<DbHash>>,
+	_marker: PhantomData<Block>,
+}
+
+impl<Block: BlockT> TraceDb<Block> {
+	pub fn block_traces(
+		&self,
+		block_hash: &Block::Hash,
+	) -> Result<Option<Vec<TransactionTrace>>, String> {
+		match self
+			.db
+			.get(crate::columns::TRACE_CACHE, &block_hash.encode())
+		{
+			Some(raw) => Ok(Some(
+				Vec::<TransactionTrace>::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	/// Number of the next finalized block to be traced.
+	pub fn next_traced_block(&self) -> Result<Option<NumberFor<Block>>, String> {
+		match self
+			.db
+			.get(crate::columns::META, crate::static_keys::NEXT_TRACED_BLOCK)
+		{
+			Some(raw) => Ok(Some(
+				NumberFor::<Block>::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+			)),
+			None => Ok(None),
+		}
+	}
+
+	/// Stores the traces of a block, if any, and moves on to the next block.
+	pub fn write_block_traces(
+		&self,
+		block_hash: Block::Hash,
+		traces: Option<Vec<TransactionTrace>>,
+		next_traced_block: NumberFor<Block>,
+	) -> Result<(), String> {
+		let mut transaction = sp_database::Transaction::new();
+
+		if let Some(traces) = traces {
+			transaction.set(
+				crate::columns::TRACE_CACHE,
+				&block_hash.encode(),
+				&traces.encode(),
+			);
+		}
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::NEXT_TRACED_BLOCK,
+			&next_traced_block.encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+}
+
+/// Position of a log in the canonical chain.
+#[derive(Clone, Copy, Encode, Debug, Decode, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LogPosition {
+	pub block_number: u64,
+	pub transaction_index: u32,
+	/// Index of the log in the block.
+	pub log_index: u32,
+}
+
+/// Index of the logs of the finalized blocks by address and by topic, so that `eth_getLogs`
+/// doesn't have to go through every block of the requested range.
+///
+/// The blocks holding logs of an address or a topic are recorded in a bit set per bucket of
+/// [`LogIndexDb::BUCKET_SIZE`] blocks, and the positions of these logs in an entry per block.
+/// Indexing a block thus only writes entries of a bounded size.
+pub struct LogIndexDb<Block: BlockT> {
+	db: Arc<dyn Database<DbHash>>,
+	_marker: PhantomData<Block>,
+}
+
+impl<Block: BlockT> LogIndexDb<Block> {
+	pub const BUCKET_SIZE: u64 = 128;
+
+	const ADDRESS_BUCKET_PREFIX: u8 = 0;
+	const TOPIC_BUCKET_PREFIX: u8 = 1;
+	const ADDRESS_BLOCK_PREFIX: u8 = 2;
+	const TOPIC_BLOCK_PREFIX: u8 = 3;
+
+	/// Range of the indexed blocks, `None` if the indexing never started.
+	pub fn indexed_range(&self) -> Result<Option<Range<NumberFor<Block>>>, String> {
+		let first = self.db.get(
+			crate::columns::META,
+			crate::static_keys::FIRST_LOG_INDEXED_BLOCK,
+		);
+		let next = self.db.get(
+			crate::columns::META,
+			crate::static_keys::NEXT_LOG_INDEXED_BLOCK,
+		);
+		match (first, next) {
+			(Some(first), Some(next)) => Ok(Some(
+				NumberFor::<Block>::decode(&mut &first[..]).map_err(|e| format!("{:?}", e))?
+					..NumberFor::<Block>::decode(&mut &next[..]).map_err(|e| format!("{:?}", e))?,
+			)),
+			_ => Ok(None),
+		}
+	}
+
+	/// Restarts the indexing at the block `from`, the blocks before it being left out of the
+	/// index.
+	pub fn reset(&self, from: NumberFor<Block>) -> Result<(), String> {
+		let mut transaction = sp_database::Transaction::new();
+
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::FIRST_LOG_INDEXED_BLOCK,
+			&from.encode(),
+		);
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::NEXT_LOG_INDEXED_BLOCK,
+			&from.encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+
+	/// Positions of the logs emitted by the address in the range of blocks, in ascending order.
+	pub fn address_positions(
+		&self,
+		address: &H160,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> Result<Vec<LogPosition>, String> {
+		self.positions(
+			|bucket| (Self::ADDRESS_BUCKET_PREFIX, address, bucket).encode(),
+			|block_number| (Self::ADDRESS_BLOCK_PREFIX, address, block_number).encode(),
+			from,
+			to,
+		)
+	}
+
+	/// Positions of the logs having the topic, at any position, in the range of blocks, in
+	/// ascending order.
+	pub fn topic_positions(
+		&self,
+		topic: &H256,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> Result<Vec<LogPosition>, String> {
+		self.positions(
+			|bucket| (Self::TOPIC_BUCKET_PREFIX, topic, bucket).encode(),
+			|block_number| (Self::TOPIC_BLOCK_PREFIX, topic, block_number).encode(),
+			from,
+			to,
+		)
+	}
+
+	fn positions(
+		&self,
+		bucket_key: impl Fn(u64) -> Vec<u8>,
+		block_key: impl Fn(u64) -> Vec<u8>,
+		from: NumberFor<Block>,
+		to: NumberFor<Block>,
+	) -> Result<Vec<LogPosition>, String> {
+		let from: u64 = from.unique_saturated_into();
+		let to: u64 = to.unique_saturated_into();
+
+		let mut positions = Vec::new();
+		for bucket in (from / Self::BUCKET_SIZE)..=(to / Self::BUCKET_SIZE) {
+			let blocks = match self.db.get(crate::columns::LOG_INDEX, &bucket_key(bucket)) {
+				Some(raw) => u128::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+				None => continue,
+			};
+			for offset in 0..Self::BUCKET_SIZE {
+				let block_number = bucket * Self::BUCKET_SIZE + offset;
+				if blocks & (1 << offset) == 0 || !(from..=to).contains(&block_number) {
+					continue;
+				}
+				if let Some(raw) = self
+					.db
+					.get(crate::columns::LOG_INDEX, &block_key(block_number))
+				{
+					positions.extend(
+						Vec::<LogPosition>::decode(&mut &raw[..])
+							.map_err(|e| format!("{:?}", e))?,
+					);
+				}
+			}
+		}
+
+		Ok(positions)
+	}
+
+	/// Indexes the logs of a block, given along with the index of their transaction in the
+	/// block order, and moves on to the next block.
+	///
+	/// Blocks are to be indexed in ascending order, each of them once.
+	pub fn write_block_logs(
+		&self,
+		block_number: NumberFor<Block>,
+		logs: Vec<(u32, H160, Vec<H256>)>,
+		next_indexed_block: NumberFor<Block>,
+	) -> Result<(), String> {
+		let block_number: u64 = block_number.unique_saturated_into();
+		let bucket = block_number / Self::BUCKET_SIZE;
+
+		let mut additions = BTreeMap::<(Vec<u8>, Vec<u8>), Vec<LogPosition>>::new();
+		for (log_index, (transaction_index, address, topics)) in logs.into_iter().enumerate() {
+			let position = LogPosition {
+				block_number,
+				transaction_index,
+				log_index: log_index as u32,
+			};
+			additions
+				.entry((
+					(Self::ADDRESS_BUCKET_PREFIX, address, bucket).encode(),
+					(Self::ADDRESS_BLOCK_PREFIX, address, block_number).encode(),
+				))
+				.or_default()
+				.push(position);
+			for topic in topics {
+				let positions = additions
+					.entry((
+						(Self::TOPIC_BUCKET_PREFIX, topic, bucket).encode(),
+						(Self::TOPIC_BLOCK_PREFIX, topic, block_number).encode(),
+					))
+					.or_default();
+				// A topic may be repeated in the log.
+				if positions.last() != Some(&position) {
+					positions.push(position);
+				}
+			}
+		}
+
+		let mut transaction = sp_database::Transaction::new();
+
+		let block_bit = 1u128 << (block_number % Self::BUCKET_SIZE);
+		for ((bucket_key, block_key), positions) in additions {
+			let blocks = match self.db.get(crate::columns::LOG_INDEX, &bucket_key) {
+				Some(raw) => u128::decode(&mut &raw[..]).map_err(|e| format!("{:?}", e))?,
+				None => 0,
+			};
+			transaction.set(
+				crate::columns::LOG_INDEX,
+				&bucket_key,
+				&(blocks | block_bit).encode(),
+			);
+			transaction.set(crate::columns::LOG_INDEX, &block_key, &positions.encode());
+		}
+		transaction.set(
+			crate::columns::META,
+			crate::static_keys::NEXT_LOG_INDEXED_BLOCK,
+			&next_indexed_block.encode(),
+		);
+
+		self.db
+			.commit(transaction)
+			.map_err(|e| format!("{:?}", e))?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::testing::{Block as RawBlock, ExtrinsicWrapper};
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+
+	fn log_index() -> LogIndexDb<Block> {
+		LogIndexDb {
+			db: Arc::new(sp_database::MemDb::default()),
+			_marker: PhantomData,
+		}
+	}
+
+	fn position(block_number: u64, transaction_index: u32, log_index: u32) -> LogPosition {
+		LogPosition {
+			block_number,
+			transaction_index,
+			log_index,
+		}
+	}
+
+	#[test]
+	fn indexed_range_starts_at_reset() {
+		let log_index = log_index();
+		assert_eq!(log_index.indexed_range(), Ok(None));
+
+		log_index.reset(5).unwrap();
+		assert_eq!(log_index.indexed_range(), Ok(Some(5..5)));
+
+		log_index.write_block_logs(5, Vec::new(), 6).unwrap();
+		assert_eq!(log_index.indexed_range(), Ok(Some(5..6)));
+
+		log_index.reset(100).unwrap();
+		assert_eq!(log_index.indexed_range(), Ok(Some(100..100)));
+	}
+
+	#[test]
+	fn log_positions_by_address_and_topic() {
+		let log_index = log_index();
+		let (alice, bob) = (H160::repeat_byte(1), H160::repeat_byte(2));
+		let (transfer, approval) = (H256::repeat_byte(1), H256::repeat_byte(2));
+
+		log_index
+			.write_block_logs(
+				1,
+				vec![
+					(0, alice, vec![transfer]),
+					(1, bob, vec![approval, approval]),
+					(1, alice, vec![approval, transfer]),
+				],
+				2,
+			)
+			.unwrap();
+		// In another bucket.
+		log_index
+			.write_block_logs(130, vec![(0, alice, vec![approval])], 131)
+			.unwrap();
+
+		assert_eq!(
+			log_index.address_positions(&alice, 0, 200),
+			Ok(vec![
+				position(1, 0, 0),
+				position(1, 1, 2),
+				position(130, 0, 0)
+			])
+		);
+		assert_eq!(
+			log_index.address_positions(&bob, 0, 200),
+			Ok(vec![position(1, 1, 1)])
+		);
+		assert_eq!(
+			log_index.topic_positions(&transfer, 0, 200),
+			Ok(vec![position(1, 0, 0), position(1, 1, 2)])
+		);
+		// A repeated topic is indexed once.
+		assert_eq!(
+			log_index.topic_positions(&approval, 0, 200),
+			Ok(vec![
+				position(1, 1, 1),
+				position(1, 1, 2),
+				position(130, 0, 0)
+			])
+		);
+
+		// The positions out of the range are left out.
+		assert_eq!(
+			log_index.address_positions(&alice, 2, 130),
+			Ok(vec![position(130, 0, 0)])
+		);
+		assert_eq!(log_index.address_positions(&alice, 2, 129), Ok(Vec::new()));
+		assert_eq!(
+			log_index.topic_positions(&H256::repeat_byte(3), 0, 200),
+			Ok(Vec::new())
+		);
+	}
+}
@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Upgrades of the database layout.
+//!
+//! The version of the layout is stored in a file next to the database. The databases created
+//! before the file was introduced are at version 1.
+
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+const VERSION_FILE_NAME: &str = "db_version";
+
+/// Version of the database layout:
+///
+/// 1. The `META`, `BLOCK_MAPPING`, `TRANSACTION_MAPPING` and `SYNCED_MAPPING` columns.
+/// 2. The `TRACE_CACHE`, `LOG_INDEX` and `CANONICAL_MAPPING` columns are added.
+const CURRENT_VERSION: u32 = 2;
+
+/// Number of columns of the version 1 layout.
+const V1_NUM_COLUMNS: u32 = 4;
+
+#[derive(Clone, Copy)]
+pub(crate) enum DatabaseKind {
+	#[cfg(feature = "kvdb-rocksdb")]
+	RocksDb,
+	#[cfg(feature = "parity-db")]
+	ParityDb,
+}
+
+/// Migrates the database at `path` to the current layout, if it exists.
+///
+/// It has to run before the database is opened, ParityDB refusing to open a database with a
+/// different number of columns.
+pub(crate) fn upgrade_db(path: &Path, kind: DatabaseKind) -> Result<(), String> {
+	let is_empty = path
+		.read_dir()
+		.map_or(true, |mut entries| entries.next().is_none());
+	if is_empty {
+		return Ok(());
+	}
+
+	match current_version(path)? {
+		1 => migrate_1_to_2(path, kind)?,
+		CURRENT_VERSION => return Ok(()),
+		version => {
+			return Err(format!(
+				"Unsupported database version {}, the latest known is {}",
+				version, CURRENT_VERSION
+			))
+		}
+	}
+
+	update_version(path)
+}
+
+/// Records that the database at `path` has the current layout.
+pub(crate) fn update_version(path: &Path) -> Result<(), String> {
+	fs::create_dir_all(path).map_err(|err| format!("{}", err))?;
+	fs::write(version_file_path(path), CURRENT_VERSION.to_string())
+		.map_err(|err| format!("{}", err))
+}
+
+fn current_version(path: &Path) -> Result<u32, String> {
+	match fs::read_to_string(version_file_path(path)) {
+		Ok(content) => content
+			.trim()
+			.parse()
+			.map_err(|_| "Corrupted database version file".to_string()),
+		Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(1),
+		Err(err) => Err(format!("{}", err)),
+	}
+}
+
+fn version_file_path(path: &Path) -> PathBuf {
+	path.join(VERSION_FILE_NAME)
+}
+
+fn migrate_1_to_2(path: &Path, kind: DatabaseKind) -> Result<(), String> {
+	match kind {
+		#[cfg(feature = "kvdb-rocksdb")]
+		DatabaseKind::RocksDb => {
+			let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(V1_NUM_COLUMNS);
+			let path = path
+				.to_str()
+				.ok_or_else(|| "Invalid database path".to_string())?;
+
+			let mut db = kvdb_rocksdb::Database::open(&db_config, &path)
+				.map_err(|err| format!("{}", err))?;
+			for _ in V1_NUM_COLUMNS..crate::columns::NUM_COLUMNS {
+				db.add_column().map_err(|err| format!("{}", err))?;
+			}
+		}
+		#[cfg(feature = "parity-db")]
+		DatabaseKind::ParityDb => {
+			let mut options = parity_db::Options::with_columns(path, V1_NUM_COLUMNS as u8);
+			for _ in V1_NUM_COLUMNS..crate::columns::NUM_COLUMNS {
+				parity_db::Db::add_column(&mut options, Default::default())
+					.map_err(|err| format!("{}", err))?;
+			}
+		}
+	}
+
+	Ok(())
+}
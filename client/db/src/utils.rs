@@ -18,7 +18,7 @@
 
 use std::{path::Path, sync::Arc};
 
-use crate::{Database, DatabaseSettings, DatabaseSource, DbHash};
+use crate::{upgrade, Database, DatabaseSettings, DatabaseSource, DbHash};
 
 pub fn open_database(config: &DatabaseSettings) -> Result<Arc<dyn Database<DbHash>>, String> {
 	let db: Arc<dyn Database<DbHash>> = match &config.source {
@@ -39,6 +39,8 @@ pub fn open_database(config: &DatabaseSettings) -> Result<Arc<dyn Database<DbHas
 
 #[cfg(feature = "kvdb-rocksdb")]
 fn open_kvdb_rocksdb(path: &Path, create: bool) -> Result<Arc<dyn Database<DbHash>>, String> {
+	upgrade::upgrade_db(path, upgrade::DatabaseKind::RocksDb)?;
+
 	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(crate::columns::NUM_COLUMNS);
 	db_config.create_if_missing = create;
 
@@ -47,6 +49,7 @@ fn open_kvdb_rocksdb(path: &Path, create: bool) -> Result<Arc<dyn Database<DbHas
 		.ok_or_else(|| "Invalid database path".to_string())?;
 
 	let db = kvdb_rocksdb::Database::open(&db_config, &path).map_err(|err| format!("{}", err))?;
+	upgrade::update_version(Path::new(path))?;
 	return Ok(sp_database::as_database(db));
 }
 
@@ -57,8 +60,11 @@ fn open_kvdb_rocksdb(_path: &Path, _create: bool) -> Result<Arc<dyn Database<DbH
 
 #[cfg(feature = "parity-db")]
 fn open_parity_db(path: &Path) -> Result<Arc<dyn Database<DbHash>>, String> {
+	upgrade::upgrade_db(path, upgrade::DatabaseKind::ParityDb)?;
+
 	let config = parity_db::Options::with_columns(path, crate::columns::NUM_COLUMNS as u8);
 	let db = parity_db::Db::open_or_create(&config).map_err(|err| format!("{}", err))?;
+	upgrade::update_version(path)?;
 	Ok(Arc::new(crate::parity_db_adapter::DbAdapter(db)))
 }
 
@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Substrate
+use sc_client_api::{BlockImportNotification, FinalityNotification};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+// Frontier
+use fp_consensus::FindLogError;
+
+/// Marks the mapping of a block as canonical or not.
+///
+/// Blocks without an Ethereum block have no mapping to track and are skipped.
+pub fn write_canonicality<Block: BlockT>(
+	frontier_backend: &fc_db::Backend<Block>,
+	header: &Block::Header,
+	canonical: bool,
+) -> Result<(), String> {
+	match fp_consensus::find_log(header.digest()) {
+		Ok(log) => {
+			let post_hashes = log.into_hashes();
+
+			let mapping = fc_db::BlockMapping {
+				block_hash: header.hash(),
+				block_number: *header.number(),
+				ethereum_block_hash: post_hashes.block_hash,
+				ethereum_transaction_hashes: post_hashes.transaction_hashes,
+			};
+			frontier_backend
+				.mapping()
+				.write_canonicality(mapping, canonical)
+		}
+		Err(FindLogError::NotFound) => Ok(()),
+		Err(FindLogError::MultipleLogs) => Err("Multiple logs found".to_string()),
+	}
+}
+
+fn write_canonicality_by_hash<Block: BlockT, B>(
+	substrate_backend: &B,
+	frontier_backend: &fc_db::Backend<Block>,
+	block_hash: Block::Hash,
+	canonical: bool,
+) -> Result<(), String>
+where
+	B: sp_blockchain::HeaderBackend<Block>,
+{
+	let header = substrate_backend
+		.header(BlockId::Hash(block_hash))
+		.map_err(|e| format!("{:?}", e))?
+		.ok_or_else(|| "Header not found".to_string())?;
+	write_canonicality(frontier_backend, &header, canonical)
+}
+
+/// Tracks the canonicality of the mappings from a block import.
+///
+/// A new best block retracts the blocks of the previous best branch and enacts its own
+/// branch, any other imported block is non-canonical.
+pub fn sync_import_canonicality<Block: BlockT, B>(
+	substrate_backend: &B,
+	frontier_backend: &fc_db::Backend<Block>,
+	notification: &BlockImportNotification<Block>,
+) -> Result<(), String>
+where
+	B: sp_blockchain::HeaderBackend<Block>,
+{
+	if !notification.is_new_best {
+		return write_canonicality(frontier_backend, &notification.header, false);
+	}
+
+	if let Some(tree_route) = &notification.tree_route {
+		for retracted in tree_route.retracted() {
+			write_canonicality_by_hash(substrate_backend, frontier_backend, retracted.hash, false)?;
+		}
+		for enacted in tree_route.enacted() {
+			if enacted.hash != notification.hash {
+				write_canonicality_by_hash(
+					substrate_backend,
+					frontier_backend,
+					enacted.hash,
+					true,
+				)?;
+			}
+		}
+	}
+	write_canonicality(frontier_backend, &notification.header, true)
+}
+
+/// Tracks the canonicality of the mappings from a finality notification, and prunes the
+/// non-canonical mappings that fell below finality.
+pub fn sync_finality_canonicality<Block: BlockT, B>(
+	substrate_backend: &B,
+	frontier_backend: &fc_db::Backend<Block>,
+	notification: &FinalityNotification<Block>,
+) -> Result<usize, String>
+where
+	B: sp_blockchain::HeaderBackend<Block>,
+{
+	for block_hash in notification.tree_route.iter() {
+		write_canonicality_by_hash(substrate_backend, frontier_backend, *block_hash, true)?;
+	}
+	write_canonicality(frontier_backend, &notification.header, true)?;
+
+	frontier_backend
+		.mapping()
+		.prune_non_canonical(*notification.header.number())
+}
@@ -18,8 +18,14 @@
 
 #![allow(clippy::too_many_arguments)]
 
+mod canonicality;
+mod log_index;
+mod trace;
 mod worker;
 
+pub use canonicality::{sync_finality_canonicality, sync_import_canonicality, write_canonicality};
+pub use log_index::{sync_log_index, LogIndexSyncWorker};
+pub use trace::{sync_traces, trace_block, TraceSyncWorker};
 pub use worker::{MappingSyncWorker, SyncStrategy};
 
 // Substrate
@@ -153,6 +159,11 @@ where
 			return Ok(false);
 		}
 		sync_block(frontier_backend, &operating_header)?;
+		let canonical = client
+			.hash(*operating_header.number())
+			.map_err(|e| format!("{:?}", e))?
+			== Some(operating_header.hash());
+		write_canonicality(frontier_backend, &operating_header, canonical)?;
 
 		current_syncing_tips.push(*operating_header.parent_hash());
 		frontier_backend
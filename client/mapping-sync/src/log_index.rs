@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use futures::{
+	prelude::*,
+	task::{Context, Poll},
+};
+use futures_timer::Delay;
+use log::debug;
+// Substrate
+use sc_client_api::FinalityNotifications;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
+};
+// Frontier
+use fp_consensus::FindLogError;
+use fp_rpc::TransactionStatus;
+
+/// Whether the state of the block `number` of the canonical chain is available.
+fn has_state<Block: BlockT, C, BE>(
+	client: &C,
+	substrate_backend: &BE,
+	number: NumberFor<Block>,
+) -> Result<bool, String>
+where
+	C: HeaderBackend<Block>,
+	BE: sc_client_api::Backend<Block>,
+{
+	let hash = client
+		.hash(number)
+		.map_err(|e| format!("{:?}", e))?
+		.ok_or_else(|| "Block hash not found".to_string())?;
+	Ok(substrate_backend.have_state_at(&hash, number))
+}
+
+/// Whether the block `hash` contains an Ethereum block, according to its digest.
+fn has_ethereum_block<Block: BlockT, C>(client: &C, hash: Block::Hash) -> Result<bool, String>
+where
+	C: HeaderBackend<Block>,
+{
+	let header = client
+		.header(BlockId::Hash(hash))
+		.map_err(|e| format!("{:?}", e))?
+		.ok_or_else(|| "Block header not found".to_string())?;
+	Ok(!matches!(
+		fp_consensus::find_log(header.digest()),
+		Err(FindLogError::NotFound)
+	))
+}
+
+/// First block in `from..=to` whose state is available, the state of the blocks before it
+/// being pruned.
+fn first_block_with_state<Block: BlockT, C, BE>(
+	client: &C,
+	substrate_backend: &BE,
+	from: NumberFor<Block>,
+	to: NumberFor<Block>,
+) -> Result<Option<NumberFor<Block>>, String>
+where
+	C: HeaderBackend<Block>,
+	BE: sc_client_api::Backend<Block>,
+{
+	if from > to || !has_state(client, substrate_backend, to)? {
+		return Ok(None);
+	}
+
+	let (mut low, mut high) = (from, to);
+	while low < high {
+		let middle = low + (high - low) / 2u32.into();
+		if has_state(client, substrate_backend, middle)? {
+			high = middle;
+		} else {
+			low = middle + One::one();
+		}
+	}
+
+	Ok(Some(high))
+}
+
+/// Indexes the logs of up to `limit` finalized blocks, starting from the next unindexed one.
+///
+/// Only the finalized blocks are indexed, so that the index never has to be reverted. The
+/// indexing starts at `sync_from`, or at the first block whose state is available if it's
+/// pruned, and restarts there if the state of the next block gets pruned before it's indexed.
+/// The transaction statuses of a block are read with `transaction_statuses`. If they can't be
+/// read for a block containing an Ethereum block, an error is returned and the indexing stops
+/// before it.
+pub fn sync_log_index<Block: BlockT, C, BE>(
+	client: &C,
+	substrate_backend: &BE,
+	frontier_backend: &fc_db::Backend<Block>,
+	transaction_statuses: impl Fn(&BlockId<Block>) -> Option<Vec<TransactionStatus>>,
+	limit: usize,
+	sync_from: NumberFor<Block>,
+) -> Result<bool, String>
+where
+	C: HeaderBackend<Block>,
+	BE: sc_client_api::Backend<Block>,
+{
+	let finalized_number = client.info().finalized_number;
+	let log_index = frontier_backend.log_index();
+	let indexed_range = log_index.indexed_range()?;
+
+	let next = indexed_range
+		.as_ref()
+		.map_or(sync_from, |range| range.end.max(sync_from));
+	if next > finalized_number {
+		return Ok(false);
+	}
+	let mut number = match indexed_range {
+		Some(range)
+			if range.end >= sync_from && has_state(client, substrate_backend, range.end)? =>
+		{
+			range.end
+		}
+		_ => match first_block_with_state(client, substrate_backend, next, finalized_number)? {
+			Some(first) => {
+				log_index.reset(first)?;
+				first
+			}
+			None => return Ok(false),
+		},
+	};
+	let mut synced_any = false;
+
+	for _ in 0..limit {
+		if number > finalized_number {
+			break;
+		}
+
+		let hash = client
+			.hash(number)
+			.map_err(|e| format!("{:?}", e))?
+			.ok_or_else(|| "Block hash not found".to_string())?;
+		let statuses = match transaction_statuses(&BlockId::Hash(hash)) {
+			Some(statuses) => statuses,
+			// The blocks without an Ethereum block, for ex. the ones before the Ethereum pallet
+			// was added, have no transaction statuses.
+			None if !has_ethereum_block(client, hash)? => Vec::new(),
+			// The block is left unindexed, to be retried by the next call.
+			None => {
+				return Err(format!(
+					"Transaction statuses of block {} not available",
+					number
+				))
+			}
+		};
+		let logs = statuses
+			.into_iter()
+			.flat_map(|status| {
+				let transaction_index = status.transaction_index;
+				status
+					.logs
+					.into_iter()
+					.map(move |log| (transaction_index, log.address, log.topics))
+			})
+			.collect();
+
+		let indexed_number = number;
+		number += One::one();
+		log_index.write_block_logs(indexed_number, logs, number)?;
+		synced_any = true;
+	}
+
+	Ok(synced_any)
+}
+
+pub struct LogIndexSyncWorker<Block: BlockT, C, BE, F> {
+	finality_notifications: FinalityNotifications<Block>,
+	timeout: Duration,
+	inner_delay: Option<Delay>,
+
+	client: Arc<C>,
+	substrate_backend: Arc<BE>,
+	frontier_backend: Arc<fc_db::Backend<Block>>,
+	transaction_statuses: F,
+
+	have_next: bool,
+	blocks_per_tick: usize,
+	sync_from: NumberFor<Block>,
+}
+
+impl<Block: BlockT, C, BE, F> Unpin for LogIndexSyncWorker<Block, C, BE, F> {}
+
+impl<Block: BlockT, C, BE, F> LogIndexSyncWorker<Block, C, BE, F> {
+	pub fn new(
+		finality_notifications: FinalityNotifications<Block>,
+		timeout: Duration,
+		client: Arc<C>,
+		substrate_backend: Arc<BE>,
+		frontier_backend: Arc<fc_db::Backend<Block>>,
+		transaction_statuses: F,
+		blocks_per_tick: usize,
+		sync_from: NumberFor<Block>,
+	) -> Self {
+		Self {
+			finality_notifications,
+			timeout,
+			inner_delay: None,
+
+			client,
+			substrate_backend,
+			frontier_backend,
+			transaction_statuses,
+
+			have_next: true,
+			blocks_per_tick,
+			sync_from,
+		}
+	}
+}
+
+impl<Block: BlockT, C, BE, F> Stream for LogIndexSyncWorker<Block, C, BE, F>
+where
+	C: HeaderBackend<Block> + Send + Sync,
+	BE: sc_client_api::Backend<Block>,
+	F: Fn(&BlockId<Block>) -> Option<Vec<TransactionStatus>>,
+{
+	type Item = ();
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<()>> {
+		let mut fire = false;
+
+		loop {
+			match Stream::poll_next(Pin::new(&mut self.finality_notifications), cx) {
+				Poll::Pending => break,
+				Poll::Ready(Some(_)) => {
+					fire = true;
+				}
+				Poll::Ready(None) => return Poll::Ready(None),
+			}
+		}
+
+		let timeout = self.timeout;
+		let inner_delay = self.inner_delay.get_or_insert_with(|| Delay::new(timeout));
+
+		match Future::poll(Pin::new(inner_delay), cx) {
+			Poll::Pending => (),
+			Poll::Ready(()) => {
+				fire = true;
+			}
+		}
+
+		if self.have_next {
+			fire = true;
+		}
+
+		if fire {
+			self.inner_delay = None;
+
+			match sync_log_index(
+				self.client.as_ref(),
+				self.substrate_backend.as_ref(),
+				self.frontier_backend.as_ref(),
+				&self.transaction_statuses,
+				self.blocks_per_tick,
+				self.sync_from,
+			) {
+				Ok(have_next) => {
+					self.have_next = have_next;
+					Poll::Ready(Some(()))
+				}
+				Err(e) => {
+					self.have_next = false;
+					debug!(target: "log-index", "Indexing logs failed with error {:?}, retrying.", e);
+					Poll::Ready(Some(()))
+				}
+			}
+		} else {
+			Poll::Pending
+		}
+	}
+}
@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{pin::Pin, sync::Arc, time::Duration};
+
+use futures::{
+	prelude::*,
+	task::{Context, Poll},
+};
+use futures_timer::Delay;
+use log::debug;
+// Substrate
+use sc_client_api::{BlockBackend, FinalityNotifications};
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, One},
+};
+// Frontier
+use fp_consensus::FindLogError;
+use fp_evm::tracing::{Trace, TracerConfig};
+use fp_rpc::DebugRuntimeApi;
+
+/// Re-executes the block with the call tracer attached. Returns `None` if the runtime
+/// doesn't support block tracing.
+pub fn trace_block<Block: BlockT, C>(
+	client: &C,
+	hash: Block::Hash,
+) -> Result<Option<Vec<fc_db::TransactionTrace>>, String>
+where
+	C: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+	C::Api: DebugRuntimeApi<Block>,
+{
+	let id = BlockId::Hash(hash);
+	let header = client
+		.header(id)
+		.map_err(|e| format!("{:?}", e))?
+		.ok_or_else(|| "Header not found".to_string())?;
+
+	// Blocks without Ethereum transactions don't need to be re-executed.
+	let has_transactions = match fp_consensus::find_log(header.digest()) {
+		Ok(log) => !log.into_hashes().transaction_hashes.is_empty(),
+		Err(FindLogError::NotFound) => false,
+		Err(FindLogError::MultipleLogs) => return Err("Multiple logs found".to_string()),
+	};
+	if !has_transactions {
+		return Ok(Some(Vec::new()));
+	}
+
+	let extrinsics = client
+		.block_body(&id)
+		.map_err(|e| format!("{:?}", e))?
+		.ok_or_else(|| "Block body not found".to_string())?;
+
+	let parent_id = BlockId::Hash(*header.parent_hash());
+	let api = client.runtime_api();
+	match api
+		.api_version::<dyn DebugRuntimeApi<Block>>(&parent_id)
+		.map_err(|e| format!("{:?}", e))?
+	{
+		Some(version) if version >= 2 => (),
+		_ => return Ok(None),
+	}
+
+	api.initialize_block(&parent_id, &header)
+		.map_err(|e| format!("{:?}", e))?;
+	let traces = api
+		.trace_block(&parent_id, extrinsics, TracerConfig::CallTracer)
+		.map_err(|e| format!("{:?}", e))?
+		.map_err(|e| format!("{:?}", e))?;
+
+	Ok(Some(
+		traces
+			.into_iter()
+			.map(|(transaction_hash, trace)| fc_db::TransactionTrace {
+				transaction_hash,
+				root: match trace {
+					Trace::CallTracer(root) => root,
+					Trace::StructLogger(_) => None,
+				},
+			})
+			.collect(),
+	))
+}
+
+/// Traces up to `limit` finalized blocks, starting from the next untraced one.
+///
+/// Only the finalized blocks are traced, so that a reorg can't leave the cursor past blocks of
+/// the new canonical chain, or fill the cache with the traces of retracted blocks.
+pub fn sync_traces<Block: BlockT, C>(
+	client: &C,
+	frontier_backend: &fc_db::Backend<Block>,
+	limit: usize,
+	sync_from: NumberFor<Block>,
+) -> Result<bool, String>
+where
+	C: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+	C::Api: DebugRuntimeApi<Block>,
+{
+	let finalized_number = client.info().finalized_number;
+	let mut number = frontier_backend
+		.trace()
+		.next_traced_block()?
+		.unwrap_or(sync_from)
+		.max(sync_from);
+	let mut synced_any = false;
+
+	for _ in 0..limit {
+		if number > finalized_number {
+			break;
+		}
+
+		let hash = client
+			.hash(number)
+			.map_err(|e| format!("{:?}", e))?
+			.ok_or_else(|| "Block hash not found".to_string())?;
+		// A block which can't be traced is skipped, the RPC re-executes it on request.
+		let traces = trace_block(client, hash).unwrap_or_else(|e| {
+			debug!(
+				target: "trace-sync",
+				"Tracing block {:?} failed with error {:?}, skipping.",
+				hash,
+				e
+			);
+			None
+		});
+
+		number += One::one();
+		frontier_backend
+			.trace()
+			.write_block_traces(hash, traces, number)?;
+		synced_any = true;
+	}
+
+	Ok(synced_any)
+}
+
+pub struct TraceSyncWorker<Block: BlockT, C> {
+	finality_notifications: FinalityNotifications<Block>,
+	timeout: Duration,
+	inner_delay: Option<Delay>,
+
+	client: Arc<C>,
+	frontier_backend: Arc<fc_db::Backend<Block>>,
+
+	have_next: bool,
+	blocks_per_tick: usize,
+	sync_from: NumberFor<Block>,
+}
+
+impl<Block: BlockT, C> Unpin for TraceSyncWorker<Block, C> {}
+
+impl<Block: BlockT, C> TraceSyncWorker<Block, C> {
+	pub fn new(
+		finality_notifications: FinalityNotifications<Block>,
+		timeout: Duration,
+		client: Arc<C>,
+		frontier_backend: Arc<fc_db::Backend<Block>>,
+		blocks_per_tick: usize,
+		sync_from: NumberFor<Block>,
+	) -> Self {
+		Self {
+			finality_notifications,
+			timeout,
+			inner_delay: None,
+
+			client,
+			frontier_backend,
+
+			have_next: true,
+			blocks_per_tick,
+			sync_from,
+		}
+	}
+}
+
+impl<Block: BlockT, C> Stream for TraceSyncWorker<Block, C>
+where
+	C: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block> + Send + Sync,
+	C::Api: DebugRuntimeApi<Block>,
+{
+	type Item = ();
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<()>> {
+		let mut fire = false;
+
+		loop {
+			match Stream::poll_next(Pin::new(&mut self.finality_notifications), cx) {
+				Poll::Pending => break,
+				Poll::Ready(Some(_)) => {
+					fire = true;
+				}
+				Poll::Ready(None) => return Poll::Ready(None),
+			}
+		}
+
+		let timeout = self.timeout;
+		let inner_delay = self.inner_delay.get_or_insert_with(|| Delay::new(timeout));
+
+		match Future::poll(Pin::new(inner_delay), cx) {
+			Poll::Pending => (),
+			Poll::Ready(()) => {
+				fire = true;
+			}
+		}
+
+		if self.have_next {
+			fire = true;
+		}
+
+		if fire {
+			self.inner_delay = None;
+
+			match sync_traces(
+				self.client.as_ref(),
+				self.frontier_backend.as_ref(),
+				self.blocks_per_tick,
+				self.sync_from,
+			) {
+				Ok(have_next) => {
+					self.have_next = have_next;
+					Poll::Ready(Some(()))
+				}
+				Err(e) => {
+					self.have_next = false;
+					debug!(target: "trace-sync", "Syncing failed with error {:?}, retrying.", e);
+					Poll::Ready(Some(()))
+				}
+			}
+		} else {
+			Poll::Pending
+		}
+	}
+}
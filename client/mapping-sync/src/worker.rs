@@ -25,7 +25,7 @@ use futures::{
 use futures_timer::Delay;
 use log::debug;
 // Substrate
-use sc_client_api::{BlockOf, ImportNotifications};
+use sc_client_api::{BlockOf, FinalityNotifications, ImportNotifications};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
@@ -40,6 +40,7 @@ pub enum SyncStrategy {
 
 pub struct MappingSyncWorker<Block: BlockT, C, B> {
 	import_notifications: ImportNotifications<Block>,
+	finality_notifications: FinalityNotifications<Block>,
 	timeout: Duration,
 	inner_delay: Option<Delay>,
 
@@ -58,6 +59,7 @@ impl<Block: BlockT, C, B> Unpin for MappingSyncWorker<Block, C, B> {}
 impl<Block: BlockT, C, B> MappingSyncWorker<Block, C, B> {
 	pub fn new(
 		import_notifications: ImportNotifications<Block>,
+		finality_notifications: FinalityNotifications<Block>,
 		timeout: Duration,
 		client: Arc<C>,
 		substrate_backend: Arc<B>,
@@ -68,6 +70,7 @@ impl<Block: BlockT, C, B> MappingSyncWorker<Block, C, B> {
 	) -> Self {
 		Self {
 			import_notifications,
+			finality_notifications,
 			timeout,
 			inner_delay: None,
 
@@ -97,8 +100,49 @@ where
 		loop {
 			match Stream::poll_next(Pin::new(&mut self.import_notifications), cx) {
 				Poll::Pending => break,
-				Poll::Ready(Some(_)) => {
+				Poll::Ready(Some(notification)) => {
 					fire = true;
+					if let Err(e) = crate::sync_import_canonicality(
+						self.substrate_backend.blockchain(),
+						self.frontier_backend.as_ref(),
+						&notification,
+					) {
+						debug!(
+							target: "mapping-sync",
+							"Tracking canonicality failed with error {:?}.",
+							e
+						);
+					}
+				}
+				Poll::Ready(None) => return Poll::Ready(None),
+			}
+		}
+
+		loop {
+			match Stream::poll_next(Pin::new(&mut self.finality_notifications), cx) {
+				Poll::Pending => break,
+				Poll::Ready(Some(notification)) => {
+					match crate::sync_finality_canonicality(
+						self.substrate_backend.blockchain(),
+						self.frontier_backend.as_ref(),
+						&notification,
+					) {
+						Ok(pruned) if pruned > 0 => {
+							debug!(
+								target: "mapping-sync",
+								"Pruned {} non-canonical block mappings.",
+								pruned
+							);
+						}
+						Ok(_) => (),
+						Err(e) => {
+							debug!(
+								target: "mapping-sync",
+								"Pruning non-canonical mappings failed with error {:?}.",
+								e
+							);
+						}
+					}
 				}
 				Poll::Ready(None) => return Poll::Ready(None),
 			}
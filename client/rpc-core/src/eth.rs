@@ -18,6 +18,8 @@
 
 //! Eth rpc interface.
 
+use std::collections::BTreeMap;
+
 use ethereum_types::{H160, H256, H64, U256, U64};
 use jsonrpsee::{core::RpcResult as Result, proc_macros::rpc};
 
@@ -145,18 +147,47 @@ pub trait EthApi {
 	#[method(name = "eth_getCode")]
 	fn code_at(&self, address: H160, number: Option<BlockNumber>) -> Result<Bytes>;
 
+	/// Returns the account and its storage slots at given time (block number), along with the
+	/// Substrate read proofs of the storage entries backing them. See `fp-account-proof` for
+	/// the layout and the verification of the proofs.
+	#[method(name = "eth_getProof")]
+	fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number: Option<BlockNumber>,
+	) -> Result<EthAccount>;
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
 
-	/// Call contract, returning the output data.
+	/// Call contract on top of the optionally overridden state, returning the output data.
 	#[method(name = "eth_call")]
-	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes>;
+	fn call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> Result<Bytes>;
 
-	/// Estimate gas needed for execution of given contract.
+	/// Estimate gas needed for execution of given contract on top of the optionally overridden state.
 	#[method(name = "eth_estimateGas")]
-	async fn estimate_gas(&self, request: CallRequest, number: Option<BlockNumber>)
-		-> Result<U256>;
+	async fn estimate_gas(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> Result<U256>;
+
+	/// Generates the access list of the call, returning it along with the gas used by the call
+	/// once it is applied.
+	#[method(name = "eth_createAccessList")]
+	fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> Result<AccessListResult>;
 
 	// ########################################################################
 	// Fee
@@ -216,6 +247,22 @@ pub trait EthApi {
 	/// Sends signed transaction, returning its hash.
 	#[method(name = "eth_sendRawTransaction")]
 	async fn send_raw_transaction(&self, bytes: Bytes) -> Result<H256>;
+
+	// ########################################################################
+	// Sign
+	// ########################################################################
+
+	/// Signs the data, prefixed as an Ethereum message, with the account.
+	#[method(name = "eth_sign")]
+	fn sign(&self, address: H160, data: Bytes) -> Result<Bytes>;
+
+	/// Same as `eth_sign`, with the parameters in reverse order.
+	#[method(name = "personal_sign")]
+	fn personal_sign(&self, data: Bytes, address: H160) -> Result<Bytes>;
+
+	/// Signs the EIP-712 typed data with the account.
+	#[method(name = "eth_signTypedData_v4")]
+	fn sign_typed_data(&self, address: H160, typed_data: TypedDataRequest) -> Result<Bytes>;
 }
 
 /// Eth filters rpc api (polling).
@@ -248,4 +295,10 @@ pub trait EthFilterApi {
 	/// Returns logs matching given filter object.
 	#[method(name = "eth_getLogs")]
 	async fn logs(&self, filter: Filter) -> Result<Vec<Log>>;
+
+	/// Returns logs matching given filter object, a page at a time: instead of failing once the
+	/// query reaches one of the node limits, returns the logs of the blocks processed so far
+	/// along with the block to resume from.
+	#[method(name = "eth_getLogsPaginated")]
+	async fn logs_paginated(&self, filter: Filter) -> Result<PaginatedLogs>;
 }
@@ -18,14 +18,20 @@
 
 pub mod types;
 
+mod debug;
 mod eth;
 mod eth_pubsub;
 mod net;
+mod trace;
+mod txpool;
 mod web3;
 
 pub use self::{
+	debug::DebugApiServer,
 	eth::{EthApiServer, EthFilterApiServer},
 	eth_pubsub::EthPubSubApiServer,
 	net::NetApiServer,
+	trace::TraceApiServer,
+	txpool::TxPoolApiServer,
 	web3::Web3ApiServer,
 };
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Trace rpc interface.
+
+use ethereum_types::H256;
+use jsonrpsee::{core::RpcResult as Result, proc_macros::rpc};
+
+use crate::types::{BlockNumber, LocalizedTrace, TraceFilter};
+
+/// Trace rpc interface.
+#[rpc(server)]
+pub trait TraceApi {
+	/// Returns the traces of all the calls executed in the block.
+	#[method(name = "trace_block")]
+	fn trace_block(&self, number: BlockNumber) -> Result<Option<Vec<LocalizedTrace>>>;
+
+	/// Returns the traces of all the calls executed by the transaction.
+	#[method(name = "trace_transaction")]
+	fn trace_transaction(&self, transaction_hash: H256) -> Result<Option<Vec<LocalizedTrace>>>;
+
+	/// Returns the traces matching the filter.
+	#[method(name = "trace_filter")]
+	fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<LocalizedTrace>>;
+}
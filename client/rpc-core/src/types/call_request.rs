@@ -16,10 +16,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use crate::types::Bytes;
 use ethereum::AccessListItem;
-use ethereum_types::{H160, U256};
-use serde::Deserialize;
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
 
 /// Call request
 #[derive(Debug, Default, PartialEq, Deserialize, Clone)]
@@ -50,3 +52,33 @@ pub struct CallRequest {
 	#[serde(rename = "type")]
 	pub transaction_type: Option<U256>,
 }
+
+/// Account state to override before executing a call
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct CallStateOverride {
+	/// Balance
+	pub balance: Option<U256>,
+	/// Nonce
+	pub nonce: Option<U256>,
+	/// Code
+	pub code: Option<Bytes>,
+	/// Full storage, replacing the account's one
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Storage slots, merged into the account's storage
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Access list generated for a call
+#[derive(Debug, PartialEq, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListResult {
+	/// Accounts and storage slots accessed by the call
+	pub access_list: Vec<AccessListItem>,
+	/// Gas used by the call with the access list applied
+	pub gas_used: U256,
+	/// Reason of the call failure, if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
@@ -0,0 +1,119 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use crate::types::Bytes;
+use ethereum_types::{H160, U256};
+use serde::{Deserialize, Serialize};
+
+/// Tracing options, the struct logger is used unless a `tracer` is set
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceParams {
+	/// Name of the tracer, only `callTracer` is supported
+	pub tracer: Option<String>,
+	/// Don't collect the stack
+	#[serde(default)]
+	pub disable_stack: bool,
+	/// Don't collect the memory
+	#[serde(default)]
+	pub disable_memory: bool,
+	/// Don't collect the storage
+	#[serde(default)]
+	pub disable_storage: bool,
+}
+
+/// Trace formatted according to the used tracer
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+	/// Struct logger trace
+	StructLogger(StructLoggerResult),
+	/// Call tracer trace
+	CallTracer(Option<CallTracerFrame>),
+}
+
+/// Struct logger trace
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerResult {
+	/// Gas used
+	pub gas: u64,
+	/// Whether the execution failed
+	pub failed: bool,
+	/// Returned data, hex without prefix
+	pub return_value: String,
+	/// Executed steps
+	pub struct_logs: Vec<StructLogEntry>,
+}
+
+/// State of the EVM before an executed step
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogEntry {
+	/// Program counter
+	pub pc: u64,
+	/// Opcode name
+	pub op: String,
+	/// Remaining gas
+	pub gas: u64,
+	/// Gas cost of the step
+	pub gas_cost: u64,
+	/// Call depth
+	pub depth: u32,
+	/// Stack words, hex without prefix
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<String>>,
+	/// Memory words, hex without prefix
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<String>>,
+	/// Accessed storage slots, hex without prefix
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<String, String>>,
+}
+
+/// Call tracer frame
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTracerFrame {
+	/// Call type, the opcode name
+	#[serde(rename = "type")]
+	pub call_type: String,
+	/// Caller
+	pub from: H160,
+	/// Callee
+	pub to: H160,
+	/// Transferred value
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<U256>,
+	/// Gas
+	pub gas: U256,
+	/// Gas used
+	pub gas_used: U256,
+	/// Input
+	pub input: Bytes,
+	/// Output
+	pub output: Bytes,
+	/// Error
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Nested calls
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallTracerFrame>,
+}
@@ -46,3 +46,13 @@ pub struct Log {
 	#[serde(default)]
 	pub removed: bool,
 }
+
+/// Page of the logs matching a filter
+#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedLogs {
+	/// Logs of the processed blocks
+	pub logs: Vec<Log>,
+	/// Block to resume the query from, `None` if the whole range was processed
+	pub next_block: Option<U256>,
+}
@@ -23,14 +23,18 @@ mod block;
 mod block_number;
 mod bytes;
 mod call_request;
+mod debug;
 mod fee;
 mod filter;
 mod index;
 mod log;
 mod receipt;
 mod sync;
+mod trace;
 mod transaction;
 mod transaction_request;
+mod txpool;
+mod typed_data;
 mod work;
 
 pub mod pubsub;
@@ -40,20 +44,27 @@ pub use self::{
 	block::{Block, BlockTransactions, Header, Rich, RichBlock, RichHeader},
 	block_number::BlockNumber,
 	bytes::Bytes,
-	call_request::CallRequest,
+	call_request::{AccessListResult, CallRequest, CallStateOverride},
+	debug::{CallTracerFrame, StructLogEntry, StructLoggerResult, TraceParams, TraceResult},
 	fee::{FeeHistory, FeeHistoryCache, FeeHistoryCacheItem, FeeHistoryCacheLimit},
 	filter::{
 		Filter, FilterAddress, FilterChanges, FilterPool, FilterPoolItem, FilterType,
 		FilteredParams, Topic, VariadicValue,
 	},
 	index::Index,
-	log::Log,
+	log::{Log, PaginatedLogs},
 	receipt::Receipt,
 	sync::{
 		ChainStatus, EthProtocolInfo, PeerCount, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 		Peers, PipProtocolInfo, SyncInfo, SyncStatus, TransactionStats,
 	},
+	trace::{
+		CallAction, CallOutput, CreateAction, CreateOutput, LocalizedTrace, SuicideAction,
+		TraceAction, TraceFilter, TraceOutput,
+	},
 	transaction::{LocalTransactionStatus, RichRawTransaction, Transaction},
 	transaction_request::{TransactionMessage, TransactionRequest},
+	txpool::{Summary, TransactionMap, TxPoolResult},
+	typed_data::{TypedData, TypedDataField, TypedDataRequest},
 	work::Work,
 };
@@ -18,13 +18,13 @@
 
 //! Pub-Sub types.
 
-use crate::types::{Filter, Log, RichHeader};
-use ethereum_types::H256;
+use crate::types::{Filter, FilterAddress, Log, RichHeader, Transaction, VariadicValue};
+use ethereum_types::{H160, H256};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Result {
 	/// New block header.
 	Header(Box<RichHeader>),
@@ -32,6 +32,8 @@ pub enum Result {
 	Log(Box<Log>),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Transaction
+	Transaction(Box<Transaction>),
 	/// SyncStatus
 	SyncState(PubSubSyncStatus),
 }
@@ -62,6 +64,7 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
+			Result::Transaction(ref transaction) => transaction.serialize(serializer),
 			Result::SyncState(ref sync) => sync.serialize(serializer),
 		}
 	}
@@ -80,6 +83,10 @@ pub enum Kind {
 	NewPendingTransactions,
 	/// Node syncing status subscription.
 	Syncing,
+	/// Finalized block headers subscription.
+	NewFinalizedHeads,
+	/// Finalized logs subscription.
+	FinalizedLogs,
 }
 
 /// Subscription kind.
@@ -89,6 +96,41 @@ pub enum Params {
 	None,
 	/// Log parameters.
 	Logs(Filter),
+	/// Pending transactions parameters.
+	PendingTransactions(PendingTransactionFilter),
+}
+
+/// Pending transactions subscription parameters, given either as an object or as the
+/// `fullTransactions` flag alone.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionFilter {
+	/// Whether to send the transactions rather than their hash.
+	#[serde(default)]
+	pub full_transactions: bool,
+	/// Senders of the transactions, any if none.
+	pub from: Option<FilterAddress>,
+	/// Receivers of the transactions, any if none.
+	pub to: Option<FilterAddress>,
+}
+
+impl PendingTransactionFilter {
+	/// Returns whether the transaction matches the sender and receiver filters.
+	pub fn matches(&self, transaction: &Transaction) -> bool {
+		fn contains(addresses: &Option<FilterAddress>, address: Option<H160>) -> bool {
+			match (addresses, address) {
+				(None, _) | (Some(VariadicValue::Null), _) => true,
+				(Some(VariadicValue::Single(expected)), Some(address)) => *expected == address,
+				(Some(VariadicValue::Multiple(expected)), Some(address)) => {
+					expected.contains(&address)
+				}
+				// Contract creations have no receiver.
+				(_, None) => false,
+			}
+		}
+		contains(&self.from, Some(transaction.from)) && contains(&self.to, transaction.to)
+	}
 }
 
 impl Default for Params {
@@ -107,9 +149,20 @@ impl<'a> Deserialize<'a> for Params {
 		if v.is_null() {
 			return Ok(Params::None);
 		}
+		if let Value::Bool(full_transactions) = v {
+			return Ok(Params::PendingTransactions(PendingTransactionFilter {
+				full_transactions,
+				..Default::default()
+			}));
+		}
 
-		from_value(v)
+		from_value(v.clone())
 			.map(Params::Logs)
+			.or_else(|err| {
+				from_value(v)
+					.map(Params::PendingTransactions)
+					.map_err(|_| err)
+			})
 			.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)))
 	}
 }
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U256};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockNumber, Bytes};
+
+/// Trace filter
+#[derive(Debug, Default, PartialEq, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+	/// From block, latest by default
+	pub from_block: Option<BlockNumber>,
+	/// To block, latest by default
+	pub to_block: Option<BlockNumber>,
+	/// Senders of the traced calls, any if empty
+	pub from_address: Option<Vec<H160>>,
+	/// Receivers of the traced calls, any if empty
+	pub to_address: Option<Vec<H160>>,
+	/// Number of matching traces to skip
+	pub after: Option<usize>,
+	/// Maximum number of traces to return
+	pub count: Option<usize>,
+}
+
+/// Parity-style trace of a call, localized in its transaction and block
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTrace {
+	/// Action
+	pub action: TraceAction,
+	/// Result, unless the call failed
+	pub result: Option<TraceOutput>,
+	/// Error, if the call failed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Indexes of the call in the call tree of the transaction
+	pub trace_address: Vec<usize>,
+	/// Number of nested calls
+	pub subtraces: usize,
+	/// Transaction position in the block
+	pub transaction_position: u32,
+	/// Transaction hash
+	pub transaction_hash: H256,
+	/// Block number
+	pub block_number: u64,
+	/// Block hash
+	pub block_hash: H256,
+	/// Trace type, `call`, `create` or `suicide`
+	#[serde(rename = "type")]
+	pub trace_type: String,
+}
+
+/// Traced action
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(untagged)]
+pub enum TraceAction {
+	/// Call
+	Call(CallAction),
+	/// Contract creation
+	Create(CreateAction),
+	/// Contract self-destruction
+	Suicide(SuicideAction),
+}
+
+/// Call action
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+	/// Caller
+	pub from: H160,
+	/// Callee
+	pub to: H160,
+	/// Transferred value
+	pub value: U256,
+	/// Gas
+	pub gas: U256,
+	/// Input
+	pub input: Bytes,
+	/// Call type, `call`, `callcode`, `delegatecall` or `staticcall`
+	pub call_type: String,
+}
+
+/// Contract creation action
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+	/// Creator
+	pub from: H160,
+	/// Transferred value
+	pub value: U256,
+	/// Gas
+	pub gas: U256,
+	/// Init code
+	pub init: Bytes,
+	/// Creation method, `create` or `create2`
+	pub creation_method: String,
+}
+
+/// Contract self-destruction action
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuicideAction {
+	/// Destroyed contract
+	pub address: H160,
+	/// Beneficiary of the contract balance
+	pub refund_address: H160,
+	/// Contract balance
+	pub balance: U256,
+}
+
+/// Traced action result
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(untagged)]
+pub enum TraceOutput {
+	/// Call result
+	Call(CallOutput),
+	/// Contract creation result
+	Create(CreateOutput),
+}
+
+/// Call result
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOutput {
+	/// Gas used
+	pub gas_used: U256,
+	/// Output
+	pub output: Bytes,
+}
+
+/// Contract creation result
+#[derive(Debug, PartialEq, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOutput {
+	/// Gas used
+	pub gas_used: U256,
+	/// Deployed code
+	pub code: Bytes,
+	/// Created contract
+	pub address: H160,
+}
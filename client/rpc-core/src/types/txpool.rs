@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use ethereum_types::{H160, U256};
+use serde::{Serialize, Serializer};
+
+/// Transactions of the pool, by sender and by nonce (in decimal)
+pub type TransactionMap<T> = BTreeMap<H160, BTreeMap<String, T>>;
+
+/// Content of the transaction pool, split between the transactions ready to be included in a
+/// block and the ones waiting for a lower nonce
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct TxPoolResult<T> {
+	/// Ready transactions
+	pub pending: T,
+	/// Future transactions
+	pub queued: T,
+}
+
+/// Short description of a pooled transaction, serialized as
+/// `<receiver>: <value> wei + <gas> gas × <gas price> wei`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+	/// Recipient, `None` for a contract creation
+	pub to: Option<H160>,
+	/// Transfered value
+	pub value: U256,
+	/// Gas
+	pub gas: U256,
+	/// Gas price, or max fee per gas of an EIP-1559 transaction
+	pub gas_price: U256,
+}
+
+impl Serialize for Summary {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let to = match self.to {
+			Some(to) => format!("{:?}", to),
+			None => "contract creation".to_string(),
+		};
+		serializer.serialize_str(&format!(
+			"{}: {} wei + {} gas × {} wei",
+			to, self.value, self.gas, self.gas_price
+		))
+	}
+}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! EIP-712 typed data
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Member of a struct type of typed data
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypedDataField {
+	/// Name
+	pub name: String,
+	/// Type, a struct type, an atomic type or an array of them
+	#[serde(rename = "type")]
+	pub field_type: String,
+}
+
+/// Typed data to sign, as defined by EIP-712
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+	/// Struct types, including `EIP712Domain`
+	pub types: BTreeMap<String, Vec<TypedDataField>>,
+	/// Type of the message
+	pub primary_type: String,
+	/// Domain separator values
+	pub domain: Value,
+	/// Message values
+	#[serde(default)]
+	pub message: Value,
+}
+
+/// Typed data given either as an object or as its JSON encoding, which some libraries send
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum TypedDataRequest {
+	/// Typed data object
+	Object(TypedData),
+	/// JSON encoding of the typed data
+	Json(String),
+}
+
+impl TypedDataRequest {
+	/// Decodes the typed data.
+	pub fn into_typed_data(self) -> Result<TypedData, serde_json::Error> {
+		match self {
+			TypedDataRequest::Object(typed_data) => Ok(typed_data),
+			TypedDataRequest::Json(json) => serde_json::from_str(&json),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn typed_data_request_deserialization() {
+		let json = r#"{
+			"types": {"EIP712Domain": [{"name": "name", "type": "string"}]},
+			"primaryType": "EIP712Domain",
+			"domain": {"name": "Test"}
+		}"#;
+		let expected = TypedData {
+			types: BTreeMap::from([(
+				"EIP712Domain".to_string(),
+				vec![TypedDataField {
+					name: "name".to_string(),
+					field_type: "string".to_string(),
+				}],
+			)]),
+			primary_type: "EIP712Domain".to_string(),
+			domain: serde_json::json!({"name": "Test"}),
+			message: Value::Null,
+		};
+
+		let object: TypedDataRequest = serde_json::from_str(json).unwrap();
+		assert_eq!(object.into_typed_data().unwrap(), expected);
+
+		let encoded: TypedDataRequest =
+			serde_json::from_value(Value::String(json.to_string())).unwrap();
+		assert_eq!(encoded.into_typed_data().unwrap(), expected);
+	}
+}
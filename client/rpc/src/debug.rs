@@ -0,0 +1,401 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::H256;
+use evm::{ExitError, ExitReason};
+use jsonrpsee::core::RpcResult as Result;
+// Substrate
+use sc_client_api::BlockBackend;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_blockchain::{BlockStatus, HeaderBackend};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT},
+};
+// Frontier
+use fc_rpc_core::{types::*, DebugApiServer};
+use fp_evm::tracing::{CallFrame, CallType, StructLog, Trace, TracerConfig};
+use fp_rpc::{DebugRuntimeApi, EthereumRuntimeRPCApi};
+
+use crate::{
+	eth::{fee_details, JSON_RPC_ERROR_DEFAULT},
+	frontier_backend_client, internal_err,
+};
+
+/// Debug API implementation.
+pub struct Debug<B: BlockT, C> {
+	client: Arc<C>,
+	backend: Arc<fc_db::Backend<B>>,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C> Debug<B, C> {
+	pub fn new(client: Arc<C>, backend: Arc<fc_db::Backend<B>>) -> Self {
+		Self {
+			client,
+			backend,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C> DebugApiServer for Debug<B, C>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + BlockBackend<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: DebugRuntimeApi<B> + EthereumRuntimeRPCApi<B>,
+{
+	fn trace_transaction(
+		&self,
+		transaction_hash: H256,
+		params: Option<TraceParams>,
+	) -> Result<TraceResult> {
+		let tracer = tracer_config(params)?;
+
+		let (ethereum_block_hash, _) = frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			transaction_hash,
+			true,
+		)?
+		.ok_or_else(|| internal_err("transaction not found"))?;
+		let id =
+			frontier_backend_client::load_hash::<B>(self.backend.as_ref(), ethereum_block_hash)?
+				.ok_or_else(|| internal_err("block not found"))?;
+
+		let header = self
+			.client
+			.header(id)
+			.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+			.ok_or_else(|| internal_err("header not found"))?;
+		let extrinsics = self
+			.client
+			.block_body(&id)
+			.map_err(|err| internal_err(format!("fetch block body failed: {:?}", err)))?
+			.ok_or_else(|| internal_err("block body not found"))?;
+
+		// The block is re-executed on top of its parent's state.
+		let parent_id = BlockId::Hash(*header.parent_hash());
+		let api = self.client.runtime_api();
+		ensure_debug_api(&api, &parent_id)?;
+
+		api.initialize_block(&parent_id, &header)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+		let trace = api
+			.trace_transaction(&parent_id, extrinsics, transaction_hash, tracer)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+		Ok(format_trace(trace))
+	}
+
+	fn trace_call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		params: Option<TraceParams>,
+	) -> Result<TraceResult> {
+		let tracer = tracer_config(params)?;
+
+		let CallRequest {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			gas,
+			value,
+			data,
+			nonce,
+			access_list,
+			..
+		} = request;
+		let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+
+		// Pending calls are traced on top of the best block.
+		let id = frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)?
+		.unwrap_or_else(|| BlockId::Hash(self.client.info().best_hash));
+		if let Ok(BlockStatus::Unknown) = self.client.status(id) {
+			return Err(crate::err(JSON_RPC_ERROR_DEFAULT, "header not found", None));
+		}
+
+		let api = self.client.runtime_api();
+		ensure_debug_api(&api, &id)?;
+
+		let gas_limit = match gas {
+			Some(gas) => gas,
+			None => {
+				api.current_block(&id)
+					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+					.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
+					.header
+					.gas_limit
+			}
+		};
+
+		let trace = api
+			.trace_call(
+				&id,
+				from.unwrap_or_default(),
+				to,
+				data.map(|d| d.0).unwrap_or_default(),
+				value.unwrap_or_default(),
+				gas_limit,
+				details.max_fee_per_gas,
+				details.max_priority_fee_per_gas,
+				nonce,
+				access_list.map(|access_list| {
+					access_list
+						.into_iter()
+						.map(|item| (item.address, item.storage_keys))
+						.collect()
+				}),
+				tracer,
+			)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+		Ok(format_trace(trace))
+	}
+}
+
+fn ensure_debug_api<B: BlockT, Api: ApiExt<B> + DebugRuntimeApi<B>>(
+	api: &Api,
+	id: &BlockId<B>,
+) -> Result<()> {
+	match api.has_api::<dyn DebugRuntimeApi<B>>(id) {
+		Ok(true) => Ok(()),
+		_ => Err(internal_err("tracing is not supported by the runtime")),
+	}
+}
+
+fn tracer_config(params: Option<TraceParams>) -> Result<TracerConfig> {
+	let params = params.unwrap_or_default();
+	match params.tracer.as_deref() {
+		None => Ok(TracerConfig::StructLogger {
+			disable_stack: params.disable_stack,
+			disable_memory: params.disable_memory,
+			disable_storage: params.disable_storage,
+		}),
+		Some("callTracer") => Ok(TracerConfig::CallTracer),
+		Some(tracer) => Err(internal_err(format!("tracer {} is not supported", tracer))),
+	}
+}
+
+fn format_trace(trace: Trace) -> TraceResult {
+	match trace {
+		Trace::StructLogger(trace) => TraceResult::StructLogger(StructLoggerResult {
+			gas: trace.gas.low_u64(),
+			failed: trace.failed,
+			return_value: hex::encode(trace.return_value),
+			struct_logs: trace
+				.struct_logs
+				.into_iter()
+				.map(format_struct_log)
+				.collect(),
+		}),
+		Trace::CallTracer(root) => TraceResult::CallTracer(root.map(format_call_frame)),
+	}
+}
+
+fn format_struct_log(struct_log: StructLog) -> StructLogEntry {
+	StructLogEntry {
+		pc: struct_log.pc,
+		op: opcode_name(struct_log.op),
+		gas: struct_log.gas,
+		gas_cost: struct_log.gas_cost,
+		depth: struct_log.depth,
+		stack: struct_log
+			.stack
+			.map(|stack| stack.iter().map(|word| format!("{:x}", word)).collect()),
+		memory: struct_log
+			.memory
+			.map(|memory| memory.chunks(32).map(hex::encode).collect()),
+		storage: struct_log.storage.map(|storage| {
+			storage
+				.iter()
+				.map(|(index, value)| (format!("{:x}", index), format!("{:x}", value)))
+				.collect()
+		}),
+	}
+}
+
+fn format_call_frame(frame: CallFrame) -> CallTracerFrame {
+	let call_type = match frame.call_type {
+		CallType::Call => "CALL",
+		CallType::CallCode => "CALLCODE",
+		CallType::DelegateCall => "DELEGATECALL",
+		CallType::StaticCall => "STATICCALL",
+		CallType::Create => "CREATE",
+		CallType::Create2 => "CREATE2",
+		CallType::SelfDestruct => "SELFDESTRUCT",
+	};
+	let error = match frame.exit_reason {
+		None | Some(ExitReason::Succeed(_)) => None,
+		Some(ExitReason::Revert(_)) => Some("execution reverted".to_string()),
+		Some(ExitReason::Error(ExitError::OutOfGas)) => Some("out of gas".to_string()),
+		Some(ExitReason::Error(err)) => Some(format!("evm error: {:?}", err)),
+		Some(ExitReason::Fatal(err)) => Some(format!("evm fatal: {:?}", err)),
+	};
+
+	CallTracerFrame {
+		call_type: call_type.to_string(),
+		from: frame.from,
+		to: frame.to,
+		value: frame.value,
+		gas: frame.gas,
+		gas_used: frame.gas_used,
+		input: Bytes(frame.input),
+		output: Bytes(frame.output),
+		error,
+		calls: frame.calls.into_iter().map(format_call_frame).collect(),
+	}
+}
+
+fn opcode_name(opcode: u8) -> String {
+	let name = match opcode {
+		0x00 => "STOP",
+		0x01 => "ADD",
+		0x02 => "MUL",
+		0x03 => "SUB",
+		0x04 => "DIV",
+		0x05 => "SDIV",
+		0x06 => "MOD",
+		0x07 => "SMOD",
+		0x08 => "ADDMOD",
+		0x09 => "MULMOD",
+		0x0a => "EXP",
+		0x0b => "SIGNEXTEND",
+		0x10 => "LT",
+		0x11 => "GT",
+		0x12 => "SLT",
+		0x13 => "SGT",
+		0x14 => "EQ",
+		0x15 => "ISZERO",
+		0x16 => "AND",
+		0x17 => "OR",
+		0x18 => "XOR",
+		0x19 => "NOT",
+		0x1a => "BYTE",
+		0x1b => "SHL",
+		0x1c => "SHR",
+		0x1d => "SAR",
+		0x20 => "SHA3",
+		0x30 => "ADDRESS",
+		0x31 => "BALANCE",
+		0x32 => "ORIGIN",
+		0x33 => "CALLER",
+		0x34 => "CALLVALUE",
+		0x35 => "CALLDATALOAD",
+		0x36 => "CALLDATASIZE",
+		0x37 => "CALLDATACOPY",
+		0x38 => "CODESIZE",
+		0x39 => "CODECOPY",
+		0x3a => "GASPRICE",
+		0x3b => "EXTCODESIZE",
+		0x3c => "EXTCODECOPY",
+		0x3d => "RETURNDATASIZE",
+		0x3e => "RETURNDATACOPY",
+		0x3f => "EXTCODEHASH",
+		0x40 => "BLOCKHASH",
+		0x41 => "COINBASE",
+		0x42 => "TIMESTAMP",
+		0x43 => "NUMBER",
+		0x44 => "DIFFICULTY",
+		0x45 => "GASLIMIT",
+		0x46 => "CHAINID",
+		0x47 => "SELFBALANCE",
+		0x48 => "BASEFEE",
+		0x50 => "POP",
+		0x51 => "MLOAD",
+		0x52 => "MSTORE",
+		0x53 => "MSTORE8",
+		0x54 => "SLOAD",
+		0x55 => "SSTORE",
+		0x56 => "JUMP",
+		0x57 => "JUMPI",
+		0x58 => "PC",
+		0x59 => "MSIZE",
+		0x5a => "GAS",
+		0x5b => "JUMPDEST",
+		0x60..=0x7f => return format!("PUSH{}", opcode - 0x5f),
+		0x80..=0x8f => return format!("DUP{}", opcode - 0x7f),
+		0x90..=0x9f => return format!("SWAP{}", opcode - 0x8f),
+		0xa0..=0xa4 => return format!("LOG{}", opcode - 0xa0),
+		0xf0 => "CREATE",
+		0xf1 => "CALL",
+		0xf2 => "CALLCODE",
+		0xf3 => "RETURN",
+		0xf4 => "DELEGATECALL",
+		0xf5 => "CREATE2",
+		0xfa => "STATICCALL",
+		0xfd => "REVERT",
+		0xfe => "INVALID",
+		0xff => "SELFDESTRUCT",
+		_ => return format!("opcode 0x{:x} not defined", opcode),
+	};
+
+	name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn opcode_names() {
+		assert_eq!(opcode_name(0x01), "ADD");
+		assert_eq!(opcode_name(0x60), "PUSH1");
+		assert_eq!(opcode_name(0x7f), "PUSH32");
+		assert_eq!(opcode_name(0x8f), "DUP16");
+		assert_eq!(opcode_name(0x90), "SWAP1");
+		assert_eq!(opcode_name(0xa4), "LOG4");
+		assert_eq!(opcode_name(0x0c), "opcode 0xc not defined");
+	}
+
+	#[test]
+	fn tracer_from_params() {
+		assert_eq!(
+			tracer_config(None).unwrap(),
+			TracerConfig::StructLogger {
+				disable_stack: false,
+				disable_memory: false,
+				disable_storage: false,
+			}
+		);
+		assert_eq!(
+			tracer_config(Some(TraceParams {
+				tracer: Some("callTracer".to_string()),
+				..Default::default()
+			}))
+			.unwrap(),
+			TracerConfig::CallTracer
+		);
+		assert!(tracer_config(Some(TraceParams {
+			tracer: Some("prestateTracer".to_string()),
+			..Default::default()
+		}))
+		.is_err());
+	}
+}
@@ -0,0 +1,484 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! EIP-712 hashing of typed data.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ethereum_types::{H256, U256};
+use serde_json::Value;
+// Substrate
+use sp_core::hashing::keccak_256;
+// Frontier
+use fc_rpc_core::types::{TypedData, TypedDataField};
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Returns the hash to sign of the typed data,
+/// `keccak256("\x19\x01" ‖ hashStruct(domain) ‖ hashStruct(message))`.
+///
+/// The types and the values are checked against each other: all the referenced types must be
+/// defined, and the values must hold exactly the fields of their type, each in the range of its
+/// type.
+pub fn hash_typed_data(typed_data: &TypedData) -> Result<H256, String> {
+	let encoder = Encoder::new(&typed_data.types)?;
+
+	let mut data = vec![0x19, 0x01];
+	data.extend_from_slice(&encoder.hash_struct(DOMAIN_TYPE, &typed_data.domain)?);
+	// The message is omitted when only the domain is signed.
+	if typed_data.primary_type != DOMAIN_TYPE {
+		data.extend_from_slice(
+			&encoder.hash_struct(&typed_data.primary_type, &typed_data.message)?,
+		);
+	}
+	Ok(H256(keccak_256(&data)))
+}
+
+/// Atomic type of a member.
+enum AtomicType {
+	Address,
+	Bool,
+	String,
+	Bytes,
+	FixedBytes(usize),
+	Integer { bits: usize, signed: bool },
+}
+
+impl AtomicType {
+	fn parse(name: &str) -> Option<Self> {
+		match name {
+			"address" => return Some(AtomicType::Address),
+			"bool" => return Some(AtomicType::Bool),
+			"string" => return Some(AtomicType::String),
+			"bytes" => return Some(AtomicType::Bytes),
+			_ => {}
+		}
+		let size = |digits: &str| -> Option<usize> {
+			// Sizes are written without leading zeros.
+			if digits.starts_with('0') {
+				return None;
+			}
+			digits.parse().ok()
+		};
+		if let Some(length) = name.strip_prefix("bytes").and_then(size) {
+			return if (1..=32).contains(&length) {
+				Some(AtomicType::FixedBytes(length))
+			} else {
+				None
+			};
+		}
+		let (signed, bits) = match name.strip_prefix("uint") {
+			Some(bits) => (false, bits),
+			None => (true, name.strip_prefix("int")?),
+		};
+		size(bits)
+			.filter(|bits| bits % 8 == 0 && (8..=256).contains(bits))
+			.map(|bits| AtomicType::Integer { bits, signed })
+	}
+}
+
+/// Splits an array type into the type of its items and its length, if fixed. Returns `None`
+/// if the type is not an array.
+fn split_array(name: &str) -> Result<Option<(&str, Option<usize>)>, String> {
+	let inner = match name.strip_suffix(']') {
+		Some(inner) => inner,
+		None => return Ok(None),
+	};
+	let open = inner
+		.rfind('[')
+		.ok_or_else(|| format!("invalid type `{}`", name))?;
+	let length = match &inner[open + 1..] {
+		"" => None,
+		length => Some(
+			length
+				.parse()
+				.map_err(|_| format!("invalid array length in `{}`", name))?,
+		),
+	};
+	Ok(Some((&inner[..open], length)))
+}
+
+/// Returns the type of the items of a (possibly nested) array type, the type itself otherwise.
+fn base_type(mut name: &str) -> Result<&str, String> {
+	while let Some((item, _)) = split_array(name)? {
+		name = item;
+	}
+	Ok(name)
+}
+
+fn is_identifier(name: &str) -> bool {
+	let mut chars = name.chars();
+	matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+		&& chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+struct Encoder<'a> {
+	types: &'a BTreeMap<String, Vec<TypedDataField>>,
+}
+
+impl<'a> Encoder<'a> {
+	/// Checks that the struct types are well formed and only reference defined types.
+	fn new(types: &'a BTreeMap<String, Vec<TypedDataField>>) -> Result<Self, String> {
+		if !types.contains_key(DOMAIN_TYPE) {
+			return Err(format!("missing `{}` type", DOMAIN_TYPE));
+		}
+		for (name, fields) in types {
+			if !is_identifier(name) || AtomicType::parse(name).is_some() {
+				return Err(format!("invalid type name `{}`", name));
+			}
+			let mut names = BTreeSet::new();
+			for field in fields {
+				if !is_identifier(&field.name) {
+					return Err(format!(
+						"invalid member name `{}` in `{}`",
+						field.name, name
+					));
+				}
+				if !names.insert(&field.name) {
+					return Err(format!("duplicate member `{}` in `{}`", field.name, name));
+				}
+				let base = base_type(&field.field_type)?;
+				if !types.contains_key(base) && AtomicType::parse(base).is_none() {
+					return Err(format!("unknown type `{}`", field.field_type));
+				}
+			}
+		}
+		Ok(Self { types })
+	}
+
+	fn fields(&self, name: &str) -> Result<&'a [TypedDataField], String> {
+		self.types
+			.get(name)
+			.map(|fields| &fields[..])
+			.ok_or_else(|| format!("unknown type `{}`", name))
+	}
+
+	/// Collects the struct types referenced by the type, directly or not.
+	fn dependencies(&self, name: &str, found: &mut BTreeSet<&'a str>) -> Result<(), String> {
+		for field in self.fields(name)? {
+			let base = base_type(&field.field_type)?;
+			if let Some((dependency, _)) = self.types.get_key_value(base) {
+				if found.insert(dependency.as_str()) {
+					self.dependencies(dependency, found)?;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Returns the type followed by the types it references, sorted by name, for ex.
+	/// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+	fn encode_type(&self, name: &str) -> Result<String, String> {
+		let mut dependencies = BTreeSet::new();
+		self.dependencies(name, &mut dependencies)?;
+		dependencies.remove(name);
+
+		let mut encoded = String::new();
+		for name in std::iter::once(name).chain(dependencies) {
+			let members = self
+				.fields(name)?
+				.iter()
+				.map(|field| format!("{} {}", field.field_type, field.name))
+				.collect::<Vec<_>>();
+			encoded.push_str(&format!("{}({})", name, members.join(",")));
+		}
+		Ok(encoded)
+	}
+
+	fn hash_struct(&self, name: &str, value: &Value) -> Result<[u8; 32], String> {
+		let fields = self.fields(name)?;
+		let values = value
+			.as_object()
+			.ok_or_else(|| format!("expected an object for `{}`", name))?;
+		if let Some(unknown) = values
+			.keys()
+			.find(|key| !fields.iter().any(|field| &&field.name == key))
+		{
+			return Err(format!("unknown member `{}` in `{}`", unknown, name));
+		}
+
+		let mut encoded = keccak_256(self.encode_type(name)?.as_bytes()).to_vec();
+		for field in fields {
+			let value = values
+				.get(&field.name)
+				.ok_or_else(|| format!("missing member `{}` in `{}`", field.name, name))?;
+			encoded.extend_from_slice(
+				&self
+					.encode_value(&field.field_type, value)
+					.map_err(|err| format!("{}.{}: {}", name, field.name, err))?,
+			);
+		}
+		Ok(keccak_256(&encoded))
+	}
+
+	/// Encodes the value of a member in 32 bytes.
+	fn encode_value(&self, field_type: &str, value: &Value) -> Result<[u8; 32], String> {
+		if let Some((item_type, length)) = split_array(field_type)? {
+			let items = value.as_array().ok_or("expected an array")?;
+			if let Some(length) = length {
+				if items.len() != length {
+					return Err(format!("expected {} items, got {}", length, items.len()));
+				}
+			}
+			let mut encoded = Vec::with_capacity(32 * items.len());
+			for item in items {
+				encoded.extend_from_slice(&self.encode_value(item_type, item)?);
+			}
+			return Ok(keccak_256(&encoded));
+		}
+		if self.types.contains_key(field_type) {
+			return self.hash_struct(field_type, value);
+		}
+
+		let atomic_type = AtomicType::parse(field_type)
+			.ok_or_else(|| format!("unknown type `{}`", field_type))?;
+		let mut encoded = [0u8; 32];
+		match atomic_type {
+			AtomicType::Address => {
+				let address = parse_bytes(value)?;
+				if address.len() != 20 {
+					return Err("expected a 20 bytes address".into());
+				}
+				encoded[12..].copy_from_slice(&address);
+			}
+			AtomicType::Bool => {
+				encoded[31] = value.as_bool().ok_or("expected a boolean")? as u8;
+			}
+			AtomicType::String => {
+				encoded = keccak_256(value.as_str().ok_or("expected a string")?.as_bytes());
+			}
+			AtomicType::Bytes => {
+				encoded = keccak_256(&parse_bytes(value)?);
+			}
+			AtomicType::FixedBytes(length) => {
+				let bytes = parse_bytes(value)?;
+				if bytes.len() != length {
+					return Err(format!("expected {} bytes", length));
+				}
+				encoded[..length].copy_from_slice(&bytes);
+			}
+			AtomicType::Integer { bits, signed } => {
+				parse_integer(value, bits, signed)?.to_big_endian(&mut encoded);
+			}
+		}
+		Ok(encoded)
+	}
+}
+
+/// Parses a `0x`-prefixed hex string.
+fn parse_bytes(value: &Value) -> Result<Vec<u8>, String> {
+	value
+		.as_str()
+		.and_then(|value| value.strip_prefix("0x"))
+		.and_then(|value| hex::decode(value).ok())
+		.ok_or_else(|| "expected a 0x-prefixed hex string".into())
+}
+
+/// Parses a number, or a decimal or `0x`-prefixed hex string, checking it fits in the integer
+/// type. Negative numbers are returned in two's complement.
+fn parse_integer(value: &Value, bits: usize, signed: bool) -> Result<U256, String> {
+	let (negative, magnitude) = match value {
+		Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+			(Some(number), _) => (false, U256::from(number)),
+			(None, Some(number)) => (true, U256::from(number.unsigned_abs())),
+			_ => return Err("expected an integer".into()),
+		},
+		Value::String(number) => {
+			let (negative, digits) = match number.strip_prefix('-') {
+				Some(digits) => (true, digits),
+				None => (false, &number[..]),
+			};
+			let magnitude = match digits.strip_prefix("0x") {
+				Some(digits) => U256::from_str_radix(digits, 16).ok(),
+				None => U256::from_dec_str(digits).ok(),
+			}
+			.ok_or("expected an integer")?;
+			(negative, magnitude)
+		}
+		_ => return Err("expected an integer".into()),
+	};
+
+	let in_range = if !signed {
+		(!negative || magnitude.is_zero()) && (bits == 256 || (magnitude >> bits).is_zero())
+	} else {
+		let limit = U256::one() << (bits - 1);
+		if negative {
+			magnitude <= limit
+		} else {
+			magnitude < limit
+		}
+	};
+	if !in_range {
+		return Err(format!(
+			"out of range of {}int{}",
+			if signed { "" } else { "u" },
+			bits
+		));
+	}
+
+	Ok(if negative {
+		(!magnitude).overflowing_add(U256::one()).0
+	} else {
+		magnitude
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn typed_data(value: Value) -> TypedData {
+		serde_json::from_value(value).unwrap()
+	}
+
+	fn word(value: u64) -> [u8; 32] {
+		let mut word = [0u8; 32];
+		U256::from(value).to_big_endian(&mut word);
+		word
+	}
+
+	// Example of the EIP.
+	fn mail() -> Value {
+		json!({
+			"types": {
+				"EIP712Domain": [
+					{ "name": "name", "type": "string" },
+					{ "name": "version", "type": "string" },
+					{ "name": "chainId", "type": "uint256" },
+					{ "name": "verifyingContract", "type": "address" }
+				],
+				"Person": [
+					{ "name": "name", "type": "string" },
+					{ "name": "wallet", "type": "address" }
+				],
+				"Mail": [
+					{ "name": "from", "type": "Person" },
+					{ "name": "to", "type": "Person" },
+					{ "name": "contents", "type": "string" }
+				]
+			},
+			"primaryType": "Mail",
+			"domain": {
+				"name": "Ether Mail",
+				"version": "1",
+				"chainId": 1,
+				"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+			},
+			"message": {
+				"from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+				"to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+				"contents": "Hello, Bob!"
+			}
+		})
+	}
+
+	#[test]
+	fn hashes_the_eip_example() {
+		let mail = typed_data(mail());
+		let encoder = Encoder::new(&mail.types).unwrap();
+
+		assert_eq!(
+			encoder.encode_type("Mail").unwrap(),
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+		);
+		assert_eq!(
+			H256(encoder.hash_struct(DOMAIN_TYPE, &mail.domain).unwrap()),
+			"0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+				.parse()
+				.unwrap()
+		);
+		assert_eq!(
+			hash_typed_data(&mail).unwrap(),
+			"0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+				.parse()
+				.unwrap()
+		);
+	}
+
+	#[test]
+	fn encodes_atomic_values() {
+		let types = BTreeMap::from([(DOMAIN_TYPE.to_string(), vec![])]);
+		let encoder = Encoder::new(&types).unwrap();
+
+		assert_eq!(
+			encoder.encode_value("int8", &json!(-1)).unwrap(),
+			[0xff; 32]
+		);
+		assert_eq!(
+			encoder.encode_value("uint16", &json!("0x1234")).unwrap(),
+			word(0x1234)
+		);
+		assert_eq!(
+			encoder.encode_value("bytes2", &json!("0x1234")).unwrap()[..3],
+			[0x12, 0x34, 0x00]
+		);
+		assert_eq!(
+			encoder.encode_value("uint8[2]", &json!([1, 2])).unwrap(),
+			keccak_256(&[word(1), word(2)].concat())
+		);
+	}
+
+	#[test]
+	fn rejects_invalid_values() {
+		let types = BTreeMap::from([(DOMAIN_TYPE.to_string(), vec![])]);
+		let encoder = Encoder::new(&types).unwrap();
+
+		assert!(encoder.encode_value("uint8", &json!(256)).is_err());
+		assert!(encoder.encode_value("uint8", &json!(-1)).is_err());
+		assert!(encoder.encode_value("int8", &json!(-129)).is_err());
+		assert!(encoder.encode_value("bytes2", &json!("0x12")).is_err());
+		assert!(encoder.encode_value("address", &json!("0x12")).is_err());
+		assert!(encoder.encode_value("uint8[2]", &json!([1])).is_err());
+		assert!(encoder.encode_value("bool", &json!("true")).is_err());
+	}
+
+	#[test]
+	fn rejects_invalid_types_and_messages() {
+		let mut unknown_type = mail();
+		unknown_type["types"]["Mail"][2]["type"] = json!("Text");
+		assert_eq!(
+			hash_typed_data(&typed_data(unknown_type)),
+			Err("unknown type `Text`".to_string())
+		);
+
+		let mut missing_domain = mail();
+		missing_domain["types"]
+			.as_object_mut()
+			.unwrap()
+			.remove(DOMAIN_TYPE);
+		assert!(hash_typed_data(&typed_data(missing_domain)).is_err());
+
+		let mut unknown_member = mail();
+		unknown_member["message"]["cc"] = json!("Alice");
+		assert_eq!(
+			hash_typed_data(&typed_data(unknown_member)),
+			Err("unknown member `cc` in `Mail`".to_string())
+		);
+
+		let mut missing_member = mail();
+		missing_member["message"]["from"]
+			.as_object_mut()
+			.unwrap()
+			.remove("wallet");
+		assert_eq!(
+			hash_typed_data(&typed_data(missing_member)),
+			Err("Mail.from: missing member `wallet` in `Person`".to_string())
+		);
+	}
+}
@@ -16,11 +16,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
-use ethereum_types::{H256, U256};
+use ethereum::AccessListItem;
+use ethereum_types::{H160, H256, U256};
 use evm::{ExitError, ExitReason};
-use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::{core::RpcResult as Result, types::error::CallError};
 // Substrate
 use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sc_network::ExHashT;
@@ -35,6 +36,7 @@ use sp_runtime::{
 };
 // Frontier
 use fc_rpc_core::types::*;
+use fp_evm::StateOverride;
 use fp_rpc::EthereumRuntimeRPCApi;
 
 use crate::{
@@ -74,7 +76,12 @@ where
 	A: ChainApi<Block = B> + 'static,
 	EGA: EstimateGasAdapter,
 {
-	pub fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes> {
+	pub fn call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> Result<Bytes> {
 		let CallRequest {
 			from,
 			to,
@@ -89,6 +96,8 @@ where
 			..
 		} = request;
 
+		let state_overrides = runtime_state_overrides(state_overrides)?;
+
 		let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) = {
 			let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
 			(
@@ -123,6 +132,12 @@ where
 				return Err(internal_err("failed to retrieve Runtime Api version"));
 			};
 
+		if api_version < 5 && !state_overrides.is_empty() {
+			return Err(internal_err(
+				"state override is not supported by the runtime",
+			));
+		}
+
 		let block = if api_version > 1 {
 			api.current_block(&id)
 				.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
@@ -199,6 +214,33 @@ where
 				} else if api_version == 4 {
 					// Post-london + access list support
 					let access_list = access_list.unwrap_or_default();
+					#[allow(deprecated)]
+					let info = api.call_before_version_5(
+						&id,
+						from.unwrap_or_default(),
+						to,
+						data,
+						value.unwrap_or_default(),
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						false,
+						Some(
+							access_list
+								.into_iter()
+								.map(|item| (item.address, item.storage_keys))
+								.collect(),
+						),
+					)
+					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+					error_on_execution_failure(&info.exit_reason, &info.value)?;
+					Ok(Bytes(info.value))
+				} else if api_version >= 5 {
+					// Post-london + access list + state override support
+					let access_list = access_list.unwrap_or_default();
 					let info = api
 						.call(
 							&id,
@@ -217,6 +259,7 @@ where
 									.map(|item| (item.address, item.storage_keys))
 									.collect(),
 							),
+							state_overrides,
 						)
 						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
 						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -276,6 +319,36 @@ where
 				} else if api_version == 4 {
 					// Post-london + access list support
 					let access_list = access_list.unwrap_or_default();
+					#[allow(deprecated)]
+					let info = api.create_before_version_5(
+						&id,
+						from.unwrap_or_default(),
+						data,
+						value.unwrap_or_default(),
+						gas_limit,
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						false,
+						Some(
+							access_list
+								.into_iter()
+								.map(|item| (item.address, item.storage_keys))
+								.collect(),
+						),
+					)
+					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+					error_on_execution_failure(&info.exit_reason, &[])?;
+
+					let code = api
+						.account_code_at(&id, info.value)
+						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+					Ok(Bytes(code))
+				} else if api_version >= 5 {
+					// Post-london + access list + state override support
+					let access_list = access_list.unwrap_or_default();
 					let info = api
 						.create(
 							&id,
@@ -293,6 +366,7 @@ where
 									.map(|item| (item.address, item.storage_keys))
 									.collect(),
 							),
+							state_overrides,
 						)
 						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
 						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -310,15 +384,46 @@ where
 		}
 	}
 
-	pub async fn estimate_gas(&self, request: CallRequest, _: Option<BlockNumber>) -> Result<U256> {
+	pub async fn estimate_gas(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> Result<U256> {
 		let client = Arc::clone(&self.client);
 		let block_data_cache = Arc::clone(&self.block_data_cache);
+		let state_overrides = runtime_state_overrides(state_overrides)?;
 
 		// Define the lower bound of estimate
 		const MIN_GAS_PER_TX: U256 = U256([21_000, 0, 0, 0]);
 
-		// Get best hash (TODO missing support for estimating gas historically)
-		let best_hash = client.info().best_hash;
+		// Resolve the block to estimate against, falling back to the pending one.
+		let (substrate_hash, pending) = match frontier_backend_client::native_block_id::<B, C>(
+			client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)? {
+			Some(id) => {
+				if let Ok(BlockStatus::Unknown) = client.status(id) {
+					return Err(crate::err(JSON_RPC_ERROR_DEFAULT, "header not found", None));
+				}
+				let hash = client
+					.expect_block_hash_from_id(&id)
+					.map_err(|_| crate::err(JSON_RPC_ERROR_DEFAULT, "header not found", None))?;
+				(hash, false)
+			}
+			// Not mapped in the db, assume pending.
+			None => (client.info().best_hash, true),
+		};
+
+		// Pending estimations are executed over the best block's state with the ready queue applied.
+		let runtime_api = || {
+			if pending {
+				pending_runtime_api(client.as_ref(), self.graph.as_ref())
+			} else {
+				Ok(client.runtime_api())
+			}
+		};
 
 		// Adapt request for gas estimation.
 		let request = EGA::adapt_request(request);
@@ -330,10 +435,16 @@ where
 		};
 		if is_simple_transfer {
 			if let Some(to) = request.to {
-				let to_code = client
-					.runtime_api()
-					.account_code_at(&BlockId::Hash(best_hash), to)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+				let overridden_code = state_overrides
+					.iter()
+					.find(|state_override| state_override.address == to)
+					.and_then(|state_override| state_override.code.clone());
+				let to_code = match overridden_code {
+					Some(code) => code,
+					None => runtime_api()?
+						.account_code_at(&BlockId::Hash(substrate_hash), to)
+						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?,
+				};
 				if to_code.is_empty() {
 					return Ok(MIN_GAS_PER_TX);
 				}
@@ -354,7 +465,6 @@ where
 		};
 
 		let block_gas_limit = {
-			let substrate_hash = client.info().best_hash;
 			let id = BlockId::Hash(substrate_hash);
 			let schema = frontier_backend_client::onchain_storage_schema::<B, C, BE>(&client, id);
 			let block = block_data_cache.current_block(schema, substrate_hash).await;
@@ -381,16 +491,24 @@ where
 			None => max_gas_limit,
 		};
 
-		let api = client.runtime_api();
+		let api = runtime_api()?;
 
 		// Recap the highest gas allowance with account's balance.
 		if let Some(from) = request.from {
 			let gas_price = gas_price.unwrap_or_default();
 			if gas_price > U256::zero() {
-				let balance = api
-					.account_basic(&BlockId::Hash(best_hash), from)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-					.balance;
+				let overridden_balance = state_overrides
+					.iter()
+					.find(|state_override| state_override.address == from)
+					.and_then(|state_override| state_override.balance);
+				let balance = match overridden_balance {
+					Some(balance) => balance,
+					None => {
+						api.account_basic(&BlockId::Hash(substrate_hash), from)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.balance
+					}
+				};
 				let mut available = balance;
 				if let Some(value) = request.value {
 					if value > available {
@@ -413,6 +531,8 @@ where
 			}
 		}
 
+		let has_state_overrides = !state_overrides.is_empty();
+
 		struct ExecutableResult {
 			data: Vec<u8>,
 			exit_reason: ExitReason,
@@ -424,12 +544,11 @@ where
 		// A new ApiRef instance needs to be used per execution to avoid the overlayed state to affect
 		// the estimation result of subsequent calls.
 		//
-		// Note that this would have a performance penalty if we introduce gas estimation for past
-		// blocks - and thus, past runtime versions. Substrate has a default `runtime_cache_size` of
-		// 2 slots LRU-style, meaning if users were to access multiple runtime versions in a short period
-		// of time, the RPC response time would degrade a lot, as the VersionedRuntime needs to be compiled.
-		//
-		// To solve that, and if we introduce historical gas estimation, we'd need to increase that default.
+		// Note that estimating against past blocks - and thus, past runtime versions - has a performance
+		// penalty. Substrate has a default `runtime_cache_size` of 2 slots LRU-style, meaning if users
+		// were to access multiple runtime versions in a short period of time, the RPC response time
+		// would degrade a lot, as the VersionedRuntime needs to be compiled. Node operators serving
+		// historical estimations should increase that default.
 		#[rustfmt::skip]
 			let executable = move |
 				request, gas_limit, api_version, api: sp_api::ApiRef<'_, C::Api>, estimate_mode
@@ -456,7 +575,7 @@ where
 							// Legacy pre-london
 							#[allow(deprecated)]
 							api.call_before_version_2(
-								&BlockId::Hash(best_hash),
+								&BlockId::Hash(substrate_hash),
 								from.unwrap_or_default(),
 								to,
 								data,
@@ -472,7 +591,7 @@ where
 							// Post-london
 							#[allow(deprecated)]
 							api.call_before_version_4(
-								&BlockId::Hash(best_hash),
+								&BlockId::Hash(substrate_hash),
 								from.unwrap_or_default(),
 								to,
 								data,
@@ -485,11 +604,35 @@ where
 							)
 							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
 							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						} else {
+						} else if api_version == 4 {
 							// Post-london + access list support
 							let access_list = access_list.unwrap_or_default();
+							#[allow(deprecated)]
+							api.call_before_version_5(
+								&BlockId::Hash(substrate_hash),
+								from.unwrap_or_default(),
+								to,
+								data,
+								value.unwrap_or_default(),
+								gas_limit,
+								max_fee_per_gas,
+								max_priority_fee_per_gas,
+								nonce,
+								estimate_mode,
+								Some(
+									access_list
+										.into_iter()
+										.map(|item| (item.address, item.storage_keys))
+										.collect(),
+								),
+							)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
+						} else {
+							// Post-london + access list + state override support
+							let access_list = access_list.unwrap_or_default();
 							api.call(
-								&BlockId::Hash(best_hash),
+								&BlockId::Hash(substrate_hash),
 								from.unwrap_or_default(),
 								to,
 								data,
@@ -505,6 +648,7 @@ where
 										.map(|item| (item.address, item.storage_keys))
 										.collect(),
 								),
+								state_overrides.clone(),
 							)
 							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
 							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
@@ -517,7 +661,7 @@ where
 							// Legacy pre-london
 							#[allow(deprecated)]
 							api.create_before_version_2(
-								&BlockId::Hash(best_hash),
+								&BlockId::Hash(substrate_hash),
 								from.unwrap_or_default(),
 								data,
 								value.unwrap_or_default(),
@@ -532,7 +676,7 @@ where
 							// Post-london
 							#[allow(deprecated)]
 							api.create_before_version_4(
-								&BlockId::Hash(best_hash),
+								&BlockId::Hash(substrate_hash),
 								from.unwrap_or_default(),
 								data,
 								value.unwrap_or_default(),
@@ -544,11 +688,34 @@ where
 							)
 							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
 							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
-						} else {
+						} else if api_version == 4 {
 							// Post-london + access list support
 							let access_list = access_list.unwrap_or_default();
+							#[allow(deprecated)]
+							api.create_before_version_5(
+								&BlockId::Hash(substrate_hash),
+								from.unwrap_or_default(),
+								data,
+								value.unwrap_or_default(),
+								gas_limit,
+								max_fee_per_gas,
+								max_priority_fee_per_gas,
+								nonce,
+								estimate_mode,
+								Some(
+									access_list
+										.into_iter()
+										.map(|item| (item.address, item.storage_keys))
+										.collect(),
+								),
+							)
+							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
+						} else {
+							// Post-london + access list + state override support
+							let access_list = access_list.unwrap_or_default();
 							api.create(
-								&BlockId::Hash(best_hash),
+								&BlockId::Hash(substrate_hash),
 								from.unwrap_or_default(),
 								data,
 								value.unwrap_or_default(),
@@ -563,6 +730,7 @@ where
 										.map(|item| (item.address, item.storage_keys))
 										.collect(),
 								),
+								state_overrides.clone(),
 							)
 							.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
 							.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?
@@ -578,15 +746,19 @@ where
 				})
 			};
 		let api_version = if let Ok(Some(api_version)) =
-			client
-				.runtime_api()
-				.api_version::<dyn EthereumRuntimeRPCApi<B>>(&BlockId::Hash(best_hash))
+			api.api_version::<dyn EthereumRuntimeRPCApi<B>>(&BlockId::Hash(substrate_hash))
 		{
 			api_version
 		} else {
 			return Err(internal_err("failed to retrieve Runtime Api version"));
 		};
 
+		if api_version < 5 && has_state_overrides {
+			return Err(internal_err(
+				"state override is not supported by the runtime",
+			));
+		}
+
 		// Verify that the transaction succeed with highest capacity
 		let cap = highest;
 		let estimate_mode = !cfg!(feature = "rpc_binary_search_estimate");
@@ -598,7 +770,7 @@ where
 			request.clone(),
 			highest,
 			api_version,
-			client.runtime_api(),
+			runtime_api()?,
 			estimate_mode,
 		)?;
 		match exit_reason {
@@ -625,7 +797,7 @@ where
 						request.clone(),
 						max_gas_limit,
 						api_version,
-						client.runtime_api(),
+						runtime_api()?,
 						estimate_mode,
 					)?;
 					match exit_reason {
@@ -671,7 +843,7 @@ where
 					request.clone(),
 					mid,
 					api_version,
-					client.runtime_api(),
+					runtime_api()?,
 					estimate_mode,
 				)?;
 				match exit_reason {
@@ -697,6 +869,192 @@ where
 			Ok(highest)
 		}
 	}
+
+	pub fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> Result<AccessListResult> {
+		// Upper bound of the executions needed for the access list to settle.
+		const MAX_EXECUTIONS: usize = 10;
+
+		let CallRequest {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			gas,
+			value,
+			data,
+			nonce,
+			access_list,
+			..
+		} = request;
+
+		let (_, max_fee_per_gas, max_priority_fee_per_gas) = {
+			let details = fee_details(gas_price, max_fee_per_gas, max_priority_fee_per_gas)?;
+			(
+				details.gas_price,
+				details.max_fee_per_gas,
+				details.max_priority_fee_per_gas,
+			)
+		};
+
+		let (id, pending) = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)? {
+			Some(id) => (id, false),
+			// Not mapped in the db, assume pending.
+			None => (BlockId::Hash(self.client.info().best_hash), true),
+		};
+
+		if let Ok(BlockStatus::Unknown) = self.client.status(id) {
+			return Err(crate::err(JSON_RPC_ERROR_DEFAULT, "header not found", None));
+		}
+
+		// Each execution needs a new ApiRef instance, not to be affected by the overlayed state
+		// of the previous one.
+		let runtime_api = || {
+			if pending {
+				pending_runtime_api(self.client.as_ref(), self.graph.as_ref())
+			} else {
+				Ok(self.client.runtime_api())
+			}
+		};
+
+		let api = runtime_api()?;
+		let api_version =
+			if let Ok(Some(api_version)) = api.api_version::<dyn EthereumRuntimeRPCApi<B>>(&id) {
+				api_version
+			} else {
+				return Err(internal_err("failed to retrieve Runtime Api version"));
+			};
+		if api_version < 7 {
+			return Err(internal_err(
+				"eth_createAccessList is not supported by the runtime",
+			));
+		}
+
+		let block_gas_limit = api
+			.current_block(&id)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.ok_or_else(|| internal_err("block unavailable, cannot query gas limit"))?
+			.header
+			.gas_limit;
+		let max_gas_limit = block_gas_limit * self.execute_gas_limit_multiplier;
+
+		// use given gas limit or query current block's limit
+		let gas_limit = match gas {
+			Some(amount) => {
+				if amount > max_gas_limit {
+					return Err(internal_err(format!(
+						"provided gas limit is too high (can be up to {}x the block gas limit)",
+						self.execute_gas_limit_multiplier
+					)));
+				}
+				amount
+			}
+			None => max_gas_limit,
+		};
+
+		let data = data.map(|d| d.0).unwrap_or_default();
+		let mut access_list: Vec<(H160, Vec<H256>)> = access_list
+			.unwrap_or_default()
+			.into_iter()
+			.map(|item| (item.address, item.storage_keys))
+			.collect();
+
+		// Warming up the accounts and slots of the list changes the gas left to the execution,
+		// which may then access other ones: execute until it accesses the ones of the list.
+		let mut api = Some(api);
+		for _ in 0..MAX_EXECUTIONS {
+			let info = match api.take() {
+				Some(api) => api,
+				None => runtime_api()?,
+			}
+			.create_access_list(
+				&id,
+				from.unwrap_or_default(),
+				to,
+				data.clone(),
+				value.unwrap_or_default(),
+				gas_limit,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				Some(access_list.clone()),
+			)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+			if info.access_list == access_list {
+				let error = error_on_execution_failure(&info.exit_reason, &info.value)
+					.err()
+					.map(|err| match err {
+						jsonrpsee::core::Error::Call(CallError::Custom(err)) => {
+							err.message().to_string()
+						}
+						err => err.to_string(),
+					});
+				return Ok(AccessListResult {
+					access_list: info
+						.access_list
+						.into_iter()
+						.map(|(address, storage_keys)| AccessListItem {
+							address,
+							storage_keys,
+						})
+						.collect(),
+					gas_used: info.used_gas,
+					error,
+				});
+			}
+			access_list = info.access_list;
+		}
+
+		Err(internal_err(format!(
+			"access list did not settle after {} executions",
+			MAX_EXECUTIONS
+		)))
+	}
+}
+
+/// Converts the RPC state override set into the runtime one.
+fn runtime_state_overrides(
+	state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+) -> Result<Vec<StateOverride>> {
+	state_overrides
+		.unwrap_or_default()
+		.into_iter()
+		.map(|(address, state_override)| {
+			let CallStateOverride {
+				balance,
+				nonce,
+				code,
+				state,
+				state_diff,
+			} = state_override;
+
+			if state.is_some() && state_diff.is_some() {
+				return Err(internal_err(format!(
+					"account {:?} has both 'state' and 'stateDiff'",
+					address
+				)));
+			}
+
+			Ok(StateOverride {
+				address,
+				balance,
+				nonce,
+				code: code.map(|code| code.into_vec()),
+				state: state.map(|state| state.into_iter().collect()),
+				state_diff: state_diff.map(|state_diff| state_diff.into_iter().collect()),
+			})
+		})
+		.collect()
 }
 
 pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> Result<()> {
@@ -740,13 +1098,13 @@ pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> Result<()
 	}
 }
 
-struct FeeDetails {
-	gas_price: Option<U256>,
-	max_fee_per_gas: Option<U256>,
-	max_priority_fee_per_gas: Option<U256>,
+pub(crate) struct FeeDetails {
+	pub gas_price: Option<U256>,
+	pub max_fee_per_gas: Option<U256>,
+	pub max_priority_fee_per_gas: Option<U256>,
 }
 
-fn fee_details(
+pub(crate) fn fee_details(
 	request_gas_price: Option<U256>,
 	request_max_fee: Option<U256>,
 	request_priority: Option<U256>,
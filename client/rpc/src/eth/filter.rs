@@ -16,7 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::{marker::PhantomData, sync::Arc, time};
+use std::{collections::BTreeSet, marker::PhantomData, sync::Arc, time};
 
 use ethereum::BlockV2 as EthereumBlock;
 use ethereum_types::{H256, U256};
@@ -42,6 +42,8 @@ pub struct EthFilter<B: BlockT, C, BE> {
 	filter_pool: FilterPool,
 	max_stored_filters: usize,
 	max_past_logs: u32,
+	max_block_range: Option<u32>,
+	logs_request_timeout: time::Duration,
 	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
 	_marker: PhantomData<BE>,
 }
@@ -53,6 +55,8 @@ impl<B: BlockT, C, BE> EthFilter<B, C, BE> {
 		filter_pool: FilterPool,
 		max_stored_filters: usize,
 		max_past_logs: u32,
+		max_block_range: Option<u32>,
+		logs_request_timeout: time::Duration,
 		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
 	) -> Self {
 		Self {
@@ -61,6 +65,8 @@ impl<B: BlockT, C, BE> EthFilter<B, C, BE> {
 			filter_pool,
 			max_stored_filters,
 			max_past_logs,
+			max_block_range,
+			logs_request_timeout,
 			block_data_cache,
 			_marker: PhantomData,
 		}
@@ -231,8 +237,10 @@ where
 		};
 
 		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
 		let block_data_cache = Arc::clone(&self.block_data_cache);
 		let max_past_logs = self.max_past_logs;
+		let logs_request_timeout = self.logs_request_timeout;
 
 		match path {
 			FuturePath::Error(err) => Err(err),
@@ -264,9 +272,12 @@ where
 				let mut ret: Vec<Log> = Vec::new();
 				let _ = filter_range_logs(
 					client.as_ref(),
+					backend.as_ref(),
 					&block_data_cache,
 					&mut ret,
 					max_past_logs,
+					logs_request_timeout,
+					false,
 					&filter,
 					from_number,
 					current_number,
@@ -302,41 +313,8 @@ where
 			}
 		})();
 
-		let client = Arc::clone(&self.client);
-		let block_data_cache = Arc::clone(&self.block_data_cache);
-		let max_past_logs = self.max_past_logs;
-
-		let filter = filter_result?;
-
-		let best_number = client.info().best_number;
-		let mut current_number = filter
-			.to_block
-			.and_then(|v| v.to_min_block_num())
-			.map(|s| s.unique_saturated_into())
-			.unwrap_or(best_number);
-
-		if current_number > best_number {
-			current_number = best_number;
-		}
-
-		let from_number = filter
-			.from_block
-			.and_then(|v| v.to_min_block_num())
-			.map(|s| s.unique_saturated_into())
-			.unwrap_or(best_number);
-
-		let mut ret: Vec<Log> = Vec::new();
-		let _ = filter_range_logs(
-			client.as_ref(),
-			&block_data_cache,
-			&mut ret,
-			max_past_logs,
-			&filter,
-			from_number,
-			current_number,
-		)
-		.await?;
-		Ok(ret)
+		let (logs, _) = self.query_logs(filter_result?, false).await?;
+		Ok(logs)
 	}
 
 	fn uninstall_filter(&self, index: Index) -> Result<bool> {
@@ -356,10 +334,40 @@ where
 	}
 
 	async fn logs(&self, filter: Filter) -> Result<Vec<Log>> {
+		let (logs, _) = self.query_logs(filter, false).await?;
+		Ok(logs)
+	}
+
+	async fn logs_paginated(&self, filter: Filter) -> Result<PaginatedLogs> {
+		let (logs, next_block) = self.query_logs(filter, true).await?;
+		Ok(PaginatedLogs {
+			logs,
+			next_block: next_block.map(|number| {
+				U256::from(UniqueSaturatedInto::<u64>::unique_saturated_into(number))
+			}),
+		})
+	}
+}
+
+impl<B, C, BE> EthFilter<B, C, BE>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	/// Returns the logs matching the filter. In `partial` mode, the query stops at the first
+	/// limit reached instead of failing, also returning the number of the block to resume from.
+	async fn query_logs(
+		&self,
+		filter: Filter,
+		partial: bool,
+	) -> Result<(Vec<Log>, Option<NumberFor<B>>)> {
 		let client = Arc::clone(&self.client);
 		let block_data_cache = Arc::clone(&self.block_data_cache);
 		let backend = Arc::clone(&self.backend);
-		let max_past_logs = self.max_past_logs;
 
 		let mut ret: Vec<Log> = Vec::new();
 		if let Some(hash) = filter.block_hash {
@@ -367,7 +375,7 @@ where
 				.map_err(|err| internal_err(format!("{:?}", err)))?
 			{
 				Some(hash) => hash,
-				_ => return Ok(Vec::new()),
+				_ => return Ok((Vec::new(), None)),
 			};
 			let substrate_hash = client
 				.expect_block_hash_from_id(&id)
@@ -383,48 +391,79 @@ where
 			if let (Some(block), Some(statuses)) = (block, statuses) {
 				filter_block_logs(&mut ret, &filter, block, statuses);
 			}
-		} else {
-			let best_number = client.info().best_number;
-			let mut current_number = filter
-				.to_block
-				.and_then(|v| v.to_min_block_num())
-				.map(|s| s.unique_saturated_into())
-				.unwrap_or(best_number);
-
-			if current_number > best_number {
-				current_number = best_number;
-			}
+			return Ok((ret, None));
+		}
 
-			let from_number = filter
-				.from_block
-				.and_then(|v| v.to_min_block_num())
-				.map(|s| s.unique_saturated_into())
-				.unwrap_or(best_number);
-
-			let _ = filter_range_logs(
-				client.as_ref(),
-				&block_data_cache,
-				&mut ret,
-				max_past_logs,
-				&filter,
-				from_number,
-				current_number,
-			)
-			.await?;
+		let best_number = client.info().best_number;
+		let mut current_number = filter
+			.to_block
+			.and_then(|v| v.to_min_block_num())
+			.map(|s| s.unique_saturated_into())
+			.unwrap_or(best_number);
+
+		if current_number > best_number {
+			current_number = best_number;
+		}
+
+		let from_number = filter
+			.from_block
+			.and_then(|v| v.to_min_block_num())
+			.map(|s| s.unique_saturated_into())
+			.unwrap_or(best_number);
+
+		// Wider ranges are rejected, or split into pages in partial mode.
+		let mut next_page = None;
+		if let Some(max_block_range) = self.max_block_range {
+			let range: NumberFor<B> = max_block_range.unique_saturated_into();
+			if current_number >= from_number && current_number - from_number >= range {
+				if !partial {
+					return Err(internal_err(format!(
+						"block range is too wide (maximum {})",
+						max_block_range
+					)));
+				}
+				next_page = Some(from_number.saturating_add(range));
+				current_number = from_number.saturating_add(range).saturating_sub(One::one());
+			}
 		}
-		Ok(ret)
+
+		let next_block = filter_range_logs(
+			client.as_ref(),
+			backend.as_ref(),
+			&block_data_cache,
+			&mut ret,
+			self.max_past_logs,
+			self.logs_request_timeout,
+			partial,
+			&filter,
+			from_number,
+			current_number,
+		)
+		.await?;
+
+		Ok((ret, next_block.or(next_page)))
 	}
 }
 
-async fn filter_range_logs<B: BlockT, C, BE>(
+/// Collects the logs of the range matching the filter.
+///
+/// The query fails once it returns more than `max_past_logs` logs or lasts more than
+/// `max_duration`, unless in `partial` mode: it then stops, keeping the logs of the blocks
+/// processed so far and returning the number of the block to resume from. The logs of a
+/// block are never split across pages, a block holding more than `max_past_logs` matching
+/// logs being returned alone.
+pub(crate) async fn filter_range_logs<B: BlockT, C, BE>(
 	client: &C,
+	backend: &fc_db::Backend<B>,
 	block_data_cache: &EthBlockDataCacheTask<B>,
 	ret: &mut Vec<Log>,
 	max_past_logs: u32,
+	max_duration: time::Duration,
+	partial: bool,
 	filter: &Filter,
 	from: NumberFor<B>,
 	to: NumberFor<B>,
-) -> Result<()>
+) -> Result<Option<NumberFor<B>>>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
 	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
@@ -433,12 +472,73 @@ where
 	BE: Backend<B> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
 {
-	// Max request duration of 10 seconds.
-	let max_duration = time::Duration::from_secs(10);
 	let begin_request = time::Instant::now();
+	// Checks the restrictions once the logs of the block `number`, starting at `block_start`,
+	// were collected. Returns the number of the block to resume from if the query stops.
+	let check_restrictions = |ret: &mut Vec<Log>, block_start: usize, number: NumberFor<B>| {
+		let next = (number < to).then(|| number.saturating_add(One::one()));
+		if ret.len() as u32 > max_past_logs {
+			if !partial {
+				return Err(internal_err(format!(
+					"query returned more than {} results",
+					max_past_logs
+				)));
+			}
+			if block_start > 0 {
+				ret.truncate(block_start);
+				return Ok(Some(number));
+			}
+			return Ok(next);
+		}
+		if begin_request.elapsed() > max_duration {
+			if !partial {
+				return Err(internal_err(format!(
+					"query timeout of {} seconds exceeded",
+					max_duration.as_secs()
+				)));
+			}
+			return Ok(next);
+		}
+		Ok(None)
+	};
 
 	let mut current_number = from;
 
+	// If the range starts in the indexed blocks, its indexed part is answered from the log
+	// index, only going through the blocks holding candidate logs. The rest of it is scanned.
+	let indexed_range = backend.log_index().indexed_range().map_err(internal_err)?;
+	if let Some(indexed_range) = indexed_range.filter(|range| range.contains(&from)) {
+		let indexed_to = std::cmp::min(to, indexed_range.end.saturating_sub(One::one()));
+		if let Some(numbers) = indexed_log_blocks(backend, filter, from, indexed_to)? {
+			for number in numbers {
+				let number: NumberFor<B> = number.unique_saturated_into();
+				let id = BlockId::Number(number);
+				let substrate_hash = client
+					.expect_block_hash_from_id(&id)
+					.map_err(|_| internal_err(format!("Expect block number from id: {}", id)))?;
+				let schema =
+					frontier_backend_client::onchain_storage_schema::<B, C, BE>(client, id);
+
+				let block = block_data_cache.current_block(schema, substrate_hash).await;
+				let statuses = block_data_cache
+					.current_transaction_statuses(schema, substrate_hash)
+					.await;
+				let block_start = ret.len();
+				if let (Some(block), Some(statuses)) = (block, statuses) {
+					filter_block_logs(ret, filter, block, statuses);
+				}
+				if let Some(next) = check_restrictions(ret, block_start, number)? {
+					return Ok(Some(next));
+				}
+			}
+
+			if indexed_to == to {
+				return Ok(None);
+			}
+			current_number = indexed_to.saturating_add(One::one());
+		}
+	}
+
 	// Pre-calculate BloomInput for reuse.
 	let topics_input = if filter.topics.is_some() {
 		let filtered_params = FilteredParams::new(Some(filter.clone()));
@@ -459,6 +559,7 @@ where
 
 		let block = block_data_cache.current_block(schema, substrate_hash).await;
 
+		let block_start = ret.len();
 		if let Some(block) = block {
 			if FilteredParams::address_in_bloom(block.header.logs_bloom, &address_bloom_filter)
 				&& FilteredParams::topics_in_bloom(block.header.logs_bloom, &topics_bloom_filter)
@@ -472,17 +573,8 @@ where
 			}
 		}
 		// Check for restrictions
-		if ret.len() as u32 > max_past_logs {
-			return Err(internal_err(format!(
-				"query returned more than {} results",
-				max_past_logs
-			)));
-		}
-		if begin_request.elapsed() > max_duration {
-			return Err(internal_err(format!(
-				"query timeout of {} seconds exceeded",
-				max_duration.as_secs()
-			)));
+		if let Some(next) = check_restrictions(ret, block_start, current_number)? {
+			return Ok(Some(next));
 		}
 		if current_number == to {
 			break;
@@ -490,7 +582,93 @@ where
 			current_number = current_number.saturating_add(One::one());
 		}
 	}
-	Ok(())
+	Ok(None)
+}
+
+/// Returns the numbers of the blocks of the range holding logs which may match the filter,
+/// according to the log index. `None` if the filter constrains neither the address nor the
+/// topics, the index being of no help then.
+fn indexed_log_blocks<B: BlockT>(
+	backend: &fc_db::Backend<B>,
+	filter: &Filter,
+	from: NumberFor<B>,
+	to: NumberFor<B>,
+) -> Result<Option<BTreeSet<u64>>> {
+	let log_index = backend.log_index();
+
+	let address_positions = match &filter.address {
+		Some(VariadicValue::Single(address)) => Some(
+			log_index
+				.address_positions(address, from, to)
+				.map_err(internal_err)?
+				.into_iter()
+				.collect::<BTreeSet<_>>(),
+		),
+		Some(VariadicValue::Multiple(addresses)) if !addresses.is_empty() => {
+			let mut positions = BTreeSet::new();
+			for address in addresses {
+				positions.extend(
+					log_index
+						.address_positions(address, from, to)
+						.map_err(internal_err)?,
+				);
+			}
+			Some(positions)
+		}
+		_ => None,
+	};
+
+	// A log matching one of the alternatives of the topics filter holds all of its topics.
+	let flat_topics = FilteredParams::new(Some(filter.clone())).flat_topics;
+	let mut topic_positions = (!flat_topics.is_empty()).then(BTreeSet::new);
+	for alternative in flat_topics {
+		let topics: Vec<H256> = match alternative {
+			VariadicValue::Single(topic) => topic.into_iter().collect(),
+			VariadicValue::Multiple(topics) => topics.into_iter().flatten().collect(),
+			VariadicValue::Null => Vec::new(),
+		};
+		// An alternative made of wildcards matches any log.
+		let (first, others) = match topics.split_first() {
+			Some(split) => split,
+			None => {
+				topic_positions = None;
+				break;
+			}
+		};
+
+		let mut positions: BTreeSet<_> = log_index
+			.topic_positions(first, from, to)
+			.map_err(internal_err)?
+			.into_iter()
+			.collect();
+		for topic in others {
+			let other_positions: BTreeSet<_> = log_index
+				.topic_positions(topic, from, to)
+				.map_err(internal_err)?
+				.into_iter()
+				.collect();
+			positions.retain(|position| other_positions.contains(position));
+		}
+		if let Some(topic_positions) = topic_positions.as_mut() {
+			topic_positions.extend(positions);
+		}
+	}
+
+	let positions = match (address_positions, topic_positions) {
+		(Some(address_positions), Some(topic_positions)) => address_positions
+			.intersection(&topic_positions)
+			.copied()
+			.collect(),
+		(Some(positions), None) | (None, Some(positions)) => positions,
+		(None, None) => return Ok(None),
+	};
+
+	Ok(Some(
+		positions
+			.into_iter()
+			.map(|position| position.block_number)
+			.collect(),
+	))
 }
 
 fn filter_block_logs<'a>(
@@ -553,3 +731,125 @@ fn filter_block_logs<'a>(
 	}
 	ret
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::H160;
+	use sp_runtime::{
+		generic::{Block, Header},
+		traits::BlakeTwo256,
+	};
+	use tempfile::tempdir;
+
+	type OpaqueBlock =
+		Block<Header<u64, BlakeTwo256>, substrate_test_runtime_client::runtime::Extrinsic>;
+
+	fn filter(address: Option<FilterAddress>, topics: Option<Vec<Option<FlatTopic>>>) -> Filter {
+		Filter {
+			from_block: None,
+			to_block: None,
+			block_hash: None,
+			address,
+			topics: topics.map(VariadicValue::Multiple),
+		}
+	}
+
+	fn topic(topic: H256) -> Option<FlatTopic> {
+		Some(VariadicValue::Single(Some(topic)))
+	}
+
+	#[test]
+	fn indexed_log_blocks_matches_the_filter() {
+		let tmp = tempdir().expect("create a temporary directory");
+		let backend = fc_db::Backend::<OpaqueBlock>::new(&fc_db::DatabaseSettings {
+			source: sc_client_db::DatabaseSource::RocksDb {
+				path: tmp.path().to_path_buf(),
+				cache_size: 0,
+			},
+		})
+		.expect("open the frontier backend");
+
+		let (alice, bob) = (H160::repeat_byte(1), H160::repeat_byte(2));
+		let (transfer, approval) = (H256::repeat_byte(1), H256::repeat_byte(2));
+		let log_index = backend.log_index();
+		log_index.reset(1).unwrap();
+		log_index
+			.write_block_logs(1, vec![(0, alice, vec![transfer])], 2)
+			.unwrap();
+		log_index
+			.write_block_logs(2, vec![(0, bob, vec![transfer])], 3)
+			.unwrap();
+		log_index
+			.write_block_logs(3, vec![(0, alice, vec![approval])], 4)
+			.unwrap();
+		log_index
+			.write_block_logs(4, vec![(0, bob, vec![transfer, approval])], 5)
+			.unwrap();
+
+		let blocks = |address, topics| {
+			indexed_log_blocks(&backend, &filter(address, topics), 1, 4)
+				.unwrap()
+				.map(|numbers| numbers.into_iter().collect::<Vec<_>>())
+		};
+
+		// Addresses.
+		assert_eq!(
+			blocks(Some(VariadicValue::Single(alice)), None),
+			Some(vec![1, 3])
+		);
+		assert_eq!(
+			blocks(Some(VariadicValue::Multiple(vec![alice, bob])), None),
+			Some(vec![1, 2, 3, 4])
+		);
+
+		// The address and the topics are to be matched by the same log.
+		assert_eq!(
+			blocks(
+				Some(VariadicValue::Single(alice)),
+				Some(vec![topic(transfer)])
+			),
+			Some(vec![1])
+		);
+		assert_eq!(
+			blocks(None, Some(vec![topic(transfer), topic(approval)])),
+			Some(vec![4])
+		);
+		// The wildcards don't constrain the logs.
+		assert_eq!(
+			blocks(None, Some(vec![None, topic(approval)])),
+			Some(vec![3, 4])
+		);
+
+		// A log is to match one of the alternatives.
+		assert_eq!(
+			blocks(
+				Some(VariadicValue::Single(alice)),
+				Some(vec![Some(VariadicValue::Multiple(vec![
+					Some(transfer),
+					Some(approval)
+				]))])
+			),
+			Some(vec![1, 3])
+		);
+		// An alternative made of wildcards matches any log.
+		assert_eq!(
+			blocks(
+				Some(VariadicValue::Single(bob)),
+				Some(vec![Some(VariadicValue::Multiple(vec![
+					Some(approval),
+					None
+				]))])
+			),
+			Some(vec![2, 4])
+		);
+
+		// The blocks are scanned if neither the address nor the topics are constrained.
+		assert_eq!(blocks(None, None), None);
+		assert_eq!(
+			blocks(Some(VariadicValue::Multiple(Vec::new())), None),
+			None
+		);
+		assert_eq!(blocks(None, Some(vec![None, None])), None);
+	}
+}
@@ -19,11 +19,13 @@
 mod block;
 mod cache;
 mod client;
+mod eip712;
 mod execute;
 mod fee;
 mod filter;
 pub mod format;
 mod mining;
+mod sign;
 mod state;
 mod submit;
 mod transaction;
@@ -34,7 +36,10 @@ use ethereum::{BlockV2 as EthereumBlock, TransactionV2 as EthereumTransaction};
 use ethereum_types::{H160, H256, H512, H64, U256, U64};
 use jsonrpsee::core::{async_trait, RpcResult as Result};
 // Substrate
-use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StateBackend, StorageProvider},
+	ProofProvider,
+};
 use sc_network::{ExHashT, NetworkService};
 use sc_transaction_pool::{ChainApi, Pool};
 use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
@@ -57,6 +62,10 @@ pub use self::{
 	execute::EstimateGasAdapter,
 	filter::EthFilter,
 };
+pub(crate) use self::{
+	execute::{fee_details, JSON_RPC_ERROR_DEFAULT},
+	filter::filter_range_logs,
+};
 
 /// Eth API implementation.
 pub struct Eth<B: BlockT, C, P, CT, BE, H: ExHashT, A: ChainApi, EGA = ()> {
@@ -117,7 +126,7 @@ impl<B: BlockT, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A
 impl<B, C, P, CT, BE, H: ExHashT, A> EthApiServer for Eth<B, C, P, CT, BE, H, A>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
-	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + ProofProvider<B>,
 	C: HeaderBackend<B> + Send + Sync + 'static,
 	C::Api: BlockBuilderApi<B> + ConvertTransactionRuntimeApi<B> + EthereumRuntimeRPCApi<B>,
 	P: TransactionPool<Block = B> + Send + Sync + 'static,
@@ -243,20 +252,43 @@ where
 		self.code_at(address, number)
 	}
 
+	fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number: Option<BlockNumber>,
+	) -> Result<EthAccount> {
+		self.proof(address, storage_keys, number)
+	}
+
 	// ########################################################################
 	// Execute
 	// ########################################################################
 
-	fn call(&self, request: CallRequest, number: Option<BlockNumber>) -> Result<Bytes> {
-		self.call(request, number)
+	fn call(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
+	) -> Result<Bytes> {
+		self.call(request, number, state_overrides)
 	}
 
 	async fn estimate_gas(
 		&self,
 		request: CallRequest,
 		number: Option<BlockNumber>,
+		state_overrides: Option<BTreeMap<H160, CallStateOverride>>,
 	) -> Result<U256> {
-		self.estimate_gas(request, number).await
+		self.estimate_gas(request, number, state_overrides).await
+	}
+
+	fn create_access_list(
+		&self,
+		request: CallRequest,
+		number: Option<BlockNumber>,
+	) -> Result<AccessListResult> {
+		self.create_access_list(request, number)
 	}
 
 	// ########################################################################
@@ -315,6 +347,22 @@ where
 	async fn send_raw_transaction(&self, bytes: Bytes) -> Result<H256> {
 		self.send_raw_transaction(bytes).await
 	}
+
+	// ########################################################################
+	// Sign
+	// ########################################################################
+
+	fn sign(&self, address: H160, data: Bytes) -> Result<Bytes> {
+		self.sign(address, data)
+	}
+
+	fn personal_sign(&self, data: Bytes, address: H160) -> Result<Bytes> {
+		self.sign(address, data)
+	}
+
+	fn sign_typed_data(&self, address: H160, typed_data: TypedDataRequest) -> Result<Bytes> {
+		self.sign_typed_data(address, typed_data)
+	}
 }
 
 fn rich_block_build(
@@ -383,7 +431,7 @@ fn rich_block_build(
 	}
 }
 
-fn transaction_build(
+pub(crate) fn transaction_build(
 	ethereum_transaction: EthereumTransaction,
 	block: Option<EthereumBlock>,
 	status: Option<TransactionStatus>,
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256};
+use jsonrpsee::core::RpcResult as Result;
+// Substrate
+use sc_network::ExHashT;
+use sc_transaction_pool::ChainApi;
+use sp_core::hashing::keccak_256;
+use sp_runtime::traits::Block as BlockT;
+// Frontier
+use fc_rpc_core::types::*;
+
+use crate::{
+	eth::{eip712::hash_typed_data, Eth},
+	internal_err,
+};
+
+impl<B: BlockT, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A> {
+	pub fn sign(&self, address: H160, data: Bytes) -> Result<Bytes> {
+		let mut message = format!("\x19Ethereum Signed Message:\n{}", data.0.len()).into_bytes();
+		message.extend_from_slice(&data.0);
+		self.sign_hash(H256(keccak_256(&message)), address)
+	}
+
+	pub fn sign_typed_data(&self, address: H160, typed_data: TypedDataRequest) -> Result<Bytes> {
+		let typed_data = typed_data
+			.into_typed_data()
+			.map_err(|err| internal_err(format!("invalid typed data: {}", err)))?;
+		let hash = hash_typed_data(&typed_data)
+			.map_err(|err| internal_err(format!("invalid typed data: {}", err)))?;
+		self.sign_hash(hash, address)
+	}
+
+	fn sign_hash(&self, hash: H256, address: H160) -> Result<Bytes> {
+		let signer = self
+			.signers
+			.iter()
+			.find(|signer| signer.accounts().contains(&address))
+			.ok_or_else(|| internal_err("no signer available"))?;
+		Ok(Bytes::new(signer.sign_hash(hash, &address)?.to_vec()))
+	}
+}
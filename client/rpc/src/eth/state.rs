@@ -16,21 +16,26 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use codec::Encode;
+use codec::{Decode, Encode};
 use ethereum_types::{H160, H256, U256};
 use jsonrpsee::core::RpcResult as Result;
 // Substrate
-use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
+use sc_client_api::{
+	backend::{Backend, StateBackend, StorageProvider},
+	ProofProvider,
+};
 use sc_network::ExHashT;
 use sc_transaction_pool::ChainApi;
 use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
+use sp_core::hashing::keccak_256;
 use sp_runtime::{
 	generic::BlockId,
-	traits::{BlakeTwo256, Block as BlockT},
+	traits::{BlakeTwo256, Block as BlockT, Header as HeaderT},
 };
+use sp_storage::StorageKey;
 // Frontier
 use fc_rpc_core::types::*;
 use fp_rpc::EthereumRuntimeRPCApi;
@@ -43,7 +48,7 @@ use crate::{
 impl<B, C, P, CT, BE, H: ExHashT, A: ChainApi> Eth<B, C, P, CT, BE, H, A>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
-	C: ProvideRuntimeApi<B> + StorageProvider<B, BE>,
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + ProofProvider<B>,
 	C: HeaderBackend<B> + Send + Sync + 'static,
 	C::Api: BlockBuilderApi<B> + EthereumRuntimeRPCApi<B>,
 	BE: Backend<B> + 'static,
@@ -182,4 +187,97 @@ where
 			Ok(Bytes(vec![]))
 		}
 	}
+
+	pub fn proof(
+		&self,
+		address: H160,
+		storage_keys: Vec<U256>,
+		number: Option<BlockNumber>,
+	) -> Result<EthAccount> {
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)? {
+			Some(id) => id,
+			None => {
+				return Err(internal_err(
+					"proofs of the pending block are not available",
+				))
+			}
+		};
+		let header = self
+			.client
+			.header(id)
+			.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+			.ok_or_else(|| internal_err("header not found"))?;
+
+		let api = self.client.runtime_api();
+		let api_version = api
+			.api_version::<dyn EthereumRuntimeRPCApi<B>>(&id)
+			.map_err(|err| {
+				internal_err(format!("failed to retrieve Runtime Api version: {:?}", err))
+			})?
+			.ok_or_else(|| internal_err("failed to retrieve Runtime Api version"))?;
+		if api_version < 6 {
+			return Err(internal_err("eth_getProof is not supported by the runtime"));
+		}
+
+		let account = api.account_basic(&id, address).map_err(|err| {
+			internal_err(format!("fetch runtime account basic failed: {:?}", err))
+		})?;
+		let code = api
+			.account_code_at(&id, address)
+			.map_err(|err| internal_err(format!("fetch runtime account code failed: {:?}", err)))?;
+		let mut account_keys = api.account_storage_keys(&id, address).map_err(|err| {
+			internal_err(format!(
+				"fetch runtime account storage keys failed: {:?}",
+				err
+			))
+		})?;
+		account_keys.push(fp_account_proof::account_code_key(address));
+
+		let read_proof = |keys: &[Vec<u8>]| -> Result<Vec<Bytes>> {
+			Ok(self
+				.client
+				.read_proof(&id, &mut keys.iter().map(|key| &key[..]))
+				.map_err(|err| internal_err(format!("read proof failed: {:?}", err)))?
+				.into_iter_nodes()
+				.map(Bytes)
+				.collect())
+		};
+
+		let storage_proof = storage_keys
+			.into_iter()
+			.map(|index| {
+				let mut slot = [0u8; 32];
+				index.to_big_endian(&mut slot);
+				let key = fp_account_proof::account_storage_key(address, H256::from(slot));
+				let value = self
+					.client
+					.storage(&id, &StorageKey(key.clone()))
+					.map_err(|err| internal_err(format!("fetch storage failed: {:?}", err)))?
+					.map(|data| H256::decode(&mut &data.0[..]))
+					.transpose()
+					.map_err(|err| internal_err(format!("decode storage failed: {:?}", err)))?
+					.unwrap_or_default();
+
+				Ok(StorageProof {
+					key: index,
+					value: U256::from_big_endian(value.as_bytes()),
+					proof: read_proof(&[key])?,
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(EthAccount {
+			address,
+			balance: account.balance,
+			nonce: account.nonce,
+			code_hash: H256::from(keccak_256(&code)),
+			storage_hash: *header.state_root(),
+			account_proof: read_proof(&account_keys)?,
+			storage_proof,
+		})
+	}
 }
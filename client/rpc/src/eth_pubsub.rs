@@ -16,36 +16,54 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc, time::Duration};
 
 use ethereum::{BlockV2 as EthereumBlock, TransactionV2 as EthereumTransaction};
 use ethereum_types::{H256, U256};
 use futures::{FutureExt as _, StreamExt as _};
-use jsonrpsee::{types::SubscriptionResult, SubscriptionSink};
+use jsonrpsee::{
+	types::{
+		error::{ErrorObject, INTERNAL_ERROR_CODE},
+		SubscriptionResult,
+	},
+	SubscriptionSink,
+};
 // Substrate
 use sc_client_api::{
 	backend::{Backend, StateBackend, StorageProvider},
-	client::BlockchainEvents,
+	client::{BlockchainEvents, FinalityNotification},
 };
 use sc_network::{ExHashT, NetworkService, NetworkStatusProvider};
 use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::{ApiExt, BlockId, ProvideRuntimeApi};
-use sp_blockchain::HeaderBackend;
+use sp_blockchain::{HeaderBackend, HeaderMetadata};
 use sp_consensus::SyncOracle;
 use sp_core::hashing::keccak_256;
-use sp_runtime::traits::{BlakeTwo256, Block as BlockT, UniqueSaturatedInto};
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT, NumberFor, UniqueSaturatedInto};
 // Frontier
 use fc_rpc_core::{
 	types::{
-		pubsub::{Kind, Params, PubSubSyncStatus, Result as PubSubResult, SyncStatusMetadata},
-		Bytes, FilteredParams, Header, Log, Rich,
+		pubsub::{
+			Kind, Params, PendingTransactionFilter, PubSubSyncStatus, Result as PubSubResult,
+			SyncStatusMetadata,
+		},
+		Bytes, Filter, FilteredParams, Header, Log, Rich,
 	},
 	EthPubSubApiServer,
 };
 use fp_rpc::EthereumRuntimeRPCApi;
 
-use crate::{frontier_backend_client, overrides::OverrideHandle};
+use crate::{
+	eth::{filter_range_logs, transaction_build, EthBlockDataCacheTask},
+	frontier_backend_client,
+	overrides::OverrideHandle,
+};
+
+/// Maximum number of logs replayed at once by a logs subscription starting in the past.
+const BACKFILL_PAGE_SIZE: u32 = 1_000;
+/// Maximum duration of the query of a page of replayed logs.
+const BACKFILL_PAGE_DURATION: Duration = Duration::from_secs(2);
 
 #[derive(Debug)]
 pub struct EthereumSubIdProvider;
@@ -63,6 +81,9 @@ pub struct EthPubSub<B: BlockT, P, C, BE, H: ExHashT> {
 	network: Arc<NetworkService<B, H>>,
 	subscriptions: SubscriptionTaskExecutor,
 	overrides: Arc<OverrideHandle<B>>,
+	backend: Arc<fc_db::Backend<B>>,
+	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	max_block_range: Option<u32>,
 	starting_block: u64,
 	_marker: PhantomData<BE>,
 }
@@ -77,6 +98,9 @@ where
 		network: Arc<NetworkService<B, H>>,
 		subscriptions: SubscriptionTaskExecutor,
 		overrides: Arc<OverrideHandle<B>>,
+		backend: Arc<fc_db::Backend<B>>,
+		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+		max_block_range: Option<u32>,
 	) -> Self {
 		// Capture the best block as seen on initialization. Used for syncing subscriptions.
 		let starting_block =
@@ -87,6 +111,9 @@ where
 			network,
 			subscriptions,
 			overrides,
+			backend,
+			block_data_cache,
+			max_block_range,
 			starting_block,
 			_marker: PhantomData,
 		}
@@ -123,6 +150,7 @@ impl EthSubscriptionResult {
 		block: EthereumBlock,
 		receipts: Vec<ethereum::ReceiptV3>,
 		params: &FilteredParams,
+		removed: bool,
 	) -> Vec<Log> {
 		let block_hash = Some(H256::from(keccak_256(&rlp::encode(&block.header))));
 		let mut logs: Vec<Log> = vec![];
@@ -151,7 +179,7 @@ impl EthSubscriptionResult {
 						transaction_index: Some(U256::from(receipt_index)),
 						log_index: Some(U256::from(log_index)),
 						transaction_log_index: Some(U256::from(transaction_log_index)),
-						removed: false,
+						removed,
 					});
 				}
 				log_index += 1;
@@ -193,12 +221,100 @@ impl EthSubscriptionResult {
 	}
 }
 
+/// Returns the blocks leaving the canonical chain when its head moves from `from` to `to`,
+/// newest first, and the blocks joining it, oldest first and ending with `to`.
+///
+/// Returns `None` when `to` is `from` or one of its ancestors, which is the case of the import
+/// notifications of blocks already known when the subscription started.
+fn canonical_route<B, C>(
+	client: &C,
+	from: B::Hash,
+	to: B::Hash,
+) -> Option<(Vec<B::Hash>, Vec<B::Hash>)>
+where
+	B: BlockT,
+	C: HeaderMetadata<B>,
+{
+	let tree_route = sp_blockchain::tree_route(client, from, to).ok()?;
+	if tree_route.enacted().is_empty() {
+		return None;
+	}
+	Some((
+		tree_route
+			.retracted()
+			.iter()
+			.map(|hash_and_number| hash_and_number.hash)
+			.collect(),
+		tree_route
+			.enacted()
+			.iter()
+			.map(|hash_and_number| hash_and_number.hash)
+			.collect(),
+	))
+}
+
+/// Returns the blocks finalized at once, in order: the ones finalized implicitly followed by the
+/// one the notification is about.
+fn finalized_route<B: BlockT>(notification: &FinalityNotification<B>) -> Vec<B::Hash> {
+	notification
+		.tree_route
+		.iter()
+		.cloned()
+		.chain(std::iter::once(notification.hash))
+		.collect()
+}
+
+/// Returns the range of blocks of the canonical chain whose matching logs are replayed to a
+/// logs subscription, from `fromBlock` to `toBlock` or the best block.
+fn replayed_range<B: BlockT>(
+	filter: &Filter,
+	best_number: NumberFor<B>,
+) -> Option<(NumberFor<B>, NumberFor<B>)> {
+	if filter.block_hash.is_some() {
+		return None;
+	}
+	let from = filter
+		.from_block
+		.and_then(|number| number.to_min_block_num())
+		.map(UniqueSaturatedInto::<NumberFor<B>>::unique_saturated_into)?;
+	let to = filter
+		.to_block
+		.and_then(|number| number.to_min_block_num())
+		.map(UniqueSaturatedInto::<NumberFor<B>>::unique_saturated_into)
+		.map_or(best_number, |to| to.min(best_number));
+
+	(from <= to).then(|| (from, to))
+}
+
+/// Returns the Ethereum block and receipts stored in the Substrate block.
+fn block_and_receipts<B, C, BE>(
+	client: &C,
+	overrides: &OverrideHandle<B>,
+	hash: B::Hash,
+) -> Option<(EthereumBlock, Vec<ethereum::ReceiptV3>)>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: StorageProvider<B, BE> + Send + Sync + 'static,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+{
+	let id = BlockId::Hash(hash);
+	let schema = frontier_backend_client::onchain_storage_schema::<B, C, BE>(client, id);
+	let handler = overrides
+		.schemas
+		.get(&schema)
+		.unwrap_or(&overrides.fallback);
+
+	Some((handler.current_block(&id)?, handler.current_receipts(&id)?))
+}
+
 impl<B: BlockT, P, C, BE, H: ExHashT> EthPubSubApiServer for EthPubSub<B, P, C, BE, H>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
 	P: TransactionPool<Block = B> + Send + Sync + 'static,
 	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + BlockchainEvents<B>,
-	C: HeaderBackend<B> + Send + Sync + 'static,
+	C: HeaderBackend<B> + HeaderMetadata<B>,
+	C: Send + Sync + 'static,
 	C::Api: EthereumRuntimeRPCApi<B>,
 	BE: Backend<B> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
@@ -209,89 +325,217 @@ where
 		kind: Kind,
 		params: Option<Params>,
 	) -> SubscriptionResult {
+		let (filter, pending_filter) = match params {
+			Some(Params::Logs(filter)) => (Some(filter), PendingTransactionFilter::default()),
+			Some(Params::PendingTransactions(pending_filter)) => (None, pending_filter),
+			_ => (None, PendingTransactionFilter::default()),
+		};
+
+		// The replayed logs are bounded as the `eth_getLogs` ones.
+		if let (Some(filter), Some(max_block_range)) = (&filter, self.max_block_range) {
+			let range = replayed_range::<B>(filter, self.client.info().best_number);
+			let max_range: NumberFor<B> = max_block_range.unique_saturated_into();
+			if matches!(range, Some((from, to)) if to - from >= max_range) {
+				sink.reject(ErrorObject::owned(
+					INTERNAL_ERROR_CODE,
+					format!("block range is too wide (maximum {})", max_block_range),
+					None::<()>,
+				))?;
+				return Ok(());
+			}
+		}
+
 		sink.accept()?;
 
-		let filtered_params = match params {
-			Some(Params::Logs(filter)) => FilteredParams::new(Some(filter)),
-			_ => FilteredParams::default(),
-		};
+		let filtered_params = FilteredParams::new(filter.clone());
 
 		let client = self.client.clone();
 		let pool = self.pool.clone();
 		let network = self.network.clone();
 		let overrides = self.overrides.clone();
+		let backend = self.backend.clone();
+		let block_data_cache = self.block_data_cache.clone();
 		let starting_block = self.starting_block;
 		let fut = async move {
 			match kind {
 				Kind::Logs => {
-					let stream = client
-						.import_notification_stream()
-						.filter_map(move |notification| {
-							if notification.is_new_best {
-								let id = BlockId::Hash(notification.hash);
-
-								let schema = frontier_backend_client::onchain_storage_schema::<
-									B,
-									C,
-									BE,
-								>(client.as_ref(), id);
-								let handler = overrides
-									.schemas
-									.get(&schema)
-									.unwrap_or(&overrides.fallback);
-
-								let block = handler.current_block(&id);
-								let receipts = handler.current_receipts(&id);
+					// The best block is read once subscribed to the import notifications, so that
+					// the blocks imported in between are neither missed nor sent twice.
+					let stream = client.import_notification_stream();
+					let info = client.info();
+					let mut best_hash = info.best_hash;
 
-								match (receipts, block) {
-									(Some(receipts), Some(block)) => {
-										futures::future::ready(Some((block, receipts)))
-									}
-									_ => futures::future::ready(None),
+					// The matching logs of the canonical chain are replayed from `fromBlock`,
+					// before switching to the imported blocks.
+					let replayed = filter.and_then(|filter| {
+						replayed_range::<B>(&filter, info.best_number)
+							.map(|(from, to)| (filter, from, to))
+					});
+					if let Some((filter, from, to)) = replayed {
+						let mut next = Some(from);
+						while let Some(from) = next {
+							let mut logs = Vec::new();
+							next = match filter_range_logs::<B, C, BE>(
+								client.as_ref(),
+								backend.as_ref(),
+								block_data_cache.as_ref(),
+								&mut logs,
+								BACKFILL_PAGE_SIZE,
+								BACKFILL_PAGE_DURATION,
+								true,
+								&filter,
+								from,
+								to,
+							)
+							.await
+							{
+								Ok(next) => next,
+								Err(err) => {
+									log::warn!(target: "rpc", "Failed to replay the logs: {:?}", err);
+									return;
 								}
+							};
+							for log in logs {
+								if !matches!(sink.send(&PubSubResult::Log(Box::new(log))), Ok(true))
+								{
+									return;
+								}
+							}
+						}
+					}
+
+					let stream = stream
+						.filter_map(move |notification| {
+							let route = if notification.is_new_best {
+								canonical_route::<B, C>(
+									client.as_ref(),
+									best_hash,
+									notification.hash,
+								)
+							} else {
+								None
+							};
+							if let Some((retracted, enacted)) = route {
+								best_hash = notification.hash;
+								// Logs of the blocks retracted by a reorg are sent again, marked
+								// as removed, before the logs of the new canonical blocks.
+								let logs = retracted
+									.into_iter()
+									.map(|hash| (hash, true))
+									.chain(enacted.into_iter().map(|hash| (hash, false)))
+									.filter_map(|(hash, removed)| {
+										let (block, receipts) = block_and_receipts::<B, C, BE>(
+											client.as_ref(),
+											overrides.as_ref(),
+											hash,
+										)?;
+										Some(EthSubscriptionResult::logs(
+											block,
+											receipts,
+											&filtered_params,
+											removed,
+										))
+									})
+									.flatten()
+									.collect::<Vec<_>>();
+								futures::future::ready(Some(logs))
 							} else {
 								futures::future::ready(None)
 							}
 						})
-						.flat_map(move |(block, receipts)| {
-							futures::stream::iter(EthSubscriptionResult::logs(
-								block,
-								receipts,
-								&filtered_params,
-							))
-						})
+						.flat_map(futures::stream::iter)
 						.map(|x| PubSubResult::Log(Box::new(x)));
 					sink.pipe_from_stream(stream).await;
 				}
 				Kind::NewHeads => {
-					let stream = client
-						.import_notification_stream()
+					let stream = client.import_notification_stream();
+					let mut best_hash = client.info().best_hash;
+					let stream = stream
 						.filter_map(move |notification| {
-							if notification.is_new_best {
-								let id = BlockId::Hash(notification.hash);
-
-								let schema = frontier_backend_client::onchain_storage_schema::<
-									B,
-									C,
-									BE,
-								>(client.as_ref(), id);
-								let handler = overrides
-									.schemas
-									.get(&schema)
-									.unwrap_or(&overrides.fallback);
-
-								let block = handler.current_block(&id);
-								futures::future::ready(block)
+							let route = if notification.is_new_best {
+								canonical_route::<B, C>(
+									client.as_ref(),
+									best_hash,
+									notification.hash,
+								)
+							} else {
+								None
+							};
+							if let Some((_, enacted)) = route {
+								best_hash = notification.hash;
+								// After a reorg, the heads of all the new canonical blocks are
+								// sent, oldest first.
+								let blocks = enacted
+									.into_iter()
+									.filter_map(|hash| {
+										block_and_receipts::<B, C, BE>(
+											client.as_ref(),
+											overrides.as_ref(),
+											hash,
+										)
+										.map(|(block, _)| block)
+									})
+									.collect::<Vec<_>>();
+								futures::future::ready(Some(blocks))
 							} else {
 								futures::future::ready(None)
 							}
 						})
+						.flat_map(futures::stream::iter)
+						.map(EthSubscriptionResult::new_heads);
+					sink.pipe_from_stream(stream).await;
+				}
+				Kind::NewFinalizedHeads => {
+					let stream = client
+						.finality_notification_stream()
+						.flat_map(move |notification| {
+							let blocks = finalized_route(&notification)
+								.into_iter()
+								.filter_map(|hash| {
+									block_and_receipts::<B, C, BE>(
+										client.as_ref(),
+										overrides.as_ref(),
+										hash,
+									)
+									.map(|(block, _)| block)
+								})
+								.collect::<Vec<_>>();
+							futures::stream::iter(blocks)
+						})
 						.map(EthSubscriptionResult::new_heads);
 					sink.pipe_from_stream(stream).await;
 				}
+				Kind::FinalizedLogs => {
+					let stream = client
+						.finality_notification_stream()
+						.flat_map(move |notification| {
+							let logs = finalized_route(&notification)
+								.into_iter()
+								.filter_map(|hash| {
+									let (block, receipts) = block_and_receipts::<B, C, BE>(
+										client.as_ref(),
+										overrides.as_ref(),
+										hash,
+									)?;
+									Some(EthSubscriptionResult::logs(
+										block,
+										receipts,
+										&filtered_params,
+										false,
+									))
+								})
+								.flatten()
+								.collect::<Vec<_>>();
+							futures::stream::iter(logs)
+						})
+						.map(|x| PubSubResult::Log(Box::new(x)));
+					sink.pipe_from_stream(stream).await;
+				}
 				Kind::NewPendingTransactions => {
 					use sc_transaction_pool_api::InPoolTransaction;
 
+					let full_transactions = pending_filter.full_transactions;
+
 					let stream = pool
 						.import_notification_stream()
 						.filter_map(move |txhash| {
@@ -338,7 +582,17 @@ where
 								futures::future::ready(None)
 							}
 						})
-						.map(|transaction| PubSubResult::TransactionHash(transaction.hash()));
+						.map(|transaction| transaction_build(transaction, None, None, None))
+						.filter(move |transaction| {
+							futures::future::ready(pending_filter.matches(transaction))
+						})
+						.map(move |transaction| {
+							if full_transactions {
+								PubSubResult::Transaction(Box::new(transaction))
+							} else {
+								PubSubResult::TransactionHash(transaction.hash)
+							}
+						});
 					sink.pipe_from_stream(stream).await;
 				}
 				Kind::Syncing => {
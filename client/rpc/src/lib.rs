@@ -25,14 +25,18 @@
 	clippy::new_without_default
 )]
 
+mod debug;
 mod eth;
 mod eth_pubsub;
 mod net;
 mod overrides;
 mod signer;
+mod trace;
+mod txpool;
 mod web3;
 
 pub use self::{
+	debug::Debug,
 	eth::{format, EstimateGasAdapter, Eth, EthBlockDataCacheTask, EthFilter, EthTask},
 	eth_pubsub::{EthPubSub, EthereumSubIdProvider},
 	net::Net,
@@ -41,11 +45,14 @@ pub use self::{
 		SchemaV3Override, StorageOverride,
 	},
 	signer::{EthDevSigner, EthSigner},
+	trace::Trace,
+	txpool::TxPool,
 	web3::Web3,
 };
 pub use ethereum::TransactionV2 as EthereumTransaction;
 pub use fc_rpc_core::{
-	EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer, Web3ApiServer,
+	DebugApiServer, EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer,
+	TraceApiServer, TxPoolApiServer, Web3ApiServer,
 };
 
 pub mod frontier_backend_client {
@@ -172,27 +179,31 @@ pub mod frontier_backend_client {
 		B: BlockT<Hash = H256> + Send + Sync + 'static,
 		C: HeaderBackend<B> + Send + Sync + 'static,
 	{
-		let transaction_metadata = backend
-			.mapping()
+		let mapping = backend.mapping();
+		let transaction_metadata = mapping
 			.transaction_metadata(&transaction_hash)
 			.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?;
 
-		transaction_metadata
-			.iter()
-			.find(|meta| is_canon::<B, C>(client, meta.block_hash))
-			.map_or_else(
-				|| {
-					if !only_canonical && transaction_metadata.len() > 0 {
-						Ok(Some((
-							transaction_metadata[0].ethereum_block_hash,
-							transaction_metadata[0].ethereum_index,
-						)))
-					} else {
-						Ok(None)
-					}
-				},
-				|meta| Ok(Some((meta.ethereum_block_hash, meta.ethereum_index))),
-			)
+		// The canonicality of the blocks mapped before it was tracked is checked on the chain.
+		for meta in &transaction_metadata {
+			let canonical = match mapping
+				.is_canonical(&meta.block_hash)
+				.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?
+			{
+				Some(canonical) => canonical,
+				None => is_canon::<B, C>(client, meta.block_hash),
+			};
+			if canonical {
+				return Ok(Some((meta.ethereum_block_hash, meta.ethereum_index)));
+			}
+		}
+
+		match transaction_metadata.first() {
+			Some(meta) if !only_canonical => {
+				Ok(Some((meta.ethereum_block_hash, meta.ethereum_index)))
+			}
+			_ => Ok(None),
+		}
 	}
 }
 
@@ -36,6 +36,11 @@ pub trait EthSigner: Send + Sync {
 		message: TransactionMessage,
 		address: &H160,
 	) -> Result<EthereumTransaction, Error>;
+	/// Sign a 32 bytes hash using the given account, returning the signature as `r || s || v`
+	/// with `v` being 27 or 28.
+	fn sign_hash(&self, _hash: H256, _address: &H160) -> Result<[u8; 65], Error> {
+		Err(internal_err("signer does not support message signing"))
+	}
 }
 
 pub struct EthDevSigner {
@@ -160,4 +165,19 @@ impl EthSigner for EthDevSigner {
 
 		transaction.ok_or_else(|| internal_err("signer not available"))
 	}
+
+	fn sign_hash(&self, hash: H256, address: &H160) -> Result<[u8; 65], Error> {
+		let secret = self
+			.keys
+			.iter()
+			.find(|secret| &secret_key_address(secret) == address)
+			.ok_or_else(|| internal_err("signer not available"))?;
+
+		let signing_message = libsecp256k1::Message::parse(hash.as_fixed_bytes());
+		let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+		let mut res = [0u8; 65];
+		res[0..64].copy_from_slice(&signature.serialize());
+		res[64] = 27 + recid.serialize();
+		Ok(res)
+	}
 }
@@ -0,0 +1,444 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use ethereum_types::{H160, H256};
+use evm::{ExitError, ExitReason};
+use jsonrpsee::core::RpcResult as Result;
+// Substrate
+use sc_client_api::BlockBackend;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto},
+};
+// Frontier
+use fc_rpc_core::{types::*, TraceApiServer};
+use fp_evm::tracing::{CallFrame, CallType};
+use fp_rpc::DebugRuntimeApi;
+
+use crate::{frontier_backend_client, internal_err};
+
+/// Parity-style trace API implementation.
+pub struct Trace<B: BlockT, C> {
+	client: Arc<C>,
+	backend: Arc<fc_db::Backend<B>>,
+	max_block_range: u64,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C> Trace<B, C> {
+	pub fn new(client: Arc<C>, backend: Arc<fc_db::Backend<B>>, max_block_range: u64) -> Self {
+		Self {
+			client,
+			backend,
+			max_block_range,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C> Trace<B, C>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + BlockBackend<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: DebugRuntimeApi<B>,
+{
+	/// Returns the traces of the block, re-executing it unless it was already traced in the
+	/// background.
+	fn block_traces(&self, hash: B::Hash) -> Result<Vec<LocalizedTrace>> {
+		let traces = match self
+			.backend
+			.trace()
+			.block_traces(&hash)
+			.map_err(|err| internal_err(format!("fetch trace cache failed: {:?}", err)))?
+		{
+			Some(traces) => traces,
+			None => fc_mapping_sync::trace_block(self.client.as_ref(), hash)
+				.map_err(|err| internal_err(format!("block tracing failed: {}", err)))?
+				.ok_or_else(|| internal_err("tracing is not supported by the runtime"))?,
+		};
+		if traces.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let header = self
+			.client
+			.header(BlockId::Hash(hash))
+			.map_err(|err| internal_err(format!("fetch header failed: {:?}", err)))?
+			.ok_or_else(|| internal_err("header not found"))?;
+		let block_hash = fp_consensus::find_log(header.digest())
+			.map_err(|_| internal_err("ethereum block not found"))?
+			.into_hashes()
+			.block_hash;
+		let block_number = UniqueSaturatedInto::<u64>::unique_saturated_into(*header.number());
+
+		let mut localized = Vec::new();
+		for (position, trace) in traces.into_iter().enumerate() {
+			if let Some(root) = trace.root {
+				let location = TraceLocation {
+					transaction_position: position as u32,
+					transaction_hash: trace.transaction_hash,
+					block_number,
+					block_hash,
+				};
+				flatten_call_frame(root, Vec::new(), &location, &mut localized);
+			}
+		}
+
+		Ok(localized)
+	}
+
+	fn block_number(&self, number: Option<BlockNumber>) -> Result<u64> {
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			number,
+		)? {
+			Some(id) => id,
+			// Nothing to trace in the pending block yet.
+			None => BlockId::Hash(self.client.info().best_hash),
+		};
+
+		self.client
+			.block_number_from_id(&id)
+			.ok()
+			.flatten()
+			.map(UniqueSaturatedInto::<u64>::unique_saturated_into)
+			.ok_or_else(|| internal_err("header not found"))
+	}
+}
+
+impl<B, C> TraceApiServer for Trace<B, C>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + BlockBackend<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: DebugRuntimeApi<B>,
+{
+	fn trace_block(&self, number: BlockNumber) -> Result<Option<Vec<LocalizedTrace>>> {
+		let id = match frontier_backend_client::native_block_id::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			Some(number),
+		)? {
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		let hash = match self.client.block_hash_from_id(&id) {
+			Ok(Some(hash)) => hash,
+			_ => return Ok(None),
+		};
+
+		self.block_traces(hash).map(Some)
+	}
+
+	fn trace_transaction(&self, transaction_hash: H256) -> Result<Option<Vec<LocalizedTrace>>> {
+		let (ethereum_block_hash, index) = match frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.backend.as_ref(),
+			transaction_hash,
+			true,
+		)? {
+			Some(transaction) => transaction,
+			None => return Ok(None),
+		};
+		let id = match frontier_backend_client::load_hash::<B>(
+			self.backend.as_ref(),
+			ethereum_block_hash,
+		)? {
+			Some(id) => id,
+			None => return Ok(None),
+		};
+		let hash = self
+			.client
+			.expect_block_hash_from_id(&id)
+			.map_err(|_| internal_err("header not found"))?;
+
+		Ok(Some(
+			self.block_traces(hash)?
+				.into_iter()
+				.filter(|trace| trace.transaction_position == index)
+				.collect(),
+		))
+	}
+
+	fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<LocalizedTrace>> {
+		let from_number = self.block_number(filter.from_block.clone())?;
+		let to_number = self.block_number(filter.to_block.clone())?;
+		if from_number > to_number {
+			return Err(internal_err("fromBlock cannot be greater than toBlock"));
+		}
+		if to_number - from_number >= self.max_block_range {
+			return Err(internal_err(format!(
+				"block range is too wide (maximum {})",
+				self.max_block_range
+			)));
+		}
+
+		let mut traces = Vec::new();
+		for number in from_number..=to_number {
+			let hash = self
+				.client
+				.hash(number.unique_saturated_into())
+				.ok()
+				.flatten()
+				.ok_or_else(|| internal_err("header not found"))?;
+			traces.extend(
+				self.block_traces(hash)?
+					.into_iter()
+					.filter(|trace| matches_filter(trace, &filter)),
+			);
+		}
+
+		Ok(traces
+			.into_iter()
+			.skip(filter.after.unwrap_or(0))
+			.take(filter.count.unwrap_or(usize::MAX))
+			.collect())
+	}
+}
+
+struct TraceLocation {
+	transaction_position: u32,
+	transaction_hash: H256,
+	block_number: u64,
+	block_hash: H256,
+}
+
+/// Flattens the call tree, parents first, into `traces`.
+fn flatten_call_frame(
+	frame: CallFrame,
+	trace_address: Vec<usize>,
+	location: &TraceLocation,
+	traces: &mut Vec<LocalizedTrace>,
+) {
+	let CallFrame {
+		call_type,
+		from,
+		to,
+		value,
+		gas,
+		gas_used,
+		input,
+		output,
+		exit_reason,
+		calls,
+	} = frame;
+
+	let error = match exit_reason {
+		None | Some(ExitReason::Succeed(_)) => None,
+		Some(ExitReason::Revert(_)) => Some("Reverted".to_string()),
+		Some(ExitReason::Error(ExitError::OutOfGas)) => Some("Out of gas".to_string()),
+		Some(ExitReason::Error(err)) => Some(format!("{:?}", err)),
+		Some(ExitReason::Fatal(err)) => Some(format!("{:?}", err)),
+	};
+
+	let (trace_type, action, result) = match call_type {
+		CallType::Create | CallType::Create2 => (
+			"create",
+			TraceAction::Create(CreateAction {
+				from,
+				value: value.unwrap_or_default(),
+				gas,
+				init: Bytes(input),
+				creation_method: match call_type {
+					CallType::Create2 => "create2",
+					_ => "create",
+				}
+				.to_string(),
+			}),
+			Some(TraceOutput::Create(CreateOutput {
+				gas_used,
+				code: Bytes(output),
+				address: to,
+			})),
+		),
+		CallType::SelfDestruct => (
+			"suicide",
+			TraceAction::Suicide(SuicideAction {
+				address: from,
+				refund_address: to,
+				balance: value.unwrap_or_default(),
+			}),
+			None,
+		),
+		CallType::Call | CallType::CallCode | CallType::DelegateCall | CallType::StaticCall => (
+			"call",
+			TraceAction::Call(CallAction {
+				from,
+				to,
+				value: value.unwrap_or_default(),
+				gas,
+				input: Bytes(input),
+				call_type: match call_type {
+					CallType::CallCode => "callcode",
+					CallType::DelegateCall => "delegatecall",
+					CallType::StaticCall => "staticcall",
+					_ => "call",
+				}
+				.to_string(),
+			}),
+			Some(TraceOutput::Call(CallOutput {
+				gas_used,
+				output: Bytes(output),
+			})),
+		),
+	};
+
+	traces.push(LocalizedTrace {
+		action,
+		result: if error.is_none() { result } else { None },
+		error,
+		trace_address: trace_address.clone(),
+		subtraces: calls.len(),
+		transaction_position: location.transaction_position,
+		transaction_hash: location.transaction_hash,
+		block_number: location.block_number,
+		block_hash: location.block_hash,
+		trace_type: trace_type.to_string(),
+	});
+
+	for (index, call) in calls.into_iter().enumerate() {
+		let mut call_address = trace_address.clone();
+		call_address.push(index);
+		flatten_call_frame(call, call_address, location, traces);
+	}
+}
+
+fn matches_filter(trace: &LocalizedTrace, filter: &TraceFilter) -> bool {
+	let (from, to): (H160, Option<H160>) = match (&trace.action, &trace.result) {
+		(TraceAction::Call(action), _) => (action.from, Some(action.to)),
+		(TraceAction::Create(action), Some(TraceOutput::Create(output))) => {
+			(action.from, Some(output.address))
+		}
+		(TraceAction::Create(action), _) => (action.from, None),
+		(TraceAction::Suicide(action), _) => (action.address, Some(action.refund_address)),
+	};
+
+	let matches = |addresses: &Option<Vec<H160>>, address: Option<H160>| match addresses {
+		Some(addresses) if !addresses.is_empty() => {
+			address.map_or(false, |address| addresses.contains(&address))
+		}
+		_ => true,
+	};
+
+	matches(&filter.from_address, Some(from)) && matches(&filter.to_address, to)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::U256;
+
+	fn frame(call_type: CallType, from: u64, to: u64, calls: Vec<CallFrame>) -> CallFrame {
+		CallFrame {
+			call_type,
+			from: H160::from_low_u64_be(from),
+			to: H160::from_low_u64_be(to),
+			value: Some(U256::zero()),
+			gas: U256::from(100_000),
+			gas_used: U256::from(21_000),
+			input: Vec::new(),
+			output: Vec::new(),
+			exit_reason: Some(ExitReason::Succeed(evm::ExitSucceed::Returned)),
+			calls,
+		}
+	}
+
+	fn flatten(root: CallFrame) -> Vec<LocalizedTrace> {
+		let location = TraceLocation {
+			transaction_position: 0,
+			transaction_hash: H256::zero(),
+			block_number: 1,
+			block_hash: H256::zero(),
+		};
+		let mut traces = Vec::new();
+		flatten_call_frame(root, Vec::new(), &location, &mut traces);
+		traces
+	}
+
+	#[test]
+	fn flattens_call_tree_parents_first() {
+		let traces = flatten(frame(
+			CallType::Call,
+			1,
+			2,
+			vec![
+				frame(
+					CallType::DelegateCall,
+					2,
+					3,
+					vec![frame(CallType::Create2, 2, 4, Vec::new())],
+				),
+				frame(CallType::StaticCall, 2, 5, Vec::new()),
+			],
+		));
+
+		let addresses: Vec<_> = traces.iter().map(|t| t.trace_address.clone()).collect();
+		assert_eq!(addresses, vec![vec![], vec![0], vec![0, 0], vec![1]]);
+		let subtraces: Vec<_> = traces.iter().map(|t| t.subtraces).collect();
+		assert_eq!(subtraces, vec![2, 1, 0, 0]);
+		assert_eq!(traces[2].trace_type, "create");
+		assert!(matches!(
+			&traces[2].action,
+			TraceAction::Create(action) if action.creation_method == "create2"
+		));
+		assert!(matches!(
+			&traces[1].action,
+			TraceAction::Call(action) if action.call_type == "delegatecall"
+		));
+	}
+
+	#[test]
+	fn failed_call_has_no_result() {
+		let mut root = frame(CallType::Call, 1, 2, Vec::new());
+		root.exit_reason = Some(ExitReason::Revert(evm::ExitRevert::Reverted));
+		let traces = flatten(root);
+
+		assert_eq!(traces[0].result, None);
+		assert_eq!(traces[0].error, Some("Reverted".to_string()));
+	}
+
+	#[test]
+	fn filters_by_address() {
+		let traces = flatten(frame(
+			CallType::Call,
+			1,
+			2,
+			vec![frame(CallType::Create, 2, 3, Vec::new())],
+		));
+		let filter = |from: Vec<u64>, to: Vec<u64>| TraceFilter {
+			from_address: Some(from.into_iter().map(H160::from_low_u64_be).collect()),
+			to_address: Some(to.into_iter().map(H160::from_low_u64_be).collect()),
+			..Default::default()
+		};
+
+		let matching = |filter: TraceFilter| {
+			traces
+				.iter()
+				.filter(|trace| matches_filter(trace, &filter))
+				.count()
+		};
+		assert_eq!(matching(filter(vec![], vec![])), 2);
+		assert_eq!(matching(filter(vec![1], vec![])), 1);
+		assert_eq!(matching(filter(vec![2], vec![3])), 1);
+		assert_eq!(matching(filter(vec![1], vec![3])), 0);
+	}
+}
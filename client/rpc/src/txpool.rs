@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
+
+use ethereum::TransactionV2 as EthereumTransaction;
+use ethereum_types::{H160, H256, U256};
+use jsonrpsee::core::RpcResult as Result;
+// Substrate
+use sc_transaction_pool::{ChainApi, Pool};
+use sc_transaction_pool_api::InPoolTransaction;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::hashing::keccak_256;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+// Frontier
+use fc_rpc_core::{types::*, TxPoolApiServer};
+use fp_rpc::EthereumRuntimeRPCApi;
+
+use crate::{eth::transaction_build, internal_err, public_key};
+
+/// Txpool API implementation.
+pub struct TxPool<B: BlockT, C, A: ChainApi> {
+	client: Arc<C>,
+	graph: Arc<Pool<A>>,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C, A: ChainApi> TxPool<B, C, A> {
+	pub fn new(client: Arc<C>, graph: Arc<Pool<A>>) -> Self {
+		Self {
+			client,
+			graph,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, A> TxPool<B, C, A>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	A: ChainApi<Block = B> + 'static,
+{
+	/// Decodes the Ethereum transactions of the ready and future queues of the pool.
+	fn transactions(&self) -> Result<TxPoolResult<Vec<EthereumTransaction>>> {
+		let api = self.client.runtime_api();
+		let best_block: BlockId<B> = BlockId::Hash(self.client.info().best_hash);
+
+		let api_version = if let Ok(Some(api_version)) =
+			api.api_version::<dyn EthereumRuntimeRPCApi<B>>(&best_block)
+		{
+			api_version
+		} else {
+			return Err(internal_err("failed to retrieve Runtime Api version"));
+		};
+
+		let extrinsic_filter =
+			|xts: Vec<<B as BlockT>::Extrinsic>| -> Result<Vec<EthereumTransaction>> {
+				Ok(if api_version > 1 {
+					api.extrinsic_filter(&best_block, xts).map_err(|err| {
+						internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
+					})?
+				} else {
+					#[allow(deprecated)]
+					let legacy = api.extrinsic_filter_before_version_2(&best_block, xts)
+						.map_err(|err| {
+							internal_err(format!(
+								"fetch runtime extrinsic filter failed: {:?}",
+								err
+							))
+						})?;
+					legacy.into_iter().map(|tx| tx.into()).collect()
+				})
+			};
+
+		// Transactions in the ready validated pool.
+		let ready = self
+			.graph
+			.validated_pool()
+			.ready()
+			.map(|in_pool_tx| in_pool_tx.data().clone())
+			.collect();
+		// Transactions in the future validated pool.
+		let future = self
+			.graph
+			.validated_pool()
+			.futures()
+			.into_iter()
+			.map(|(_hash, extrinsic)| extrinsic)
+			.collect();
+
+		Ok(TxPoolResult {
+			pending: extrinsic_filter(ready)?,
+			queued: extrinsic_filter(future)?,
+		})
+	}
+
+	/// Groups the transactions of the pool by sender and by nonce, skipping the ones whose
+	/// signature doesn't recover.
+	fn map_build<T, F>(&self, f: F) -> Result<TxPoolResult<TransactionMap<T>>>
+	where
+		F: Fn(&EthereumTransaction) -> T,
+	{
+		let group = |transactions: Vec<EthereumTransaction>| {
+			let mut map: TransactionMap<T> = BTreeMap::new();
+			for transaction in transactions {
+				let from = match public_key(&transaction) {
+					Ok(pk) => H160::from(H256::from(keccak_256(&pk))),
+					Err(_) => continue,
+				};
+				let nonce = match &transaction {
+					EthereumTransaction::Legacy(t) => t.nonce,
+					EthereumTransaction::EIP2930(t) => t.nonce,
+					EthereumTransaction::EIP1559(t) => t.nonce,
+				};
+				map.entry(from)
+					.or_default()
+					.insert(nonce.to_string(), f(&transaction));
+			}
+			map
+		};
+
+		let transactions = self.transactions()?;
+		Ok(TxPoolResult {
+			pending: group(transactions.pending),
+			queued: group(transactions.queued),
+		})
+	}
+}
+
+impl<B, C, A> TxPoolApiServer for TxPool<B, C, A>
+where
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	A: ChainApi<Block = B> + 'static,
+{
+	fn content(&self) -> Result<TxPoolResult<TransactionMap<Transaction>>> {
+		self.map_build(|transaction| transaction_build(transaction.clone(), None, None, None))
+	}
+
+	fn inspect(&self) -> Result<TxPoolResult<TransactionMap<Summary>>> {
+		self.map_build(|transaction| {
+			let (action, value, gas, gas_price) = match transaction {
+				EthereumTransaction::Legacy(t) => (&t.action, t.value, t.gas_limit, t.gas_price),
+				EthereumTransaction::EIP2930(t) => (&t.action, t.value, t.gas_limit, t.gas_price),
+				EthereumTransaction::EIP1559(t) => {
+					(&t.action, t.value, t.gas_limit, t.max_fee_per_gas)
+				}
+			};
+			Summary {
+				to: match action {
+					ethereum::TransactionAction::Call(to) => Some(*to),
+					ethereum::TransactionAction::Create => None,
+				},
+				value,
+				gas,
+				gas_price,
+			}
+		})
+	}
+
+	fn status(&self) -> Result<TxPoolResult<U256>> {
+		let transactions = self.transactions()?;
+		Ok(TxPoolResult {
+			pending: U256::from(transactions.pending.len()),
+			queued: U256::from(transactions.queued.len()),
+		})
+	}
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+use frame_benchmarking::benchmarks;
+use frame_support::traits::{EnsureOrigin, Get, Hooks};
+
+use super::*;
+
+fn key<T: Config>(idx: u32) -> Vec<u8> {
+	let mut key = idx.encode();
+	key.resize(T::MaxKeyLen::get() as usize, 0);
+
+	key
+}
+
+benchmarks! {
+	on_finalize {
+		let k in 0 .. T::MaxKeys::get();
+
+		let origin = T::RegisterOrigin::successful_origin();
+		for idx in 0..k {
+			let key = key::<T>(idx);
+			sp_io::storage::set(&key, &[1; 32]);
+			Pallet::<T>::register_key(origin.clone(), key)?;
+		}
+		let block_number: T::BlockNumber = 1u32.into();
+	}: {
+		Pallet::<T>::on_finalize(block_number);
+	}
+	verify {
+		frame_system::Pallet::<T>::set_block_number(2u32.into());
+		for idx in 0..k {
+			assert_eq!(Pallet::<T>::snapshot(&key::<T>(idx), block_number), Some(Some(vec![1; 32])));
+		}
+	}
+
+	register_key {
+		let origin = T::RegisterOrigin::successful_origin();
+		for idx in 1..T::MaxKeys::get() {
+			Pallet::<T>::register_key(origin.clone(), key::<T>(idx))?;
+		}
+	}: _<T::Origin>(origin, key::<T>(0))
+	verify {
+		assert!(SnapshotKeys::<T>::get().iter().any(|registered| registered[..] == key::<T>(0)[..]));
+	}
+
+	unregister_key {
+		let r in 0 .. T::Retention::get();
+
+		let origin = T::RegisterOrigin::successful_origin();
+		Pallet::<T>::register_key(origin.clone(), key::<T>(0))?;
+		for block_number in 0..r {
+			Pallet::<T>::on_finalize(block_number.into());
+		}
+	}: _<T::Origin>(origin, key::<T>(0))
+	verify {
+		assert!(!SnapshotKeys::<T>::get().iter().any(|registered| registered[..] == key::<T>(0)[..]));
+	}
+}
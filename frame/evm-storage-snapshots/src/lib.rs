@@ -0,0 +1,199 @@
+//! Keeps per-block snapshots of the registered raw storage keys, so their past values can be read
+//! (for ex. by the `HistoricalStorageReader` precompile) within the configured retention window.
+//!
+//! Values of the registered keys are checkpointed at the end of each block into the child trie
+//! which acts as a ring buffer with `Retention` slots per key. The pallet should be placed last in
+//! `construct_runtime!`, so values modified by other pallets' `on_finalize` are checkpointed as well.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use codec::Encode;
+use frame_support::storage::child::{self, ChildInfo};
+use sp_runtime::traits::{Saturating, UniqueSaturatedInto, Zero};
+use sp_std::prelude::*;
+
+pub use self::pallet::*;
+pub use weights::WeightInfo;
+
+/// Identifier of the child trie containing snapshots.
+pub const SNAPSHOTS_CHILD_TRIE: &[u8] = b"evm-storage-snapshots";
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+		/// Origin allowed to register and unregister keys.
+		type RegisterOrigin: EnsureOrigin<Self::Origin>;
+		/// Amount of the latest blocks snapshots are kept for.
+		/// Changing it invalidates previously taken snapshots.
+		type Retention: Get<u32>;
+		/// Max amount of the registered keys.
+		type MaxKeys: Get<u32>;
+		/// Max length of the registered key.
+		type MaxKeyLen: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Raw storage keys checkpointed at the end of each block.
+	#[pallet::storage]
+	#[pallet::getter(fn snapshot_keys)]
+	pub type SnapshotKeys<T: Config> =
+		StorageValue<_, BoundedVec<BoundedVec<u8, T::MaxKeyLen>, T::MaxKeys>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// Key will be checkpointed starting from the current block.
+		KeyRegistered { key: Vec<u8> },
+		/// Key won't be checkpointed anymore, its snapshots are removed.
+		KeyUnregistered { key: Vec<u8> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Key is longer than `MaxKeyLen`.
+		KeyTooLong,
+		/// `MaxKeys` keys are registered already.
+		TooManyKeys,
+		/// Key is registered already.
+		AlreadyRegistered,
+		/// Key isn't registered.
+		NotRegistered,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: T::BlockNumber) -> Weight {
+			// Register the Weight used on_finalize.
+			T::WeightInfo::on_finalize(SnapshotKeys::<T>::decode_len().unwrap_or_default() as u32)
+				.saturating_add(T::DbWeight::get().reads(1))
+		}
+
+		fn on_finalize(n: T::BlockNumber) {
+			let slot = match Self::slot(n) {
+				Some(slot) => slot,
+				None => return,
+			};
+
+			for key in SnapshotKeys::<T>::get() {
+				let value = sp_io::storage::get(&key).map(|value| value.to_vec());
+
+				child::put(
+					&Self::child_info(),
+					&Self::snapshot_key(&key, slot),
+					&(n, value),
+				);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Registers the raw storage key to be checkpointed at the end of each block.
+		#[pallet::weight(T::WeightInfo::register_key())]
+		pub fn register_key(origin: OriginFor<T>, key: Vec<u8>) -> DispatchResult {
+			T::RegisterOrigin::ensure_origin(origin)?;
+
+			let bounded_key: BoundedVec<u8, T::MaxKeyLen> =
+				key.clone().try_into().map_err(|_| Error::<T>::KeyTooLong)?;
+
+			SnapshotKeys::<T>::try_mutate(|keys| -> Result<(), Error<T>> {
+				ensure!(!keys.contains(&bounded_key), Error::<T>::AlreadyRegistered);
+
+				keys.try_push(bounded_key)
+					.map_err(|_| Error::<T>::TooManyKeys)
+			})?;
+
+			Self::deposit_event(Event::KeyRegistered { key });
+
+			Ok(())
+		}
+
+		/// Stops checkpointing the raw storage key and removes its snapshots.
+		///
+		/// Only the `Retention` slots of the key are removed, to keep the weight bounded. The
+		/// snapshots taken under a larger previous `Retention` are left in the child trie, but
+		/// can't be read anymore as they're out of the current slots.
+		#[pallet::weight(T::WeightInfo::unregister_key(T::Retention::get()))]
+		pub fn unregister_key(origin: OriginFor<T>, key: Vec<u8>) -> DispatchResult {
+			T::RegisterOrigin::ensure_origin(origin)?;
+
+			SnapshotKeys::<T>::try_mutate(|keys| -> Result<(), Error<T>> {
+				let idx = keys
+					.iter()
+					.position(|registered| registered[..] == key[..])
+					.ok_or(Error::<T>::NotRegistered)?;
+				keys.remove(idx);
+
+				Ok(())
+			})?;
+
+			let child_info = Self::child_info();
+			for slot in 0..T::Retention::get() {
+				child::kill(&child_info, &Self::snapshot_key(&key, slot));
+			}
+
+			Self::deposit_event(Event::KeyUnregistered { key });
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the value which the raw storage key had at the end of the given block.
+	/// Returns `None` if the block is out of the retention window or the key wasn't checkpointed
+	/// for it (for ex. it was registered later).
+	pub fn snapshot(key: &[u8], block_number: T::BlockNumber) -> Option<Option<Vec<u8>>> {
+		let current = frame_system::Pallet::<T>::block_number();
+		let retention: T::BlockNumber = T::Retention::get().into();
+
+		if block_number >= current || block_number.saturating_add(retention) < current {
+			return None;
+		}
+
+		let slot = Self::slot(block_number)?;
+		let (checkpointed_at, value): (T::BlockNumber, Option<Vec<u8>>) =
+			child::get(&Self::child_info(), &Self::snapshot_key(key, slot))?;
+
+		(checkpointed_at == block_number).then(|| value)
+	}
+
+	/// Ring buffer slot used to keep snapshots for the given block.
+	/// Returns `None` if the retention is zero.
+	fn slot(block_number: T::BlockNumber) -> Option<u32> {
+		let retention: T::BlockNumber = T::Retention::get().into();
+		if retention.is_zero() {
+			return None;
+		}
+
+		Some((block_number % retention).unique_saturated_into())
+	}
+
+	fn snapshot_key(key: &[u8], slot: u32) -> Vec<u8> {
+		(key, slot).encode()
+	}
+
+	fn child_info() -> ChildInfo {
+		ChildInfo::new_default(SNAPSHOTS_CHILD_TRIE)
+	}
+}
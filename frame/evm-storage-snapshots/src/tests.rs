@@ -0,0 +1,166 @@
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{OnFinalize, OnInitialize},
+};
+use sp_runtime::DispatchError;
+
+use super::*;
+use crate::mock::{new_test_ext, Origin, StorageSnapshots, System, Test};
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		StorageSnapshots::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		StorageSnapshots::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn register_key() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			StorageSnapshots::register_key(Origin::signed(1), b"key".to_vec()),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			StorageSnapshots::register_key(Origin::root(), b"too long key".to_vec()),
+			Error::<Test>::KeyTooLong
+		);
+
+		assert_ok!(StorageSnapshots::register_key(
+			Origin::root(),
+			b"key".to_vec()
+		));
+		assert_noop!(
+			StorageSnapshots::register_key(Origin::root(), b"key".to_vec()),
+			Error::<Test>::AlreadyRegistered
+		);
+
+		assert_ok!(StorageSnapshots::register_key(
+			Origin::root(),
+			b"key2".to_vec()
+		));
+		assert_noop!(
+			StorageSnapshots::register_key(Origin::root(), b"key3".to_vec()),
+			Error::<Test>::TooManyKeys
+		);
+
+		assert_eq!(
+			StorageSnapshots::snapshot_keys()
+				.into_iter()
+				.map(Into::into)
+				.collect::<Vec<Vec<u8>>>(),
+			vec![b"key".to_vec(), b"key2".to_vec()]
+		);
+	});
+}
+
+#[test]
+fn snapshots() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(StorageSnapshots::register_key(
+			Origin::root(),
+			b"key".to_vec()
+		));
+
+		for block in 1..=10u64 {
+			if block % 3 == 0 {
+				sp_io::storage::clear(b"key");
+			} else {
+				sp_io::storage::set(b"key", &block.encode());
+			}
+			run_to_block(block + 1);
+		}
+
+		// Block 11 is being executed, so only the blocks 7..=10 are retained
+		for block in 1..=6u64 {
+			assert_eq!(StorageSnapshots::snapshot(b"key", block), None);
+		}
+		for block in 7..=10u64 {
+			let expected = (block % 3 != 0).then(|| block.encode());
+			assert_eq!(StorageSnapshots::snapshot(b"key", block), Some(expected));
+		}
+		assert_eq!(StorageSnapshots::snapshot(b"key", 11), None);
+		assert_eq!(StorageSnapshots::snapshot(b"other", 10), None);
+	});
+}
+
+#[test]
+fn unregister_key() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(
+			StorageSnapshots::unregister_key(Origin::root(), b"key".to_vec()),
+			Error::<Test>::NotRegistered
+		);
+
+		assert_ok!(StorageSnapshots::register_key(
+			Origin::root(),
+			b"key".to_vec()
+		));
+		sp_io::storage::set(b"key", b"value");
+		run_to_block(3);
+		assert_eq!(
+			StorageSnapshots::snapshot(b"key", 2),
+			Some(Some(b"value".to_vec()))
+		);
+
+		assert_noop!(
+			StorageSnapshots::unregister_key(Origin::signed(1), b"key".to_vec()),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(StorageSnapshots::unregister_key(
+			Origin::root(),
+			b"key".to_vec()
+		));
+		assert!(StorageSnapshots::snapshot_keys().is_empty());
+		assert_eq!(StorageSnapshots::snapshot(b"key", 2), None);
+		for slot in 0..4 {
+			assert!(!child::exists(
+				&StorageSnapshots::child_info(),
+				&StorageSnapshots::snapshot_key(b"key", slot)
+			));
+		}
+
+		// Snapshots aren't taken anymore
+		run_to_block(4);
+		assert_eq!(StorageSnapshots::snapshot(b"key", 3), None);
+	});
+}
+
+#[test]
+fn unregister_key_leaves_snapshots_beyond_retention_unreachable() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(StorageSnapshots::register_key(
+			Origin::root(),
+			b"key".to_vec()
+		));
+		assert_ok!(StorageSnapshots::register_key(
+			Origin::root(),
+			b"key2".to_vec()
+		));
+		// Taken while `Retention` was larger than the current one
+		let stale = StorageSnapshots::snapshot_key(b"key", 10);
+		child::put(
+			&StorageSnapshots::child_info(),
+			&stale,
+			&(10u64, Some(b"value".to_vec())),
+		);
+		run_to_block(2);
+
+		assert_ok!(StorageSnapshots::unregister_key(
+			Origin::root(),
+			b"key".to_vec()
+		));
+		assert_eq!(StorageSnapshots::snapshot(b"key", 1), None);
+		// Out of the current slots, so it's never read
+		assert!(child::exists(&StorageSnapshots::child_info(), &stale));
+		// Snapshots of the key sharing the same bytes are kept
+		assert!(child::exists(
+			&StorageSnapshots::child_info(),
+			&StorageSnapshots::snapshot_key(b"key2", 1)
+		));
+	});
+}
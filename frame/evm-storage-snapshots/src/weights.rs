@@ -0,0 +1,79 @@
+//! Weights for pallet_evm_storage_snapshots
+//!
+//! PLACEHOLDERS: these values are estimates, not benchmark results. They are to be replaced by
+//! the output of
+//!
+//! ```text
+//! ./scripts/benchmark.sh pallet_evm_storage_snapshots '*'
+//! ```
+//!
+//! run with the template node built in release mode with the `runtime-benchmarks` feature.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_evm_storage_snapshots.
+pub trait WeightInfo {
+	fn on_finalize(k: u32) -> Weight;
+	fn register_key() -> Weight;
+	fn unregister_key(r: u32) -> Weight;
+}
+
+/// Estimated weights for pallet_evm_storage_snapshots, to be replaced by the benchmark results.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: EvmStorageSnapshots SnapshotKeys (r:1 w:0)
+	// Storage: unknown [0x...] (r:1 w:0)
+	// Storage: :child_storage:default:evm-storage-snapshots (r:0 w:1)
+	fn on_finalize(k: u32) -> Weight {
+		Weight::from_ref_time(3_112_000 as u64)
+			.saturating_add(Weight::from_ref_time(6_540_000 as u64).saturating_mul(k as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(k as u64)))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(k as u64)))
+	}
+	// Storage: EvmStorageSnapshots SnapshotKeys (r:1 w:1)
+	fn register_key() -> Weight {
+		Weight::from_ref_time(17_270_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: EvmStorageSnapshots SnapshotKeys (r:1 w:1)
+	// Storage: :child_storage:default:evm-storage-snapshots (r:0 w:1)
+	fn unregister_key(r: u32) -> Weight {
+		Weight::from_ref_time(16_034_000 as u64)
+			.saturating_add(Weight::from_ref_time(1_425_000 as u64).saturating_mul(r as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(r as u64)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn on_finalize(k: u32) -> Weight {
+		Weight::from_ref_time(3_112_000 as u64)
+			.saturating_add(Weight::from_ref_time(6_540_000 as u64).saturating_mul(k as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(k as u64)))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(k as u64)))
+	}
+	fn register_key() -> Weight {
+		Weight::from_ref_time(17_270_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	fn unregister_key(r: u32) -> Weight {
+		Weight::from_ref_time(16_034_000 as u64)
+			.saturating_add(Weight::from_ref_time(1_425_000 as u64).saturating_mul(r as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(r as u64)))
+	}
+}
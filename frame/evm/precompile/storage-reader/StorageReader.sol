@@ -0,0 +1,31 @@
+pragma solidity ^0.8.2;
+
+/// Solidity interface of the `RawStorageReader` precompile (`0x0000000000000000000000000000000000000007` in the template runtime).
+/// The precompile also accepts SCALE encoded input, see `RawStorageReaderInput`.
+interface RawStorageReader {
+	/// Reads the value stored under the raw storage `key`.
+	/// Zero `offset` or `len` stand for the absent param, i.e. `len` equal to zero reads the value till its end.
+	/// Returns `false` along with empty bytes if the value is absent.
+	function readRaw(
+		bytes calldata key,
+		uint32 offset,
+		uint32 len
+	) external view returns (bool present, bytes memory value);
+}
+
+/// Solidity interface of the `MetaStorageReader` precompile (`0x0000000000000000000000000000000000000006` in the template runtime).
+/// The precompile also accepts SCALE encoded input, see `MetaStorageReaderInput`.
+interface MetaStorageReader {
+	/// Reads SCALE encoded value of the `entry` storage member of the `pallet` addressed by `keys`.
+	/// Each of `keys` has to be SCALE encoded, empty `keys` are used to access `Plain` entries.
+	/// Keys are hashed according to the storage entry metadata.
+	/// Zero `offset` or `len` stand for the absent param, i.e. `len` equal to zero reads the value till its end.
+	/// Default value is returned for absent members of the entries with `ValueQuery`.
+	function readMeta(
+		string calldata pallet,
+		string calldata entry,
+		bytes[] calldata keys,
+		uint32 offset,
+		uint32 len
+	) external view returns (bool present, bytes memory value);
+}
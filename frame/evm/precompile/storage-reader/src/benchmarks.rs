@@ -0,0 +1,84 @@
+#![cfg(feature = "runtime-benchmarks")]
+
+//! Benchmarking
+use crate::{
+	meta_storage_reader::{
+		find_entry_metadata, utils::hash_bytes_with, CachedStorageMetadata,
+		PortablePalletStorageMetadata, PortableStorageEntryMetadata, RuntimeMetadataSource,
+		RuntimeStorageMetadata, StoreStorageMetadata,
+	},
+	raw_storage_reader::RawStorageReader,
+};
+use frame_benchmarking::benchmarks;
+use frame_metadata::{StorageEntryModifier, StorageEntryType, StorageHasher};
+use frame_support::traits::OnRuntimeUpgrade;
+use sp_std::prelude::*;
+
+/// Max length of the value read by the `raw_read` benchmark.
+const MAX_VALUE_LEN: u32 = 64 * 1024;
+/// Max amount of the pallet storage entries used by the `entry_metadata_lookup` benchmark.
+const MAX_ENTRIES: u32 = 512;
+
+fn entry_metadata(name: &'static str) -> PortableStorageEntryMetadata {
+	PortableStorageEntryMetadata {
+		name: name.into(),
+		modifier: StorageEntryModifier::Optional,
+		ty: StorageEntryType::Plain(0u32.into()),
+		default: vec![],
+		docs: vec![],
+	}
+}
+
+pub struct Pallet<T: pallet_evm::Config>(pallet_evm::Pallet<T>);
+
+pub trait Config: pallet_evm::Config + RuntimeMetadataSource {}
+
+benchmarks! {
+	blake2_128 {
+		let l in 0..1000;
+		let data: Vec<_> = (0..l).map(|i| i as u8).collect();
+	}: { hash_bytes_with(&data, &StorageHasher::Blake2_128) }
+	blake2_256 {
+		let l in 0..1000;
+		let data: Vec<_> = (0..l).map(|i| i as u8).collect();
+	}: { hash_bytes_with(&data, &StorageHasher::Blake2_256) }
+	blake2_128_concat {
+		let l in 0..1000;
+		let data: Vec<_> = (0..l).map(|i| i as u8).collect();
+	}: { hash_bytes_with(&data, &StorageHasher::Blake2_128Concat) }
+	twox_128 {
+		let l in 0..1000;
+		let data: Vec<_> = (0..l).map(|i| i as u8).collect();
+	}: { hash_bytes_with(&data, &StorageHasher::Twox128) }
+	twox_256 {
+		let l in 0..1000;
+		let data: Vec<_> = (0..l).map(|i| i as u8).collect();
+	}: { hash_bytes_with(&data, &StorageHasher::Twox256) }
+	twox_64_concat {
+		let l in 0..1000;
+		let data: Vec<_> = (0..l).map(|i| i as u8).collect();
+	}: { hash_bytes_with(&data, &StorageHasher::Twox64Concat) }
+	identity {
+		let l in 0..1000;
+		let data: Vec<_> = (0..l).map(|i| i as u8).collect();
+	}: { hash_bytes_with(&data, &StorageHasher::Identity) }
+	raw_read {
+		let l in 0..MAX_VALUE_LEN;
+		let key = b"storage_reader:raw_read".to_vec();
+		sp_io::storage::set(&key, &vec![1; l as usize]);
+	}: { RawStorageReader::<T>::read(&key) }
+	entry_metadata_lookup {
+		let e in 1..MAX_ENTRIES;
+		// Searched entry is the last one
+		let entries = (1..e)
+			.map(|_| entry_metadata("Entry"))
+			.chain(Some(entry_metadata("Target")))
+			.collect();
+		let pallet_meta = PortablePalletStorageMetadata { prefix: "Pallet".into(), entries };
+	}: { find_entry_metadata(pallet_meta.clone(), "Target") }
+	runtime_metadata_build {
+	}: { CachedStorageMetadata::from_runtime_metadata(0, T::runtime_metadata()) }
+	stored_storage_metadata_read {
+		StoreStorageMetadata::<T>::on_runtime_upgrade();
+	}: { RuntimeStorageMetadata::<T>::storage_metadata().unwrap() }
+}
@@ -1,5 +1,7 @@
 pub mod output;
 pub mod params;
+pub mod proof;
+pub mod solidity;
 
 /// Ensures that caller has enough gas, producing `ExitError::OutOfGas` in case of failure.
 #[macro_export]
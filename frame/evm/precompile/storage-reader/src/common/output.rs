@@ -1,4 +1,5 @@
 use bytes::Bytes;
+use codec::{Decode, Encode};
 use sp_std::prelude::*;
 
 use super::params::Params;
@@ -90,4 +91,25 @@ impl OutputStorageValue {
 
 		OutputStorageValue(raw)
 	}
+
+	/// Encodes given values as a length-prefixed list.
+	/// The list length is compact encoded and followed by each value encoded using `encode_to_bytes`
+	/// and prefixed with its compact encoded length.
+	pub fn encode_list_to_bytes(values: &[Self]) -> Vec<u8> {
+		values
+			.iter()
+			.map(Self::encode_to_bytes)
+			.collect::<Vec<_>>()
+			.encode()
+	}
+
+	/// Decodes list of `Self` encoded using `encode_list_to_bytes`.
+	pub fn decode_list_from_bytes(mut bytes: &[u8]) -> Result<Vec<Self>, codec::Error> {
+		let encoded_values = Vec::<Vec<u8>>::decode(&mut bytes)?;
+
+		Ok(encoded_values
+			.iter()
+			.map(|bytes| Self::decode_from_bytes(bytes))
+			.collect())
+	}
 }
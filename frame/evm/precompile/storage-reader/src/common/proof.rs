@@ -0,0 +1,49 @@
+use codec::{Decode, Encode};
+use frame_support::weights::Weight;
+use sp_core::{Blake2Hasher, H256};
+use sp_std::prelude::*;
+use sp_trie::LayoutV1;
+
+use super::output::RawStorageValue;
+use crate::weights::WeightInfo;
+
+/// Storage proof supplied along with the key to read the value from the proof instead of the storage.
+/// The value is verified against the supplied state root, so it can be trusted as long as the root is.
+///
+/// Proofs can't be generated inside the runtime, so they have to be produced off-chain
+/// (for ex. using the `state_getReadProof` RPC). Trie nodes are expected to be hashed using `blake2_256`.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct StorageProof {
+	/// State root the proof was generated against.
+	pub state_root: H256,
+	/// Encoded trie nodes.
+	pub nodes: Vec<Vec<u8>>,
+}
+
+impl StorageProof {
+	/// Constructs `StorageProof` with supplied state root and encoded trie nodes.
+	pub fn new(state_root: H256, nodes: impl IntoIterator<Item = Vec<u8>>) -> Self {
+		Self {
+			state_root,
+			nodes: nodes.into_iter().collect(),
+		}
+	}
+
+	/// Reads the value stored under the raw key verifying it against the state root.
+	/// Returns `None` if the proof doesn't contain all of the trie nodes required to reach the key.
+	pub fn read(&self, raw_key: &[u8]) -> Option<RawStorageValue> {
+		let db =
+			sp_trie::StorageProof::new(self.nodes.iter().cloned()).into_memory_db::<Blake2Hasher>();
+
+		sp_trie::read_trie_value::<LayoutV1<Blake2Hasher>, _>(&db, &self.state_root, raw_key)
+			.ok()
+			.map(|value| value.map(Into::into).into())
+	}
+
+	/// Weight of the proof verification which requires hashing each of the trie nodes.
+	pub fn verification_weight<W: WeightInfo>(&self) -> Weight {
+		self.nodes.iter().fold(Weight::zero(), |weight, node| {
+			weight.saturating_add(W::blake2_256(node.len() as u32))
+		})
+	}
+}
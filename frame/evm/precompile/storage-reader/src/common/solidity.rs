@@ -0,0 +1,153 @@
+use codec::Decode;
+use sp_std::prelude::*;
+
+use super::{output::OutputStorageValue, params::Params};
+use crate::meta_storage_reader::abi::{encode_params, Token, WORD_SIZE};
+
+/// Encoding of the precompile input and output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+	/// SCALE encoded input, output encoded using `OutputStorageValue::encode_to_bytes`.
+	Scale,
+	/// Solidity ABI encoded call (selector followed by the arguments), output encoded as `(bool present, bytes value)`.
+	Solidity,
+}
+
+/// Computes selector of the Solidity function with supplied signature (for ex. `readRaw(bytes,uint32,uint32)`).
+pub fn selector(signature: &str) -> [u8; 4] {
+	let hash = sp_io::hashing::keccak_256(signature.as_bytes());
+
+	[hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Decodes input as the Solidity call using supplied function if it's recognized, otherwise as SCALE.
+pub fn decode_input<T: Decode>(
+	mut input: &[u8],
+	decode_solidity: impl FnOnce(&[u8]) -> Option<T>,
+) -> Result<(T, Encoding), codec::Error> {
+	if let Some(decoded) = decode_solidity(input) {
+		return Ok((decoded, Encoding::Solidity));
+	}
+
+	T::decode(&mut input).map(|decoded| (decoded, Encoding::Scale))
+}
+
+/// Encodes output value using supplied encoding.
+pub fn encode_output(value: &OutputStorageValue, encoding: Encoding) -> Vec<u8> {
+	match encoding {
+		Encoding::Scale => value.encode_to_bytes(),
+		Encoding::Solidity => {
+			let item = value.clone().into_item();
+
+			encode_params(&[
+				Token::bool(item.is_some()),
+				Token::Bytes(item.map(|bytes| bytes.to_vec()).unwrap_or_default()),
+			])
+		}
+	}
+}
+
+/// Converts `uint32 offset, uint32 len` call arguments to `Params`. Zero values stand for the absent params.
+pub fn params_from_offset_and_len(offset: u32, len: u32) -> Params {
+	match (offset, len) {
+		(0, 0) => Params::None,
+		(offset, 0) => Params::Offset(offset),
+		(0, len) => Params::Len(len),
+		(offset, len) => Params::OffsetAndLen { offset, len },
+	}
+}
+
+/// Converts `Params` to the `uint32 offset, uint32 len` call arguments.
+pub fn params_to_offset_and_len(params: &Params) -> (u32, u32) {
+	match *params {
+		Params::None => (0, 0),
+		Params::Offset(offset) => (offset, 0),
+		Params::Len(len) => (0, len),
+		Params::OffsetAndLen { offset, len } => (offset, len),
+	}
+}
+
+/// Encodes Solidity call with supplied signature and arguments.
+pub fn encode_call(signature: &str, args: &[Token]) -> Vec<u8> {
+	let mut call = selector(signature).to_vec();
+	call.extend(encode_params(args));
+
+	call
+}
+
+/// Reads Solidity ABI encoded arguments of the call.
+pub struct CallArgs<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> CallArgs<'a> {
+	/// Strips the selector from the call data if it corresponds to supplied signature.
+	pub fn new(input: &'a [u8], signature: &str) -> Option<Self> {
+		let (call_selector, data) = (input.get(..4)?, input.get(4..)?);
+
+		(call_selector == selector(signature)).then(|| Self { data })
+	}
+
+	/// Reads `uint32` argument with the given index.
+	pub fn u32(&self, index: usize) -> Option<u32> {
+		let word = self.word_at(index.checked_mul(WORD_SIZE)?)?;
+		let (zeroes, value) = word.split_at(WORD_SIZE - 4);
+
+		zeroes
+			.iter()
+			.all(|byte| *byte == 0)
+			.then(|| u32::from_be_bytes(value.try_into().expect("Split at 4 bytes")))
+	}
+
+	/// Reads `bytes` or `string` argument with the given index.
+	pub fn bytes(&self, index: usize) -> Option<Vec<u8>> {
+		let offset = self.usize_at(index.checked_mul(WORD_SIZE)?)?;
+
+		self.bytes_at(offset)
+	}
+
+	/// Reads `bytes[]` argument with the given index.
+	pub fn bytes_array(&self, index: usize) -> Option<Vec<Vec<u8>>> {
+		let offset = self.usize_at(index.checked_mul(WORD_SIZE)?)?;
+		let len = self.usize_at(offset)?;
+		// Each item takes at least one word, so the length can't exceed the amount of words
+		if len > self.data.len() / WORD_SIZE {
+			return None;
+		}
+
+		let items_offset = offset.checked_add(WORD_SIZE)?;
+		(0..len)
+			.map(|item_idx| {
+				let item_offset = self.usize_at(items_offset + item_idx * WORD_SIZE)?;
+
+				self.bytes_at(items_offset.checked_add(item_offset)?)
+			})
+			.collect()
+	}
+
+	fn word_at(&self, offset: usize) -> Option<&'a [u8]> {
+		self.data.get(offset..offset.checked_add(WORD_SIZE)?)
+	}
+
+	fn usize_at(&self, offset: usize) -> Option<usize> {
+		let word = self.word_at(offset)?;
+		let (zeroes, value) = word.split_at(WORD_SIZE - 8);
+
+		if zeroes.iter().any(|byte| *byte != 0) {
+			return None;
+		}
+
+		u64::from_be_bytes(value.try_into().expect("Split at 8 bytes"))
+			.try_into()
+			.ok()
+	}
+
+	fn bytes_at(&self, offset: usize) -> Option<Vec<u8>> {
+		let len = self.usize_at(offset)?;
+		let start = offset.checked_add(WORD_SIZE)?;
+
+		self.data
+			.get(start..start.checked_add(len)?)
+			.map(<[u8]>::to_vec)
+	}
+}
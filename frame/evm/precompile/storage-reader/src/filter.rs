@@ -0,0 +1,117 @@
+use core::marker::PhantomData;
+
+use frame_support::traits::Get;
+use sp_core::{storage::well_known_keys, H160};
+use sp_io::hashing::twox_128;
+use sp_std::prelude::*;
+
+use crate::meta_storage_reader::{AllowList, DenyList, StorageEntryFilter, StorageEntryList};
+pub use pallet_evm::StorageReadFilter;
+
+/// List of the raw storage key prefixes.
+pub type KeyPrefixList = &'static [&'static [u8]];
+
+/// Well-known storage keys (runtime code, heap pages, etc.) along with the child storage prefix.
+pub struct WellKnownKeys;
+
+impl Get<KeyPrefixList> for WellKnownKeys {
+	fn get() -> KeyPrefixList {
+		&[
+			well_known_keys::CODE,
+			well_known_keys::HEAP_PAGES,
+			well_known_keys::EXTRINSIC_INDEX,
+			well_known_keys::CHILD_STORAGE_KEY_PREFIX,
+		]
+	}
+}
+
+/// Allows reading only raw keys starting with one of the listed prefixes.
+/// Pallet storage entries are checked by their hashed keys as well.
+pub struct AllowKeyPrefixes<L>(PhantomData<L>);
+
+impl<L: Get<KeyPrefixList>> StorageReadFilter for AllowKeyPrefixes<L> {
+	fn allows_raw_key(_caller: &H160, key: &[u8]) -> bool {
+		L::get().iter().any(|prefix| key.starts_with(prefix))
+	}
+
+	fn allows_pallet_entry(_caller: &H160, _pallet: &str, _entry: &str) -> bool {
+		true
+	}
+}
+
+/// Denies reading raw keys starting with any of the listed prefixes.
+/// Pallet storage entries are checked by their hashed keys as well.
+pub struct DenyKeyPrefixes<L>(PhantomData<L>);
+
+impl<L: Get<KeyPrefixList>> StorageReadFilter for DenyKeyPrefixes<L> {
+	fn allows_raw_key(_caller: &H160, key: &[u8]) -> bool {
+		!L::get().iter().any(|prefix| key.starts_with(prefix))
+	}
+
+	fn allows_pallet_entry(_caller: &H160, _pallet: &str, _entry: &str) -> bool {
+		true
+	}
+}
+
+/// Returns `true` if the raw key belongs to the listed pallet storage entry, i.e. starts with
+/// its hashed prefix (or with the hashed pallet prefix if the entry isn't specified).
+fn is_listed_key(list: StorageEntryList, key: &[u8]) -> bool {
+	list.iter().any(|(pallet, entry)| {
+		let pallet_prefix = twox_128(pallet.as_bytes());
+
+		key.starts_with(&pallet_prefix)
+			&& entry.map_or(true, |entry| {
+				key[pallet_prefix.len()..].starts_with(&twox_128(entry.as_bytes()))
+			})
+	})
+}
+
+/// Allows reading only listed pallet storage entries by their names.
+/// Raw keys are allowed only if they belong to the listed entries.
+impl<L: Get<StorageEntryList>> StorageReadFilter for AllowList<L> {
+	fn allows_raw_key(_caller: &H160, key: &[u8]) -> bool {
+		is_listed_key(L::get(), key)
+	}
+
+	fn allows_pallet_entry(_caller: &H160, pallet: &str, entry: &str) -> bool {
+		<Self as StorageEntryFilter>::allows_entry(pallet, entry)
+	}
+}
+
+/// Denies reading listed pallet storage entries by their names.
+/// Raw keys belonging to the listed entries are denied as well.
+impl<L: Get<StorageEntryList>> StorageReadFilter for DenyList<L> {
+	fn allows_raw_key(_caller: &H160, key: &[u8]) -> bool {
+		!is_listed_key(L::get(), key)
+	}
+
+	fn allows_pallet_entry(_caller: &H160, pallet: &str, entry: &str) -> bool {
+		<Self as StorageEntryFilter>::allows_entry(pallet, entry)
+	}
+}
+
+/// Allows reading storage only to the listed callers.
+pub struct AllowCallers<L>(PhantomData<L>);
+
+impl<L: Get<Vec<H160>>> StorageReadFilter for AllowCallers<L> {
+	fn allows_raw_key(caller: &H160, _key: &[u8]) -> bool {
+		L::get().contains(caller)
+	}
+
+	fn allows_pallet_entry(caller: &H160, _pallet: &str, _entry: &str) -> bool {
+		L::get().contains(caller)
+	}
+}
+
+/// Denies reading storage to the listed callers.
+pub struct DenyCallers<L>(PhantomData<L>);
+
+impl<L: Get<Vec<H160>>> StorageReadFilter for DenyCallers<L> {
+	fn allows_raw_key(caller: &H160, _key: &[u8]) -> bool {
+		!L::get().contains(caller)
+	}
+
+	fn allows_pallet_entry(caller: &H160, _pallet: &str, _entry: &str) -> bool {
+		!L::get().contains(caller)
+	}
+}
@@ -0,0 +1,31 @@
+use codec::{Decode, Encode};
+use sp_std::prelude::*;
+
+use crate::common::params::Params;
+
+/// Input for the `HistoricalStorageReader` precompile.
+#[derive(Encode, Decode, Debug, Clone)]
+pub struct HistoricalStorageReaderInput {
+	/// Raw key bytes. The key has to be registered in the `pallet_evm_storage_snapshots`.
+	pub key: Vec<u8>,
+	/// Number of the block the value should be read at (i.e. value at the end of this block).
+	#[codec(compact)]
+	pub block_number: u32,
+	/// Additional params (offset and length).
+	pub params: Params,
+}
+
+impl HistoricalStorageReaderInput {
+	/// Constructs `HistoricalStorageReaderInput` with supplied arguments.
+	///
+	/// - raw key bytes used to access storage entry
+	/// - number of the block to read the value at
+	/// - optional offset and length to be applied to the value bytes
+	pub fn new(key: impl Into<Vec<u8>>, block_number: u32, params: impl Into<Params>) -> Self {
+		Self {
+			key: key.into(),
+			block_number,
+			params: params.into(),
+		}
+	}
+}
@@ -0,0 +1,116 @@
+use core::marker::PhantomData;
+
+use codec::Decode;
+use evm::{ExitError, ExitSucceed};
+use fp_evm::{Precompile, PrecompileFailure};
+use pallet_evm::{PrecompileHandle, PrecompileOutput, PrecompileResult, StorageReadFilter};
+
+use frame_support::log::debug;
+use sp_std::borrow::Cow;
+
+pub mod input;
+#[cfg(test)]
+mod tests;
+
+use crate::{common::output::RawStorageValue, raw_storage_reader::RawStorageReader};
+use input::HistoricalStorageReaderInput;
+
+/// Precompile allowing to read the value which the raw storage key had at the end of one of the
+/// recent blocks. Values are taken from the snapshots kept by the `pallet_evm_storage_snapshots`,
+/// so only the registered keys can be read, and only within the configured retention window.
+/// Access to the key is checked using the configured `StorageReadFilter`.
+///
+/// Output:
+/// - 1 byte representing presence (1) or absence (0) of the value
+/// - raw value bytes (with applied offset and length)
+///
+/// Input:
+/// - compact encoded bytes len
+/// - raw key bytes
+/// - compact encoded block number
+/// - byte representing params: 0 - no additional params, 1 - offset, 2 - length, 3 - offset and length
+/// - corresponding compact encoded offset, length or offset followed by length
+#[derive(Default, Debug, Clone, Copy)]
+pub struct HistoricalStorageReader<T>(PhantomData<T>);
+
+impl<T> Precompile for HistoricalStorageReader<T>
+where
+	T: pallet_evm::Config + pallet_evm_storage_snapshots::Config,
+{
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let mut input = handle.input();
+		let target_gas = handle.gas_limit();
+
+		debug!(
+			"`HistoricalStorageReader` input: {:?}, target gas: {:?}",
+			input, target_gas
+		);
+
+		let HistoricalStorageReaderInput {
+			key,
+			block_number,
+			params,
+		} = HistoricalStorageReaderInput::decode(&mut input).map_err(Error::Decoding)?;
+
+		if !T::StorageReadFilter::allows_raw_key(&handle.context().caller, &key) {
+			return Err(Error::AccessDenied.into());
+		}
+
+		let base_gas_cost = RawStorageReader::<T>::base_gas_cost();
+		crate::ensure_enough_gas!(target_gas >= base_gas_cost);
+
+		let raw_output = Self::read(&key, block_number)?;
+
+		let total_gas_cost =
+			base_gas_cost.saturating_add(RawStorageReader::<T>::output_gas_cost(raw_output.len()));
+		crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+		let output = raw_output.apply_params(&params);
+
+		handle.record_cost(total_gas_cost)?;
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.encode_to_bytes(),
+		})
+	}
+}
+
+impl<T> HistoricalStorageReader<T>
+where
+	T: pallet_evm::Config + pallet_evm_storage_snapshots::Config,
+{
+	/// Reads the snapshot of the value stored under the raw key at the end of the given block.
+	fn read(raw_key: &[u8], block_number: u32) -> Result<RawStorageValue, Error> {
+		debug!(
+			"`HistoricalStorageReader` read: {:?} at {:?}",
+			raw_key, block_number
+		);
+
+		pallet_evm_storage_snapshots::Pallet::<T>::snapshot(raw_key, block_number.into())
+			.map(|value| value.map(Into::into).into())
+			.ok_or(Error::SnapshotNotAvailable)
+	}
+}
+
+/// An error produced by the `HistoricalStorageReader`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+	Decoding(codec::Error),
+	AccessDenied,
+	SnapshotNotAvailable,
+}
+
+impl From<Error> for PrecompileFailure {
+	fn from(err: Error) -> Self {
+		let msg = match err {
+			Error::Decoding(_) => "Failed to decode",
+			Error::AccessDenied => "Storage access denied",
+			Error::SnapshotNotAvailable => "Snapshot is not available",
+		};
+
+		PrecompileFailure::Error {
+			exit_status: ExitError::Other(Cow::Borrowed(msg)),
+		}
+	}
+}
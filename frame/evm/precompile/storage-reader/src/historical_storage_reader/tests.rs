@@ -0,0 +1,128 @@
+use codec::{Decode, Encode};
+use fp_evm::Precompile;
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize, StorageMap};
+use pallet_evm_test_vector_support::MockHandle;
+use sp_core::{H160, U256};
+
+use super::*;
+use crate::{
+	common::params::Params,
+	mock::{test_storage::*, *},
+};
+
+const DUMMY_CTX: evm::Context = evm::Context {
+	address: H160([0; 20]),
+	caller: H160([0; 20]),
+	apparent_value: U256([0; 4]),
+};
+
+pub fn ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+
+	t.into()
+}
+
+fn finalize_block() {
+	let block_number = System::block_number();
+
+	StorageSnapshots::on_finalize(block_number);
+	System::set_block_number(block_number + 1);
+}
+
+#[test]
+fn historical_access() {
+	ext().execute_with(|| {
+		let key = MapWithBlake2_128::hashed_key_for(Bytes::with_len(10));
+		let unregistered_key = MapWithBlake2_128::hashed_key_for(Bytes::with_len(11));
+		assert_ok!(StorageSnapshots::register_key(Origin::root(), key.clone()));
+
+		System::set_block_number(1);
+		for block_number in 1..=6 {
+			MapWithBlake2_128::insert(Bytes::with_len(10), Bytes::with_len(block_number));
+			MapWithBlake2_128::insert(Bytes::with_len(11), Bytes::with_len(block_number));
+			finalize_block();
+		}
+		MapWithBlake2_128::remove(Bytes::with_len(10));
+		finalize_block();
+
+		for block_number in 4..=6 {
+			let input = HistoricalStorageReaderInput::new(&key[..], block_number, Params::None);
+			let out = HistoricalStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+				DUMMY_CTX,
+			))
+			.unwrap();
+
+			assert_eq!(out.output[0], 1);
+			assert_eq!(
+				Bytes::decode(&mut &out.output[1..]).unwrap(),
+				Bytes::with_len(block_number as usize)
+			);
+		}
+
+		let input = HistoricalStorageReaderInput::new(&key[..], 5, Params::Offset(1));
+		let out = HistoricalStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(out.output, vec![1, 0, 1, 2, 3, 4]);
+
+		let input = HistoricalStorageReaderInput::new(&key[..], 7, Params::None);
+		let out = HistoricalStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(out.output, vec![0]);
+
+		// Out of the retention window
+		for block_number in [3, 8] {
+			let input = HistoricalStorageReaderInput::new(&key[..], block_number, Params::None);
+			assert_noop!(
+				HistoricalStorageReader::<Runtime>::execute(&mut MockHandle::new(
+					input.encode(),
+					Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+					DUMMY_CTX
+				)),
+				Error::SnapshotNotAvailable
+			);
+		}
+
+		let input = HistoricalStorageReaderInput::new(&unregistered_key[..], 6, Params::None);
+		assert_noop!(
+			HistoricalStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+				DUMMY_CTX
+			)),
+			Error::SnapshotNotAvailable
+		);
+
+		let input = HistoricalStorageReaderInput::new(&key[..], 6, Params::None);
+		assert_noop!(
+			HistoricalStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(RawStorageReader::<Runtime>::base_gas_cost()),
+				DUMMY_CTX
+			)),
+			evm::ExitError::OutOfGas
+		);
+		assert_noop!(
+			HistoricalStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+				evm::Context {
+					caller: H160::repeat_byte(0xde),
+					..DUMMY_CTX
+				}
+			)),
+			Error::AccessDenied
+		);
+	});
+}
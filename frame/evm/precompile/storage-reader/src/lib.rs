@@ -1,12 +1,23 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub use frame_metadata;
+pub use frame_support;
+pub use scale_info;
+pub use sp_std;
 
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarks;
 pub mod common;
+pub mod filter;
+pub mod historical_storage_reader;
 pub mod meta_storage_reader;
 mod mock;
 pub mod raw_storage_reader;
+pub mod storage_iterator;
+pub mod weights;
 
-pub use common::{output, params};
-pub use meta_storage_reader::MetaStorageReader;
+pub use common::{output, params, proof, solidity};
+pub use historical_storage_reader::HistoricalStorageReader;
+pub use meta_storage_reader::{MetaStorageBatchReader, MetaStorageReader};
 pub use raw_storage_reader::RawStorageReader;
+pub use storage_iterator::StorageIterator;
@@ -0,0 +1,395 @@
+use codec::{Compact, Decode};
+use frame_metadata::StorageEntryType;
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive};
+use sp_std::prelude::*;
+
+use super::PortableStorageEntryType;
+
+/// Max nesting level of the types which can be re-encoded.
+pub const MAX_DEPTH: u32 = 32;
+
+/// Solidity ABI word size in bytes.
+pub const WORD_SIZE: usize = 32;
+
+/// An error produced while re-encoding SCALE value as Solidity ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+	/// Type referenced by the metadata is missing from the registry.
+	TypeNotFound,
+	/// Type can't be represented as Solidity ABI.
+	UnsupportedType,
+	/// Value bytes don't correspond to the type.
+	InvalidValue,
+	/// Type nesting level exceeds `MAX_DEPTH`.
+	TooDeep,
+	/// Amount of the visited nodes exceeds the supplied limit.
+	LimitExceeded,
+}
+
+/// Solidity ABI value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+	/// Single word value: `uintN`, `intN`, `bool` or `bytesN`.
+	Word([u8; WORD_SIZE]),
+	/// Dynamic `bytes` or `string`.
+	Bytes(Vec<u8>),
+	/// Static array `T[N]`.
+	FixedArray(Vec<Token>),
+	/// Dynamic array `T[]`.
+	Array(Vec<Token>),
+	/// Tuple `(T1, T2, ..)`.
+	Tuple(Vec<Token>),
+}
+
+impl Token {
+	/// Constructs unsigned integer word.
+	pub fn uint(value: u128) -> Self {
+		let mut word = [0; WORD_SIZE];
+		word[WORD_SIZE - 16..].copy_from_slice(&value.to_be_bytes());
+
+		Self::Word(word)
+	}
+
+	/// Constructs signed integer word from the big endian two's complement bytes.
+	pub fn int(be_bytes: &[u8]) -> Self {
+		let negative = be_bytes.first().map_or(false, |byte| byte & 0x80 != 0);
+		let mut word = [if negative { 0xff } else { 0 }; WORD_SIZE];
+		word[WORD_SIZE - be_bytes.len()..].copy_from_slice(be_bytes);
+
+		Self::Word(word)
+	}
+
+	/// Constructs `bool` word.
+	pub fn bool(value: bool) -> Self {
+		Self::uint(value as u128)
+	}
+
+	/// Returns `true` if the value has dynamic size and is referenced by an offset.
+	pub fn is_dynamic(&self) -> bool {
+		match self {
+			Self::Word(_) => false,
+			Self::Bytes(_) | Self::Array(_) => true,
+			Self::FixedArray(tokens) | Self::Tuple(tokens) => tokens.iter().any(Self::is_dynamic),
+		}
+	}
+
+	/// Encodes `self` as `abi.encode(value)` does.
+	pub fn encode(&self) -> Vec<u8> {
+		encode_params(sp_std::slice::from_ref(self))
+	}
+
+	fn head_len(&self) -> usize {
+		match self {
+			Self::Word(_) => WORD_SIZE,
+			_ if self.is_dynamic() => WORD_SIZE,
+			Self::FixedArray(tokens) | Self::Tuple(tokens) => {
+				tokens.iter().map(Self::head_len).sum()
+			}
+			Self::Bytes(_) | Self::Array(_) => unreachable!("Always dynamic"),
+		}
+	}
+
+	fn encode_to(&self, output: &mut Vec<u8>) {
+		match self {
+			Self::Word(word) => output.extend_from_slice(word),
+			Self::Bytes(bytes) => {
+				output.extend_from_slice(&word_from_len(bytes.len()));
+				output.extend_from_slice(bytes);
+				let padding = (WORD_SIZE - bytes.len() % WORD_SIZE) % WORD_SIZE;
+				output.resize(output.len() + padding, 0);
+			}
+			Self::FixedArray(tokens) | Self::Tuple(tokens) => encode_sequence(tokens, output),
+			Self::Array(tokens) => {
+				output.extend_from_slice(&word_from_len(tokens.len()));
+				encode_sequence(tokens, output);
+			}
+		}
+	}
+}
+
+/// Encodes given tokens as `abi.encode(value1, value2, ..)` does.
+pub fn encode_params(tokens: &[Token]) -> Vec<u8> {
+	let mut output = Vec::new();
+	encode_sequence(tokens, &mut output);
+
+	output
+}
+
+/// Encodes given tokens using head/tail encoding.
+fn encode_sequence(tokens: &[Token], output: &mut Vec<u8>) {
+	let heads_len: usize = tokens.iter().map(Token::head_len).sum();
+	let mut tails = Vec::new();
+
+	for token in tokens {
+		if token.is_dynamic() {
+			output.extend_from_slice(&word_from_len(heads_len + tails.len()));
+			token.encode_to(&mut tails);
+		} else {
+			token.encode_to(output);
+		}
+	}
+
+	output.extend_from_slice(&tails);
+}
+
+fn word_from_len(len: usize) -> [u8; WORD_SIZE] {
+	match Token::uint(len as u128) {
+		Token::Word(word) => word,
+		_ => unreachable!("`Token::uint` always produces a word"),
+	}
+}
+
+/// Returns identifier of the storage entry value type in the registry supplied along with the metadata.
+pub fn value_type_id(entry_type: &PortableStorageEntryType) -> u32 {
+	match entry_type {
+		StorageEntryType::Plain(value) => value.id(),
+		StorageEntryType::Map { value, .. } => value.id(),
+	}
+}
+
+/// Re-encodes SCALE encoded values as Solidity ABI using the type registry.
+///
+/// - Primitives are represented as `bool`, `uintN`, `intN` and `string`, `char` becomes `uint32`
+/// - `Compact<T>` is represented as `uint256`
+/// - Fixed arrays of bytes up to 32 bytes long are represented as `bytesN`, longer ones as `bytes`
+/// - Other fixed arrays are represented as `T[N]`
+/// - Sequences of bytes are represented as `bytes`, other sequences as `T[]`
+/// - Structs and tuples are represented as tuples of their fields
+/// - Enums are represented as `(uint8 index, bytes payload)` where payload is `abi.encode(field1, field2, ..)` of the variant fields
+pub struct AbiEncoder<'a> {
+	registry: &'a PortableRegistry,
+	remaining_nodes: u64,
+	visited_nodes: u64,
+}
+
+impl<'a> AbiEncoder<'a> {
+	/// Instantiates new encoder which will visit at most `max_nodes` type nodes.
+	pub fn new(registry: &'a PortableRegistry, max_nodes: u64) -> Self {
+		Self {
+			registry,
+			remaining_nodes: max_nodes,
+			visited_nodes: 0,
+		}
+	}
+
+	/// Amount of type nodes visited so far.
+	pub fn visited_nodes(&self) -> u64 {
+		self.visited_nodes
+	}
+
+	/// Encodes optional value of the given type as `abi.encode(bool present, T value)`.
+	/// Absent value is represented by the zeroed value of the given type.
+	pub fn encode_value(&mut self, type_id: u32, value: Option<&[u8]>) -> Result<Vec<u8>, Error> {
+		let (present, token) = match value {
+			Some(mut bytes) => {
+				let token = self.decode(type_id, &mut bytes, 0)?;
+				if !bytes.is_empty() {
+					return Err(Error::InvalidValue);
+				}
+
+				(true, token)
+			}
+			None => (false, self.zeroed(type_id, 0)?),
+		};
+
+		Ok(encode_params(&[Token::bool(present), token]))
+	}
+
+	/// Decodes SCALE value of the given type into the ABI token.
+	pub fn decode(&mut self, type_id: u32, input: &mut &[u8], depth: u32) -> Result<Token, Error> {
+		let type_def = self.visit(type_id, depth)?;
+
+		let token = match type_def {
+			TypeDef::Primitive(primitive) => decode_primitive(primitive, input)?,
+			TypeDef::Compact(_) => Token::uint(decode_scale::<Compact<u128>>(input)?.0),
+			TypeDef::Array(array) if self.is_byte(array.type_param().id())? => {
+				let bytes = take(input, array.len() as usize)?;
+				if bytes.len() <= WORD_SIZE {
+					let mut word = [0; WORD_SIZE];
+					word[..bytes.len()].copy_from_slice(bytes);
+
+					Token::Word(word)
+				} else {
+					Token::Bytes(bytes.to_vec())
+				}
+			}
+			TypeDef::Array(array) => Token::FixedArray(self.decode_many(
+				(0..array.len()).map(|_| array.type_param().id()),
+				input,
+				depth,
+			)?),
+			TypeDef::Sequence(seq) => {
+				let len = decode_scale::<Compact<u32>>(input)?.0;
+
+				if self.is_byte(seq.type_param().id())? {
+					Token::Bytes(take(input, len as usize)?.to_vec())
+				} else {
+					Token::Array(self.decode_many(
+						(0..len).map(|_| seq.type_param().id()),
+						input,
+						depth,
+					)?)
+				}
+			}
+			TypeDef::Composite(composite) => Token::Tuple(self.decode_many(
+				composite.fields().iter().map(|field| field.ty().id()),
+				input,
+				depth,
+			)?),
+			TypeDef::Tuple(tuple) => Token::Tuple(self.decode_many(
+				tuple.fields().iter().map(|ty| ty.id()),
+				input,
+				depth,
+			)?),
+			TypeDef::Variant(variant) => {
+				let index = decode_scale::<u8>(input)?;
+				let variant = variant
+					.variants()
+					.iter()
+					.find(|variant| variant.index() == index)
+					.ok_or(Error::InvalidValue)?;
+				let fields = self.decode_many(
+					variant.fields().iter().map(|field| field.ty().id()),
+					input,
+					depth,
+				)?;
+
+				Token::Tuple(vec![
+					Token::uint(index as u128),
+					Token::Bytes(encode_params(&fields)),
+				])
+			}
+			TypeDef::BitSequence(_) => return Err(Error::UnsupportedType),
+		};
+
+		Ok(token)
+	}
+
+	/// Produces zeroed ABI token for the given type.
+	pub fn zeroed(&mut self, type_id: u32, depth: u32) -> Result<Token, Error> {
+		let type_def = self.visit(type_id, depth)?;
+
+		let token = match type_def {
+			TypeDef::Primitive(TypeDefPrimitive::Str) => Token::Bytes(vec![]),
+			TypeDef::Primitive(_) | TypeDef::Compact(_) => Token::Word([0; WORD_SIZE]),
+			TypeDef::Array(array) if self.is_byte(array.type_param().id())? => {
+				if array.len() as usize <= WORD_SIZE {
+					Token::Word([0; WORD_SIZE])
+				} else {
+					Token::Bytes(vec![0; array.len() as usize])
+				}
+			}
+			TypeDef::Array(array) => Token::FixedArray(
+				(0..array.len())
+					.map(|_| self.zeroed(array.type_param().id(), depth + 1))
+					.collect::<Result<_, _>>()?,
+			),
+			TypeDef::Sequence(seq) => {
+				if self.is_byte(seq.type_param().id())? {
+					Token::Bytes(vec![])
+				} else {
+					Token::Array(vec![])
+				}
+			}
+			TypeDef::Composite(composite) => Token::Tuple(
+				composite
+					.fields()
+					.iter()
+					.map(|field| self.zeroed(field.ty().id(), depth + 1))
+					.collect::<Result<_, _>>()?,
+			),
+			TypeDef::Tuple(tuple) => Token::Tuple(
+				tuple
+					.fields()
+					.iter()
+					.map(|ty| self.zeroed(ty.id(), depth + 1))
+					.collect::<Result<_, _>>()?,
+			),
+			TypeDef::Variant(_) => Token::Tuple(vec![Token::uint(0), Token::Bytes(vec![])]),
+			TypeDef::BitSequence(_) => return Err(Error::UnsupportedType),
+		};
+
+		Ok(token)
+	}
+
+	fn decode_many(
+		&mut self,
+		type_ids: impl Iterator<Item = u32>,
+		input: &mut &[u8],
+		depth: u32,
+	) -> Result<Vec<Token>, Error> {
+		type_ids
+			.map(|type_id| self.decode(type_id, input, depth + 1))
+			.collect()
+	}
+
+	/// Resolves type definition while accounting for the visited node.
+	fn visit(&mut self, type_id: u32, depth: u32) -> Result<&'a TypeDef<PortableForm>, Error> {
+		if depth > MAX_DEPTH {
+			return Err(Error::TooDeep);
+		}
+		self.remaining_nodes = self
+			.remaining_nodes
+			.checked_sub(1)
+			.ok_or(Error::LimitExceeded)?;
+		self.visited_nodes += 1;
+
+		self.registry
+			.resolve(type_id)
+			.map(|ty| ty.type_def())
+			.ok_or(Error::TypeNotFound)
+	}
+
+	fn is_byte(&self, type_id: u32) -> Result<bool, Error> {
+		self.registry
+			.resolve(type_id)
+			.map(|ty| matches!(ty.type_def(), TypeDef::Primitive(TypeDefPrimitive::U8)))
+			.ok_or(Error::TypeNotFound)
+	}
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Token, Error> {
+	let token = match primitive {
+		TypeDefPrimitive::Bool => Token::bool(decode_scale(input)?),
+		TypeDefPrimitive::Char => Token::uint(decode_scale::<u32>(input)? as u128),
+		TypeDefPrimitive::Str => Token::Bytes(decode_scale(input)?),
+		TypeDefPrimitive::U8 => Token::uint(decode_scale::<u8>(input)? as u128),
+		TypeDefPrimitive::U16 => Token::uint(decode_scale::<u16>(input)? as u128),
+		TypeDefPrimitive::U32 => Token::uint(decode_scale::<u32>(input)? as u128),
+		TypeDefPrimitive::U64 => Token::uint(decode_scale::<u64>(input)? as u128),
+		TypeDefPrimitive::U128 => Token::uint(decode_scale(input)?),
+		TypeDefPrimitive::U256 => {
+			let mut word: [u8; WORD_SIZE] = decode_scale(input)?;
+			word.reverse();
+
+			Token::Word(word)
+		}
+		TypeDefPrimitive::I8 => Token::int(&decode_scale::<i8>(input)?.to_be_bytes()),
+		TypeDefPrimitive::I16 => Token::int(&decode_scale::<i16>(input)?.to_be_bytes()),
+		TypeDefPrimitive::I32 => Token::int(&decode_scale::<i32>(input)?.to_be_bytes()),
+		TypeDefPrimitive::I64 => Token::int(&decode_scale::<i64>(input)?.to_be_bytes()),
+		TypeDefPrimitive::I128 => Token::int(&decode_scale::<i128>(input)?.to_be_bytes()),
+		TypeDefPrimitive::I256 => {
+			let mut word: [u8; WORD_SIZE] = decode_scale(input)?;
+			word.reverse();
+
+			Token::Word(word)
+		}
+	};
+
+	Ok(token)
+}
+
+fn decode_scale<T: Decode>(input: &mut &[u8]) -> Result<T, Error> {
+	T::decode(input).map_err(|_| Error::InvalidValue)
+}
+
+fn take<'b>(input: &mut &'b [u8], len: usize) -> Result<&'b [u8], Error> {
+	if input.len() < len {
+		return Err(Error::InvalidValue);
+	}
+	let (bytes, rest) = input.split_at(len);
+	*input = rest;
+
+	Ok(bytes)
+}
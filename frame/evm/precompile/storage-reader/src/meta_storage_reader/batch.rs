@@ -0,0 +1,180 @@
+use core::marker::PhantomData;
+
+use evm::ExitSucceed;
+use fp_evm::Precompile;
+use frame_support::log::debug;
+use pallet_evm::{
+	GasWeightMapping, PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileResult,
+	StorageReadFilter,
+};
+use sp_std::prelude::*;
+
+use codec::Decode;
+use frame_metadata::StorageEntryModifier;
+
+use super::{
+	input::{MetaStorageBatchReaderInput, Params},
+	key::{pallet_entry_prefix, pallet_entry_prefix_hashing_weight, HashableKey, Key},
+	lookup_entry_metadata, with_storage_metadata_cache, Error, PalletStorageMetadataProvider,
+	PortableStorageEntryType,
+};
+use crate::{common::output::OutputStorageValue, raw_storage_reader::RawStorageReader};
+
+/// Precompile allows reading multiple members of the same pallet storage entry in a single call.
+/// Behaves as `MetaStorageReader` called for each of the supplied keys, but the pallet storage entry
+/// prefix is hashed (and charged) only once.
+/// Access to the pallet storage entry is checked once, while each of the hashed keys is checked separately.
+///
+/// Output:
+/// - compact encoded amount of values
+/// - each value represented as its compact encoded length followed by:
+///     - 1 byte representing presence (1) or absence (0) of the value
+///     - raw value bytes (with applied offset and length)
+///
+/// Input:
+/// - compact encoded length of the pallet name (raw bytes)
+/// - pallet name as raw bytes
+/// - compact encoded length of the pallet's storage member name (raw bytes)
+/// - pallet's storage member name as raw bytes
+/// - compact encoded amount of keys
+/// - each key encoded the same way as for `MetaStorageReader` followed by its params
+///
+/// # Example call
+///
+/// ```rust
+///  # use codec::{Encode, Decode};
+///  # use pallet_evm_precompile_storage_reader::meta_storage_reader::{key::MapKey, input::MetaStorageBatchReaderInput};
+///  # use pallet_evm_precompile_storage_reader::params::Params;
+///  # let input =
+///  MetaStorageBatchReaderInput::new(
+///		"TestStorage",
+///		"MapWithBlake2_128",
+///		vec![
+///			(MapKey::new_single(vec![0u8, 1, 2]), Params::None),
+///			(MapKey::new_single(vec![3u8]), Params::Len(1)),
+///		],
+///	 );
+///  // will be encoded as the following sequence of bytes
+///  # assert_eq!(
+///  vec![
+///     44, // - length("TestStorage") << 2
+///     84, 101, 115, 116, 83, 116, 111, 114, 97, 103, 101, // - "TestStorage" as bytes (UTF-8)
+///     68, // - length("MapWithBlake2_128") << 2
+///     77, 97, 112, 87, 105, 116, 104, 66, 108, 97, 107, 101, 50, 95, 49, 50, 56, // - "MapWithBlake2_128" as bytes (UTF-8)
+///     8, // - amount of keys (2 << 2)
+///     1, // - Map key will be provided
+///     16, // - length(key bytes) << 2
+///     12, 0, 1, 2, // - Key bytes: length(bytes) << 2 followed by bytes
+///     0, // - No params will be used
+///     1, // - Map key will be provided
+///     8, // - length(key bytes) << 2
+///     4, 3, // - Key bytes: length(bytes) << 2 followed by bytes
+///     2, // - Length param will be used
+///     4 // - Length value (1 << 2)
+///   ]
+///  # , input.encode());
+/// ```
+///
+#[derive(Default, Debug, Clone, Copy)]
+pub struct MetaStorageBatchReader<T>(PhantomData<T>);
+
+impl<T: pallet_evm::Config + PalletStorageMetadataProvider> Precompile
+	for MetaStorageBatchReader<T>
+{
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		with_storage_metadata_cache(|| Self::execute_cached(handle))
+	}
+}
+
+impl<T: pallet_evm::Config + PalletStorageMetadataProvider> MetaStorageBatchReader<T> {
+	/// Executes the precompile sharing the runtime metadata between lookups.
+	fn execute_cached(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let mut input = handle.input();
+		let target_gas = handle.gas_limit();
+
+		debug!(
+			"`MetaStorageBatchReader` input: {:?}, target gas: {:?}",
+			input, target_gas
+		);
+
+		let MetaStorageBatchReaderInput {
+			pallet,
+			entry,
+			keys,
+		} = MetaStorageBatchReaderInput::decode(&mut input).map_err(Error::Decoding)?;
+
+		debug!(
+			"`MetaStorageBatchReader` decoded input: pallet = {:?}, entry = {:?}, keys = {:?}",
+			pallet, entry, keys
+		);
+
+		let caller = handle.context().caller;
+		if !T::StorageReadFilter::allows_pallet_entry(&caller, &pallet, &entry) {
+			return Err(Error::AccessDenied.into());
+		}
+
+		let (entry_meta, lookup_gas_cost) =
+			lookup_entry_metadata::<T>(&pallet, &entry).ok_or(Error::PalletStorageEntryNotFound)?;
+		let default_bytes =
+			(entry_meta.modifier == StorageEntryModifier::Default).then(|| entry_meta.default);
+
+		let base_gas_cost = Self::base_gas_cost(&pallet, &entry, &keys, &entry_meta.ty)?
+			.saturating_add(lookup_gas_cost);
+		crate::ensure_enough_gas!(target_gas >= base_gas_cost);
+
+		let prefix = pallet_entry_prefix(&pallet, &entry);
+		let mut outputs = Vec::with_capacity(keys.len());
+		let mut total_gas_cost = base_gas_cost;
+
+		for (key, params) in keys {
+			let storage_key = key
+				.to_prefixed_storage_key(&prefix, &entry_meta.ty)
+				.ok_or(Error::InvalidKey)?;
+			if !T::StorageReadFilter::allows_raw_key(&caller, &storage_key) {
+				return Err(Error::AccessDenied.into());
+			}
+
+			let raw_output =
+				RawStorageReader::<T>::read(&storage_key).or_default(default_bytes.clone());
+
+			total_gas_cost = total_gas_cost.saturating_add(Self::output_gas_cost(raw_output.len()));
+			crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+			outputs.push(raw_output.apply_params(&params));
+		}
+
+		handle.record_cost(total_gas_cost)?;
+
+		Ok(PrecompileOutput {
+			output: OutputStorageValue::encode_list_to_bytes(&outputs),
+			exit_status: ExitSucceed::Returned,
+		})
+	}
+}
+
+impl<T: pallet_evm::Config> MetaStorageBatchReader<T> {
+	/// Base gas cost for performing read operations for all supplied keys.
+	/// Pallet storage entry prefix hashing is charged only once.
+	pub(super) fn base_gas_cost(
+		pallet: &str,
+		entry: &str,
+		keys: &[(Key, Params)],
+		entry_type: &PortableStorageEntryType,
+	) -> Result<u64, PrecompileFailure> {
+		let prefix_hashing_gas =
+			T::GasWeightMapping::weight_to_gas(pallet_entry_prefix_hashing_weight(pallet, entry));
+
+		keys.iter().try_fold(prefix_hashing_gas, |acc, (key, _)| {
+			let key_hashing_weight = key.hashing_weight(entry_type).ok_or(Error::InvalidKey)?;
+
+			Ok(acc
+				.saturating_add(T::GasWeightMapping::weight_to_gas(key_hashing_weight))
+				.saturating_add(RawStorageReader::<T>::base_gas_cost()))
+		})
+	}
+
+	/// Gas cost based on a single output length.
+	pub(super) fn output_gas_cost(output_len: usize) -> u64 {
+		RawStorageReader::<T>::output_gas_cost(output_len)
+	}
+}
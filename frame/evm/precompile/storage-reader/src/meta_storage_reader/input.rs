@@ -1,11 +1,37 @@
 use codec::{Decode, Encode};
-use sp_std::borrow::Cow;
+use sp_std::{borrow::Cow, prelude::*, string::String};
 
-use super::key::Key;
+use super::{abi::Token, key::Key};
 pub use crate::common::params::Params;
+use crate::common::{
+	proof::StorageProof,
+	solidity::{self, CallArgs},
+};
+
+/// Signature of the Solidity function accepted by the `MetaStorageReader` precompile.
+pub const READ_META_SIGNATURE: &str = "readMeta(string,string,bytes[],uint32,uint32)";
+
+/// Format of the value produced by the `MetaStorageReader`.
+#[derive(Debug, Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+	/// Raw SCALE encoded value bytes prefixed with the presence byte.
+	Scale,
+	/// Value re-encoded as Solidity ABI `(bool present, T value)` using the storage entry type metadata.
+	Abi,
+	/// Hashed storage key (prefix in case of a partial key) prefixed with the presence byte.
+	HashedPrefix,
+}
+
+impl Default for OutputFormat {
+	fn default() -> Self {
+		Self::Scale
+	}
+}
 
 /// Input for `MetaStorageReader` precompile.
-#[derive(Debug, Encode, Decode, Clone)]
+/// Output format is encoded only if it differs from the default one (`OutputFormat::Scale`)
+/// or the storage proof is supplied. Storage proof is encoded only if it's supplied.
+#[derive(Debug, Clone)]
 pub struct MetaStorageReaderInput<'a> {
 	/// Target pallet name (for ex. `System`)
 	pub pallet: Cow<'a, str>,
@@ -15,6 +41,53 @@ pub struct MetaStorageReaderInput<'a> {
 	pub key: Key,
 	/// Additional params (offset and length).
 	pub params: Params,
+	/// Format of the output value.
+	pub format: OutputFormat,
+	/// Optional proof to read the value from instead of the storage.
+	pub proof: Option<StorageProof>,
+}
+
+impl Encode for MetaStorageReaderInput<'_> {
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		self.pallet.encode_to(dest);
+		self.entry.encode_to(dest);
+		self.key.encode_to(dest);
+		self.params.encode_to(dest);
+
+		if self.format != OutputFormat::default() || self.proof.is_some() {
+			self.format.encode_to(dest);
+		}
+
+		if let Some(proof) = &self.proof {
+			proof.encode_to(dest);
+		}
+	}
+}
+
+impl Decode for MetaStorageReaderInput<'_> {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let pallet = Decode::decode(input)?;
+		let entry = Decode::decode(input)?;
+		let key = Decode::decode(input)?;
+		let params = Decode::decode(input)?;
+		let format = match input.remaining_len()? {
+			Some(0) => OutputFormat::default(),
+			_ => Decode::decode(input)?,
+		};
+		let proof = match input.remaining_len()? {
+			Some(0) => None,
+			_ => Some(Decode::decode(input)?),
+		};
+
+		Ok(Self {
+			pallet,
+			entry,
+			key,
+			params,
+			format,
+			proof,
+		})
+	}
 }
 
 impl<'a> MetaStorageReaderInput<'a> {
@@ -35,13 +108,102 @@ impl<'a> MetaStorageReaderInput<'a> {
 			entry: entry.into(),
 			key: key.into(),
 			params: params.into(),
+			format: OutputFormat::default(),
+			proof: None,
 		}
 	}
 
+	/// Replaces output format with the supplied one.
+	pub fn with_format(mut self, format: OutputFormat) -> Self {
+		self.format = format;
+		self
+	}
+
+	/// Sets the proof to read the value from instead of the storage.
+	pub fn with_proof(mut self, proof: StorageProof) -> Self {
+		self.proof = Some(proof);
+		self
+	}
+
 	/// Clones given input and replaces old key with the supplied key.
 	pub fn with_replaced_key(&self, key: impl Into<Key>) -> Self {
 		let mut new_self = self.clone();
 		new_self.key = key.into();
 		new_self
 	}
+
+	/// Decodes input from the `readMeta(string pallet, string entry, bytes[] keys, uint32 offset, uint32 len)`
+	/// Solidity call. Each of `keys` is a SCALE encoded key, empty `keys` stand for the `NoKey`.
+	/// Zero `offset` or `len` stand for the absent param.
+	/// Returns `None` if the input isn't a valid `readMeta` call.
+	pub fn decode_solidity(input: &[u8]) -> Option<Self> {
+		let args = CallArgs::new(input, READ_META_SIGNATURE)?;
+		let pallet = String::from_utf8(args.bytes(0)?).ok()?;
+		let entry = String::from_utf8(args.bytes(1)?).ok()?;
+
+		Some(Self::new(
+			pallet,
+			entry,
+			Key::from_encoded_keys(args.bytes_array(2)?),
+			solidity::params_from_offset_and_len(args.u32(3)?, args.u32(4)?),
+		))
+	}
+
+	/// Encodes input as the `readMeta(string pallet, string entry, bytes[] keys, uint32 offset, uint32 len)`
+	/// Solidity call. Output format and proof aren't encoded as the call always reads the storage
+	/// and produces `(bool present, bytes value)`.
+	pub fn encode_solidity(&self) -> Vec<u8> {
+		let (offset, len) = solidity::params_to_offset_and_len(&self.params);
+		let keys = self
+			.key
+			.encoded_keys()
+			.iter()
+			.cloned()
+			.map(Token::Bytes)
+			.collect();
+
+		solidity::encode_call(
+			READ_META_SIGNATURE,
+			&[
+				Token::Bytes(self.pallet.as_bytes().to_vec()),
+				Token::Bytes(self.entry.as_bytes().to_vec()),
+				Token::Array(keys),
+				Token::uint(offset.into()),
+				Token::uint(len.into()),
+			],
+		)
+	}
+}
+
+/// Input for `MetaStorageBatchReader` precompile.
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct MetaStorageBatchReaderInput<'a> {
+	/// Target pallet name (for ex. `System`)
+	pub pallet: Cow<'a, str>,
+	/// Target pallet storage entry (for ex. `Account`)
+	pub entry: Cow<'a, str>,
+	/// Keys used to access storage entry members each followed by its own params (offset and length).
+	pub keys: Vec<(Key, Params)>,
+}
+
+impl<'a> MetaStorageBatchReaderInput<'a> {
+	/// Constructs new `MetaStorageBatchReaderInput` with given arguments.
+	///
+	/// - pallet name (UTF-8)
+	/// - pallet storage entry name (UTF-8)
+	/// - keys for the storage entry members each with optional offset and length to be applied to the value bytes
+	pub fn new<K: Into<Key>, P: Into<Params>>(
+		pallet: impl Into<Cow<'a, str>>,
+		entry: impl Into<Cow<'a, str>>,
+		keys: impl IntoIterator<Item = (K, P)>,
+	) -> Self {
+		Self {
+			pallet: pallet.into(),
+			entry: entry.into(),
+			keys: keys
+				.into_iter()
+				.map(|(key, params)| (key.into(), params.into()))
+				.collect(),
+		}
+	}
 }
@@ -1,6 +1,9 @@
 use core::iter::once;
 
-use super::utils::{hash_bytes_with, hasher_weight};
+use super::{
+	utils::{hash_bytes_with, hasher_weight},
+	PortableStorageEntryType,
+};
 use codec::{Decode, Encode};
 use frame_metadata::{StorageEntryType, StorageHasher};
 use frame_support::{weights::Weight, StorageHasher as _, Twox128};
@@ -10,11 +13,20 @@ use sp_std::prelude::*;
 pub trait HashableKey {
 	/// Attempts to hash given key using supplied metadata.
 	/// Returns `None` if the key is incompatible with the given metadata.
-	fn to_hashed_key(&self, entry_type: &StorageEntryType) -> Option<Vec<u8>>;
+	fn to_hashed_key(&self, entry_type: &PortableStorageEntryType) -> Option<Vec<u8>>;
 
 	/// Attempts to calculate hashing weight for the given key using supplied metadata.
 	/// Returns `None` if the key is incompatible with the given metadata.
-	fn hashing_weight(&self, entry_type: &StorageEntryType) -> Option<Weight>;
+	fn hashing_weight(&self, entry_type: &PortableStorageEntryType) -> Option<Weight>;
+
+	/// Attempts to hash given partial key (i.e. first N keys of the `DoubleMap`/`NMap`) using supplied metadata.
+	/// Produced bytes are shared by all of the entity members addressed by the partial key.
+	/// Returns `None` if the key is incompatible with the given metadata.
+	fn to_hashed_prefix(&self, entry_type: &PortableStorageEntryType) -> Option<Vec<u8>>;
+
+	/// Attempts to calculate hashing weight for the given partial key using supplied metadata.
+	/// Returns `None` if the key is incompatible with the given metadata.
+	fn prefix_hashing_weight(&self, entry_type: &PortableStorageEntryType) -> Option<Weight>;
 }
 
 /// Empty key used to access `Plain` values.
@@ -33,6 +45,16 @@ impl MapKey {
 	pub fn new_double(key1: impl Encode, key2: impl Encode) -> Self {
 		Self(vec![key1.encode(), key2.encode()])
 	}
+
+	/// Constructs map key from the already encoded keys (for ex. to access `NMap` entities).
+	pub fn from_encoded(keys: Vec<Vec<u8>>) -> Self {
+		Self(keys)
+	}
+
+	/// Returns encoded keys.
+	pub fn encoded_keys(&self) -> &[Vec<u8>] {
+		&self.0
+	}
 }
 
 /// All kinds of allowed keys.
@@ -44,6 +66,25 @@ pub enum Key {
 	MapKey(MapKey),
 }
 
+impl Key {
+	/// Constructs `NoKey` if no keys are supplied, `MapKey` otherwise.
+	pub fn from_encoded_keys(keys: Vec<Vec<u8>>) -> Self {
+		if keys.is_empty() {
+			Self::NoKey(NoKey)
+		} else {
+			Self::MapKey(MapKey(keys))
+		}
+	}
+
+	/// Returns encoded keys (empty in case of `NoKey`).
+	pub fn encoded_keys(&self) -> &[Vec<u8>] {
+		match self {
+			Self::NoKey(_) => &[],
+			Self::MapKey(map_key) => map_key.encoded_keys(),
+		}
+	}
+}
+
 impl Encode for Key {
 	fn encode(&self) -> Vec<u8> {
 		match self {
@@ -80,85 +121,182 @@ impl Key {
 		&self,
 		pallet: &str,
 		entry: &str,
-		entry_type: &StorageEntryType,
+		entry_type: &PortableStorageEntryType,
+	) -> Option<Vec<u8>> {
+		self.to_prefixed_storage_key(&pallet_entry_prefix(pallet, entry), entry_type)
+	}
+
+	/// Converts self to the full-featured hashed storage key using already hashed pallet entry prefix.
+	/// Returns `None` if the key is incompatible with the provided metadata.
+	pub fn to_prefixed_storage_key(
+		&self,
+		prefix: &[u8],
+		entry_type: &PortableStorageEntryType,
 	) -> Option<Vec<u8>> {
 		let storage_key = self.to_hashed_key(entry_type)?;
 
-		let mut final_key = vec![0u8; 32 + storage_key.len()];
-		final_key[0..16].copy_from_slice(&Twox128::hash(pallet.as_bytes()));
-		final_key[16..32].copy_from_slice(&Twox128::hash(entry.as_bytes()));
-		final_key[32..].copy_from_slice(&storage_key);
+		let mut final_key = vec![0u8; prefix.len() + storage_key.len()];
+		final_key[..prefix.len()].copy_from_slice(prefix);
+		final_key[prefix.len()..].copy_from_slice(&storage_key);
 
 		Some(final_key)
 	}
 
+	/// Converts self treated as a partial key to the hashed storage prefix shared by all of the
+	/// addressed entity members (including the pallet storage entry prefix).
+	/// Returns `None` if the key is incompatible with the provided metadata.
+	pub fn to_pallet_entry_storage_prefix(
+		&self,
+		pallet: &str,
+		entry: &str,
+		entry_type: &PortableStorageEntryType,
+	) -> Option<Vec<u8>> {
+		let hashed_prefix = self.to_hashed_prefix(entry_type)?;
+
+		Some(
+			pallet_entry_prefix(pallet, entry)
+				.iter()
+				.copied()
+				.chain(hashed_prefix)
+				.collect(),
+		)
+	}
+
+	/// Calculates hashing weight for converting self treated as a partial key to the hashed storage prefix.
+	/// Returns `None` if the key is incompatible with the provided metadata.
+	pub fn full_prefix_hashing_weight(
+		&self,
+		pallet: &str,
+		entry: &str,
+		entry_type: &PortableStorageEntryType,
+	) -> Option<Weight> {
+		let res = self
+			.prefix_hashing_weight(entry_type)?
+			.saturating_add(pallet_entry_prefix_hashing_weight(pallet, entry));
+
+		Some(res)
+	}
+
 	/// Calculates hashing weight for converting to the full-featured hashed storage key with prefix.
 	/// Returns `None` if the key is incompatible with the provided metadata.
 	pub fn full_hashing_weight(
 		&self,
 		pallet: &str,
 		entry: &str,
-		entry_type: &StorageEntryType,
+		entry_type: &PortableStorageEntryType,
 	) -> Option<Weight> {
 		let res = self
 			.hashing_weight(entry_type)?
-			.saturating_add(hasher_weight(
-				&StorageHasher::Twox128,
-				pallet.as_bytes().len(),
-			))
-			.saturating_add(hasher_weight(
-				&StorageHasher::Twox128,
-				entry.as_bytes().len(),
-			));
+			.saturating_add(pallet_entry_prefix_hashing_weight(pallet, entry));
 
 		Some(res)
 	}
 }
 
+/// Hashes pallet and storage entry names producing the prefix shared by all members of the entry.
+pub fn pallet_entry_prefix(pallet: &str, entry: &str) -> [u8; 32] {
+	let mut prefix = [0u8; 32];
+	prefix[0..16].copy_from_slice(&Twox128::hash(pallet.as_bytes()));
+	prefix[16..32].copy_from_slice(&Twox128::hash(entry.as_bytes()));
+
+	prefix
+}
+
+/// Calculates hashing weight for producing the pallet storage entry prefix.
+pub fn pallet_entry_prefix_hashing_weight(pallet: &str, entry: &str) -> Weight {
+	hasher_weight(&StorageHasher::Twox128, pallet.as_bytes().len()).saturating_add(hasher_weight(
+		&StorageHasher::Twox128,
+		entry.as_bytes().len(),
+	))
+}
+
 impl HashableKey for NoKey {
-	fn to_hashed_key(&self, entry_type: &StorageEntryType) -> Option<Vec<u8>> {
+	fn to_hashed_key(&self, entry_type: &PortableStorageEntryType) -> Option<Vec<u8>> {
 		match entry_type {
 			StorageEntryType::Plain(_) => Some(vec![]),
 			_ => None,
 		}
 	}
 
-	fn hashing_weight(&self, entry_type: &StorageEntryType) -> Option<Weight> {
+	fn hashing_weight(&self, entry_type: &PortableStorageEntryType) -> Option<Weight> {
 		match entry_type {
 			StorageEntryType::Plain(_) => Some(Weight::zero()),
 			_ => None,
 		}
 	}
+
+	fn to_hashed_prefix(&self, _entry_type: &PortableStorageEntryType) -> Option<Vec<u8>> {
+		Some(vec![])
+	}
+
+	fn prefix_hashing_weight(&self, _entry_type: &PortableStorageEntryType) -> Option<Weight> {
+		Some(Weight::zero())
+	}
 }
 
-impl HashableKey for MapKey {
-	fn to_hashed_key(&self, entry_type: &StorageEntryType) -> Option<Vec<u8>> {
+impl MapKey {
+	/// Returns hashers to be used for the keys if their amount is compatible with the supplied metadata.
+	/// A partial key can contain fewer keys than hashers.
+	fn hashers<'a>(
+		&self,
+		entry_type: &'a PortableStorageEntryType,
+		partial: bool,
+	) -> Option<&'a [StorageHasher]> {
 		let Self(keys) = self;
 
 		match entry_type {
-			StorageEntryType::Map { hashers, .. } => (hashers.len() == keys.len()).then(|| {
-				keys.iter()
-					.zip(hashers)
-					.flat_map(|(bytes, hasher)| hash_bytes_with(bytes, hasher))
-					.collect()
-			}),
+			StorageEntryType::Map { hashers, .. } => {
+				let compatible = if partial {
+					hashers.len() >= keys.len()
+				} else {
+					hashers.len() == keys.len()
+				};
+
+				compatible.then(|| &hashers[..keys.len()])
+			}
 			_ => None,
 		}
 	}
 
-	fn hashing_weight(&self, entry_type: &StorageEntryType) -> Option<Weight> {
+	fn hash_with(&self, hashers: &[StorageHasher]) -> Vec<u8> {
 		let Self(keys) = self;
 
-		match entry_type {
-			StorageEntryType::Map { hashers, .. } => (hashers.len() == keys.len()).then(|| {
-				keys.iter()
-					.map(Vec::len)
-					.zip(hashers)
-					.map(|(len, hasher)| hasher_weight(hasher, len))
-					.fold(Weight::zero(), |acc, cur| acc.saturating_add(cur))
-			}),
-			_ => None,
-		}
+		keys.iter()
+			.zip(hashers)
+			.flat_map(|(bytes, hasher)| hash_bytes_with(bytes, hasher))
+			.collect()
+	}
+
+	fn hashing_weight_with(&self, hashers: &[StorageHasher]) -> Weight {
+		let Self(keys) = self;
+
+		keys.iter()
+			.map(Vec::len)
+			.zip(hashers)
+			.map(|(len, hasher)| hasher_weight(hasher, len))
+			.fold(Weight::zero(), |acc, cur| acc.saturating_add(cur))
+	}
+}
+
+impl HashableKey for MapKey {
+	fn to_hashed_key(&self, entry_type: &PortableStorageEntryType) -> Option<Vec<u8>> {
+		self.hashers(entry_type, false)
+			.map(|hashers| self.hash_with(hashers))
+	}
+
+	fn hashing_weight(&self, entry_type: &PortableStorageEntryType) -> Option<Weight> {
+		self.hashers(entry_type, false)
+			.map(|hashers| self.hashing_weight_with(hashers))
+	}
+
+	fn to_hashed_prefix(&self, entry_type: &PortableStorageEntryType) -> Option<Vec<u8>> {
+		self.hashers(entry_type, true)
+			.map(|hashers| self.hash_with(hashers))
+	}
+
+	fn prefix_hashing_weight(&self, entry_type: &PortableStorageEntryType) -> Option<Weight> {
+		self.hashers(entry_type, true)
+			.map(|hashers| self.hashing_weight_with(hashers))
 	}
 }
 
@@ -175,17 +313,31 @@ impl From<MapKey> for Key {
 }
 
 impl HashableKey for Key {
-	fn to_hashed_key(&self, entry_type: &StorageEntryType) -> Option<Vec<u8>> {
+	fn to_hashed_key(&self, entry_type: &PortableStorageEntryType) -> Option<Vec<u8>> {
 		match self {
 			Self::NoKey(key) => key.to_hashed_key(entry_type),
 			Self::MapKey(map_key) => map_key.to_hashed_key(entry_type),
 		}
 	}
 
-	fn hashing_weight(&self, entry_type: &StorageEntryType) -> Option<Weight> {
+	fn hashing_weight(&self, entry_type: &PortableStorageEntryType) -> Option<Weight> {
 		match self {
 			Self::NoKey(key) => key.hashing_weight(entry_type),
 			Self::MapKey(map_key) => map_key.hashing_weight(entry_type),
 		}
 	}
+
+	fn to_hashed_prefix(&self, entry_type: &PortableStorageEntryType) -> Option<Vec<u8>> {
+		match self {
+			Self::NoKey(key) => key.to_hashed_prefix(entry_type),
+			Self::MapKey(map_key) => map_key.to_hashed_prefix(entry_type),
+		}
+	}
+
+	fn prefix_hashing_weight(&self, entry_type: &PortableStorageEntryType) -> Option<Weight> {
+		match self {
+			Self::NoKey(key) => key.prefix_hashing_weight(entry_type),
+			Self::MapKey(map_key) => map_key.prefix_hashing_weight(entry_type),
+		}
+	}
 }
@@ -2,33 +2,40 @@ use core::marker::PhantomData;
 
 use evm::{ExitError, ExitSucceed};
 use fp_evm::Precompile;
-use frame_support::log::debug;
+use frame_support::{log::debug, traits::Get, weights::Weight};
 use pallet_evm::{
 	GasWeightMapping, PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileResult,
+	StorageReadFilter,
 };
 use sp_std::{borrow::Cow, prelude::*};
 
-use codec::Decode;
-use frame_metadata::{StorageEntryModifier, StorageEntryType};
-use input::MetaStorageReaderInput;
+use frame_metadata::StorageEntryModifier;
+use input::{MetaStorageReaderInput, OutputFormat, Params};
 
-use crate::raw_storage_reader::RawStorageReader;
+use crate::{
+	common::{output::RawStorageValue, solidity},
+	raw_storage_reader::RawStorageReader,
+	weights::{SubstrateWeight, WeightInfo},
+};
+pub use batch::MetaStorageBatchReader;
 pub use pallet_storage_metadata_provider::*;
+pub use runtime_metadata_provider::*;
 
 use key::Key;
 
-#[cfg(feature = "runtime-benchmarks")]
-pub mod benchmarks;
+pub mod abi;
+mod batch;
 pub mod input;
 pub mod key;
 mod pallet_storage_metadata_provider;
+mod runtime_metadata_provider;
 #[cfg(test)]
 mod tests;
-mod utils;
-mod weights;
+pub(crate) mod utils;
 
 /// Precompile allows reading any pallet storage member data using provided key hashed according to the corresponding metadata.
 /// Unlike with `RawStorageReader`, default members will be instantiated in case of absence.
+/// Both the pallet storage entry and its hashed key have to be allowed by the configured `StorageReadFilter`.
 ///
 /// Output:
 /// - 1 byte representing presence (1) or absence (0) of the value
@@ -47,6 +54,27 @@ mod weights;
 ///     - sequence of keys each of which is represented as its compact encoded length followed by bytes for MapKey
 /// - byte representing params: 0 - no additional params, 1 - offset, 2 - length, 3 - offset and length
 /// - the corresponding compact encoded offset, length or offset followed by length
+/// - optional byte representing output format: 0 - raw SCALE bytes (default), 1 - Solidity ABI, 2 - hashed prefix
+/// - optional storage proof (requires output format byte to be present): state root (32 bytes) followed by
+///   the compact encoded amount of trie nodes and the nodes each represented as its compact encoded length followed by bytes
+///
+/// With the Solidity ABI output format, value is decoded using the storage entry type metadata
+/// and returned as `abi.encode(bool present, T value)` (see `abi::AbiEncoder` for the type mapping).
+/// Absent value is represented by the zeroed value of the type. Params can't be used with this format.
+///
+/// With the hashed prefix output format, the hashed storage key is returned instead of the value.
+/// The key can be partial (i.e. contain only the first N keys of the `DoubleMap`/`NMap`), in which case
+/// the produced prefix is shared by all of the addressed members. It can be used along with `StorageIterator`
+/// to enumerate them.
+///
+/// If the storage proof is supplied, value is read from the proof verified against its state root
+/// instead of the storage (see `StorageProof`). Proofs can't be produced by the precompile itself,
+/// as the runtime doesn't have access to the trie nodes, so they have to be generated off-chain.
+///
+/// Alternatively, input can be supplied as the Solidity ABI encoded
+/// `readMeta(string pallet, string entry, bytes[] keys, uint32 offset, uint32 len)` call (see `StorageReader.sol`).
+/// Each of `keys` is a SCALE encoded key, empty `keys` stand for the `NoKey`, zero `offset` or `len` stand
+/// for the absent param. Output of such call is encoded as `abi.encode(bool present, bytes value)`.
 ///
 /// # Example call
 ///
@@ -82,7 +110,14 @@ pub struct MetaStorageReader<T>(PhantomData<T>);
 
 impl<T: pallet_evm::Config + PalletStorageMetadataProvider> Precompile for MetaStorageReader<T> {
 	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
-		let mut input = handle.input();
+		with_storage_metadata_cache(|| Self::execute_cached(handle))
+	}
+}
+
+impl<T: pallet_evm::Config + PalletStorageMetadataProvider> MetaStorageReader<T> {
+	/// Executes the precompile sharing the runtime metadata between lookups.
+	fn execute_cached(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let input = handle.input();
 		let target_gas = handle.gas_limit();
 
 		debug!(
@@ -90,56 +125,190 @@ impl<T: pallet_evm::Config + PalletStorageMetadataProvider> Precompile for MetaS
 			input, target_gas
 		);
 
-		let MetaStorageReaderInput {
-			pallet,
-			entry,
-			key,
-			params,
-		} = MetaStorageReaderInput::decode(&mut input).map_err(Error::Decoding)?;
+		let (
+			MetaStorageReaderInput {
+				pallet,
+				entry,
+				key,
+				params,
+				format,
+				proof,
+			},
+			encoding,
+		) = solidity::decode_input(input, MetaStorageReaderInput::decode_solidity)
+			.map_err(Error::Decoding)?;
 
 		debug!(
-            "`MetaStorageReader` decoded input: pallet = {:?}, entry = {:?}, key = {:?}, params = {:?}",
+            "`MetaStorageReader` decoded input: pallet = {:?}, entry = {:?}, key = {:?}, params = {:?}, format = {:?}, encoding = {:?}",
             pallet,
             entry,
             key,
-            params
+            params,
+            format,
+            encoding
         );
 
-		let entry_meta = T::pallet_storage_entry_metadata(&pallet, &entry)
-			.ok_or(Error::PalletStorageEntryNotFound)?;
+		if format == OutputFormat::Abi && params != Params::None {
+			return Err(Error::ParamsNotSupported.into());
+		}
+		if format == OutputFormat::HashedPrefix && proof.is_some() {
+			return Err(Error::ProofNotSupported.into());
+		}
+
+		let caller = handle.context().caller;
+		if !T::StorageReadFilter::allows_pallet_entry(&caller, &pallet, &entry) {
+			return Err(Error::AccessDenied.into());
+		}
+
+		let (entry_meta, lookup_gas_cost) =
+			lookup_entry_metadata::<T>(&pallet, &entry).ok_or(Error::PalletStorageEntryNotFound)?;
+
+		if format == OutputFormat::HashedPrefix {
+			return Self::execute_hashed_prefix(
+				handle,
+				&pallet,
+				&entry,
+				&key,
+				&params,
+				&entry_meta.ty,
+				lookup_gas_cost,
+			);
+		}
+
 		let default_bytes =
 			(entry_meta.modifier == StorageEntryModifier::Default).then(|| entry_meta.default);
 
-		let base_gas_cost = Self::base_gas_cost(&pallet, &entry, &key, &entry_meta.ty)?;
+		let base_gas_cost = Self::base_gas_cost(&pallet, &entry, &key, &entry_meta.ty)?
+			.saturating_add(lookup_gas_cost)
+			.saturating_add(RawStorageReader::<T>::proof_gas_cost(proof.as_ref()));
 		crate::ensure_enough_gas!(target_gas >= base_gas_cost);
 
 		let storage_key = key
 			.to_pallet_entry_storage_key(&pallet, &entry, &entry_meta.ty)
 			.ok_or(Error::InvalidKey)?;
+		if !T::StorageReadFilter::allows_raw_key(&caller, &storage_key) {
+			return Err(Error::AccessDenied.into());
+		}
 
-		let raw_output = RawStorageReader::<T>::read(&storage_key).or_default(default_bytes);
+		let raw_output = RawStorageReader::<T>::read_with_proof(&storage_key, proof.as_ref())
+			.ok_or(Error::InvalidProof)?
+			.or_default(default_bytes);
 
-		let total_gas_cost = base_gas_cost.saturating_add(Self::output_gas_cost(raw_output.len()));
+		let mut total_gas_cost =
+			base_gas_cost.saturating_add(Self::output_gas_cost(raw_output.len()));
 		crate::ensure_enough_gas!(target_gas >= total_gas_cost);
 
-		let output = raw_output.apply_params(&params);
+		let output = if format == OutputFormat::Abi {
+			let registry =
+				T::pallet_storage_types(&pallet).ok_or(Error::PalletStorageEntryNotFound)?;
+			let max_nodes = target_gas.map_or(u64::MAX, |target_gas| {
+				Self::abi_max_nodes(target_gas.saturating_sub(total_gas_cost))
+			});
+
+			let mut encoder = abi::AbiEncoder::new(&registry, max_nodes);
+			let output = encoder
+				.encode_value(
+					abi::value_type_id(&entry_meta.ty),
+					raw_output.apply_params(&params).into_item().as_deref(),
+				)
+				.map_err(|err| match err {
+					abi::Error::LimitExceeded => PrecompileFailure::from(ExitError::OutOfGas),
+					_ => Error::AbiEncoding.into(),
+				})?;
+
+			total_gas_cost = total_gas_cost
+				.saturating_add(Self::abi_gas_cost(encoder.visited_nodes(), output.len()));
+			crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+			output
+		} else {
+			solidity::encode_output(&raw_output.apply_params(&params), encoding)
+		};
 
 		handle.record_cost(total_gas_cost)?;
 
 		Ok(PrecompileOutput {
-			output: output.encode_to_bytes(),
+			output,
 			exit_status: ExitSucceed::Returned,
 		})
 	}
 }
 
+/// Looks up metadata of the pallet storage entry.
+/// Returns it along with the gas cost of the lookup which is proportional to the amount of the pallet storage entries
+/// and includes preparing the metadata (see `PalletStorageMetadataProvider::metadata_preparation_weight`).
+pub(crate) fn lookup_entry_metadata<T: pallet_evm::Config + PalletStorageMetadataProvider>(
+	pallet: &str,
+	entry: &str,
+) -> Option<(PortableStorageEntryMetadata, u64)> {
+	let preparation_weight = T::metadata_preparation_weight();
+	let pallet_meta = T::pallet_storage_metadata(pallet)?;
+	let lookup_weight =
+		SubstrateWeight::<T>::entry_metadata_lookup(pallet_meta.entries.len() as u32)
+			.saturating_add(preparation_weight);
+
+	find_entry_metadata(pallet_meta, entry).map(|entry_meta| {
+		(
+			entry_meta,
+			T::GasWeightMapping::weight_to_gas(lookup_weight),
+		)
+	})
+}
+
 impl<T: pallet_evm::Config> MetaStorageReader<T> {
+	/// Produces the hashed storage prefix addressed by the (possibly partial) key without reading the storage.
+	fn execute_hashed_prefix(
+		handle: &mut impl PrecompileHandle,
+		pallet: &str,
+		entry: &str,
+		key: &Key,
+		params: &Params,
+		entry_type: &PortableStorageEntryType,
+		lookup_gas_cost: u64,
+	) -> PrecompileResult {
+		let target_gas = handle.gas_limit();
+
+		let base_gas_cost =
+			Self::prefix_gas_cost(pallet, entry, key, entry_type)?.saturating_add(lookup_gas_cost);
+		crate::ensure_enough_gas!(target_gas >= base_gas_cost);
+
+		let prefix = key
+			.to_pallet_entry_storage_prefix(pallet, entry, entry_type)
+			.ok_or(Error::InvalidKey)?;
+
+		let total_gas_cost = base_gas_cost.saturating_add(Self::output_gas_cost(prefix.len()));
+		crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+		let output = RawStorageValue::Item(prefix.into()).apply_params(params);
+
+		handle.record_cost(total_gas_cost)?;
+
+		Ok(PrecompileOutput {
+			output: output.encode_to_bytes(),
+			exit_status: ExitSucceed::Returned,
+		})
+	}
+
+	/// Gas cost for producing the hashed storage prefix for the given (possibly partial) key.
+	fn prefix_gas_cost(
+		pallet: &str,
+		entry: &str,
+		key: &Key,
+		entry_type: &PortableStorageEntryType,
+	) -> Result<u64, PrecompileFailure> {
+		let key_hashing_weight = key
+			.full_prefix_hashing_weight(pallet, entry, entry_type)
+			.ok_or(Error::InvalidKey)?;
+
+		Ok(T::GasWeightMapping::weight_to_gas(key_hashing_weight))
+	}
+
 	/// Base gas cost for performing a single read operation based on input.
 	fn base_gas_cost(
 		pallet: &str,
 		entry: &str,
 		key: &Key,
-		entry_type: &StorageEntryType,
+		entry_type: &PortableStorageEntryType,
 	) -> Result<u64, PrecompileFailure> {
 		let key_hashing_weight = key
 			.full_hashing_weight(pallet, entry, entry_type)
@@ -153,6 +322,29 @@ impl<T: pallet_evm::Config> MetaStorageReader<T> {
 	fn output_gas_cost(output_len: usize) -> u64 {
 		RawStorageReader::<T>::output_gas_cost(output_len)
 	}
+
+	/// Weight of re-encoding the value as Solidity ABI based on the amount of visited type nodes
+	/// and the ABI output length. Each visited node is charged as reading a single ABI word.
+	fn abi_weight(visited_nodes: u64, output_len: usize) -> Weight {
+		T::ByteReadWeight::get().saturating_mul(
+			visited_nodes
+				.saturating_mul(abi::WORD_SIZE as u64)
+				.saturating_add(output_len as u64),
+		)
+	}
+
+	/// Gas cost of re-encoding the value as Solidity ABI.
+	fn abi_gas_cost(visited_nodes: u64, output_len: usize) -> u64 {
+		T::GasWeightMapping::weight_to_gas(Self::abi_weight(visited_nodes, output_len))
+	}
+
+	/// Max amount of type nodes which can be visited using supplied gas.
+	fn abi_max_nodes(gas: u64) -> u64 {
+		T::GasWeightMapping::gas_to_weight(gas)
+			.ref_time()
+			.checked_div(Self::abi_weight(1, 0).ref_time())
+			.unwrap_or(u64::MAX)
+	}
 }
 
 /// An error produced by the `MetaStorageReader`.
@@ -161,6 +353,11 @@ pub enum Error {
 	PalletStorageEntryNotFound,
 	InvalidMetadata,
 	InvalidKey,
+	ParamsNotSupported,
+	AbiEncoding,
+	AccessDenied,
+	ProofNotSupported,
+	InvalidProof,
 	Decoding(codec::Error),
 }
 
@@ -170,6 +367,11 @@ impl From<Error> for PrecompileFailure {
 			Error::InvalidMetadata => "Invalid metadata",
 			Error::PalletStorageEntryNotFound => "Pallet storage entry not found",
 			Error::InvalidKey => "Invalid key",
+			Error::ParamsNotSupported => "Params can't be used with the ABI output format",
+			Error::AbiEncoding => "Failed to encode value as ABI",
+			Error::AccessDenied => "Storage access denied",
+			Error::ProofNotSupported => "Proof can't be used with the hashed prefix output format",
+			Error::InvalidProof => "Invalid storage proof",
 			Error::Decoding(_) => "Failed to decode input",
 		};
 
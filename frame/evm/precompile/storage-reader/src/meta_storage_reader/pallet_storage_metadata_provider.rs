@@ -1,33 +1,114 @@
-use frame_metadata::{PalletStorageMetadata, StorageEntryMetadata};
+use frame_metadata::{PalletStorageMetadata, StorageEntryMetadata, StorageEntryType};
+use frame_support::weights::Weight;
+use scale_info::{form::PortableForm, PortableRegistry};
+use sp_std::rc::Rc;
+
+/// Pallet storage metadata referring to the types from the `PortableRegistry`.
+pub type PortablePalletStorageMetadata = PalletStorageMetadata<PortableForm>;
+/// Pallet storage entry metadata referring to the types from the `PortableRegistry`.
+pub type PortableStorageEntryMetadata = StorageEntryMetadata<PortableForm>;
+/// Pallet storage entry type referring to the types from the `PortableRegistry`.
+pub type PortableStorageEntryType = StorageEntryType<PortableForm>;
 
 /// Provides metadata for the pallet storage.
 pub trait PalletStorageMetadataProvider {
+	/// Weight of preparing the metadata to be provided by the next calls, e.g. building the runtime
+	/// metadata if it isn't cached. Has to be charged before requesting the metadata.
+	fn metadata_preparation_weight() -> Weight {
+		Weight::zero()
+	}
+
 	/// Provides metadata for the storage of the pallet with supplied name.
-	fn pallet_storage_metadata(pallet: &str) -> Option<PalletStorageMetadata>;
+	fn pallet_storage_metadata(pallet: &str) -> Option<PortablePalletStorageMetadata>;
+
+	/// Provides registry containing types referenced by the storage metadata of the pallet with supplied name.
+	fn pallet_storage_types(pallet: &str) -> Option<Rc<PortableRegistry>>;
 
 	/// Provides metadata for the storage entry of the pallet with supplied name.
 	/// Returns an error if metadata is invalid (i.e. encoded not properly).
-	fn pallet_storage_entry_metadata(pallet: &str, entry: &str) -> Option<StorageEntryMetadata> {
-		if let Some(pallet_meta) = Self::pallet_storage_metadata(pallet) {
-			for item in pallet_meta.entries {
-				if &item.name == &entry {
-					return Some(item.clone());
-				}
-			}
-		}
-
-		None
+	fn pallet_storage_entry_metadata(
+		pallet: &str,
+		entry: &str,
+	) -> Option<PortableStorageEntryMetadata> {
+		Self::pallet_storage_metadata(pallet)
+			.and_then(|pallet_meta| find_entry_metadata(pallet_meta, entry))
 	}
 }
 
+/// Finds metadata of the storage entry with supplied name among the pallet storage entries.
+pub fn find_entry_metadata(
+	pallet_meta: PortablePalletStorageMetadata,
+	entry: &str,
+) -> Option<PortableStorageEntryMetadata> {
+	pallet_meta
+		.entries
+		.into_iter()
+		.find(|item| item.name.as_ref() == entry)
+}
+
 /// Implements `PalletStorageMetadataProvider` for the provided identifier.
+///
+/// Either lists pallets explicitly:
+///
+/// ```ignore
+/// impl_pallet_storage_metadata_provider!(for Runtime: "System" => System, "EVM" => EVM);
+/// ```
+///
+/// or derives them from the runtime metadata (optionally filtered by the `StorageEntryFilter`):
+///
+/// ```ignore
+/// impl_pallet_storage_metadata_provider!(for Runtime: from runtime metadata);
+/// impl_pallet_storage_metadata_provider!(for Runtime: from runtime metadata filtered by DenyList<Denied>);
+/// ```
 #[macro_export]
 macro_rules! impl_pallet_storage_metadata_provider {
+    (for $name: ident: from runtime metadata) => {
+        $crate::impl_pallet_storage_metadata_provider!(for $name: from runtime metadata filtered by ());
+    };
+    (for $name: ident: from runtime metadata filtered by $filter: ty) => {
+        impl $crate::meta_storage_reader::RuntimeMetadataSource for $name {
+            fn runtime_metadata() -> $crate::frame_metadata::RuntimeMetadataPrefixed {
+                $name::metadata()
+            }
+        }
+
+        impl $crate::meta_storage_reader::PalletStorageMetadataProvider for $name {
+            fn metadata_preparation_weight() -> $crate::frame_support::weights::Weight {
+                <$crate::meta_storage_reader::RuntimeStorageMetadata<$name, $filter> as $crate::meta_storage_reader::PalletStorageMetadataProvider>::metadata_preparation_weight()
+            }
+
+            fn pallet_storage_metadata(pallet: &str) -> Option<$crate::meta_storage_reader::PortablePalletStorageMetadata> {
+                <$crate::meta_storage_reader::RuntimeStorageMetadata<$name, $filter> as $crate::meta_storage_reader::PalletStorageMetadataProvider>::pallet_storage_metadata(pallet)
+            }
+
+            fn pallet_storage_types(pallet: &str) -> Option<$crate::sp_std::rc::Rc<$crate::scale_info::PortableRegistry>> {
+                <$crate::meta_storage_reader::RuntimeStorageMetadata<$name, $filter> as $crate::meta_storage_reader::PalletStorageMetadataProvider>::pallet_storage_types(pallet)
+            }
+        }
+    };
     (for $name: ident: $($pallet_name: literal => $pallet: ident),+) => {
         impl $crate::meta_storage_reader::PalletStorageMetadataProvider for $name {
-            fn pallet_storage_metadata(pallet: &str) -> Option<$crate::frame_metadata::PalletStorageMetadata> {
+            fn pallet_storage_metadata(pallet: &str) -> Option<$crate::meta_storage_reader::PortablePalletStorageMetadata> {
+                use $crate::scale_info::IntoPortable;
+
+                // Registry is filled in the same order by `pallet_storage_types`, so type identifiers match
+                let mut registry = $crate::scale_info::Registry::new();
+                match pallet {
+                    $($pallet_name => Some($pallet::storage_metadata().into_portable(&mut registry))),+
+                    ,_ => None
+                }
+            }
+
+            fn pallet_storage_types(pallet: &str) -> Option<$crate::sp_std::rc::Rc<$crate::scale_info::PortableRegistry>> {
+                use $crate::scale_info::IntoPortable;
+
+                let mut registry = $crate::scale_info::Registry::new();
                 match pallet {
-                    $($pallet_name => Some($pallet::storage_metadata())),+
+                    $($pallet_name => {
+                        $pallet::storage_metadata().into_portable(&mut registry);
+
+                        Some($crate::sp_std::rc::Rc::new(registry.into()))
+                    }),+
                     ,_ => None
                 }
             }
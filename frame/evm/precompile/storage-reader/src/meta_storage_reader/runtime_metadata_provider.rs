@@ -0,0 +1,276 @@
+use core::marker::PhantomData;
+
+use codec::{Decode, Encode};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_support::{
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+use scale_info::{
+	form::{Form, PortableForm},
+	PortableRegistry,
+};
+use sp_std::{prelude::*, rc::Rc};
+
+use super::{PalletStorageMetadataProvider, PortablePalletStorageMetadata};
+use crate::weights::{SubstrateWeight, WeightInfo};
+
+/// Provides metadata of the whole runtime. Implemented by the `impl_pallet_storage_metadata_provider!`
+/// macro using the `metadata` function generated by `construct_runtime!`.
+pub trait RuntimeMetadataSource {
+	/// Builds metadata of the runtime.
+	fn runtime_metadata() -> RuntimeMetadataPrefixed;
+}
+
+/// Decides which pallets and pallet storage entries are exposed by the `RuntimeStorageMetadata`.
+pub trait StorageEntryFilter {
+	/// Returns `true` if at least some storage entries of the pallet with supplied name can be exposed.
+	fn allows_pallet(pallet: &str) -> bool;
+
+	/// Returns `true` if the storage entry of the pallet with supplied name can be exposed.
+	fn allows_entry(pallet: &str, entry: &str) -> bool;
+}
+
+/// Exposes all pallets and their storage entries.
+impl StorageEntryFilter for () {
+	fn allows_pallet(_pallet: &str) -> bool {
+		true
+	}
+
+	fn allows_entry(_pallet: &str, _entry: &str) -> bool {
+		true
+	}
+}
+
+/// List of the `(pallet, entry)` pairs. Entry set to `None` stands for all entries of the pallet.
+pub type StorageEntryList = &'static [(&'static str, Option<&'static str>)];
+
+/// Exposes only listed pallets and storage entries.
+pub struct AllowList<L>(PhantomData<L>);
+
+impl<L: Get<StorageEntryList>> StorageEntryFilter for AllowList<L> {
+	fn allows_pallet(pallet: &str) -> bool {
+		L::get()
+			.iter()
+			.any(|(listed_pallet, _)| *listed_pallet == pallet)
+	}
+
+	fn allows_entry(pallet: &str, entry: &str) -> bool {
+		L::get().iter().any(|(listed_pallet, listed_entry)| {
+			*listed_pallet == pallet && listed_entry.map_or(true, |listed| listed == entry)
+		})
+	}
+}
+
+/// Exposes all pallets and storage entries except listed ones.
+pub struct DenyList<L>(PhantomData<L>);
+
+impl<L: Get<StorageEntryList>> StorageEntryFilter for DenyList<L> {
+	fn allows_pallet(pallet: &str) -> bool {
+		!L::get()
+			.iter()
+			.any(|(listed_pallet, listed_entry)| *listed_pallet == pallet && listed_entry.is_none())
+	}
+
+	fn allows_entry(pallet: &str, entry: &str) -> bool {
+		!L::get().iter().any(|(listed_pallet, listed_entry)| {
+			*listed_pallet == pallet && listed_entry.map_or(true, |listed| listed == entry)
+		})
+	}
+}
+
+/// Storage metadata of all runtime pallets built from the `RuntimeMetadataV14`.
+pub struct CachedStorageMetadata {
+	/// `spec_version` of the runtime the metadata was built for.
+	pub spec_version: u32,
+	/// Registry of the types referenced by the pallets storage metadata.
+	pub types: Rc<PortableRegistry>,
+	/// Pallet names along with their storage metadata.
+	pub pallets: Vec<(
+		<PortableForm as Form>::String,
+		PortablePalletStorageMetadata,
+	)>,
+}
+
+impl CachedStorageMetadata {
+	/// Extracts storage metadata of the pallets from the runtime metadata.
+	/// Returns `None` if the metadata isn't of `V14`.
+	pub fn from_runtime_metadata(
+		spec_version: u32,
+		RuntimeMetadataPrefixed(_, metadata): RuntimeMetadataPrefixed,
+	) -> Option<Self> {
+		let metadata = match metadata {
+			RuntimeMetadata::V14(metadata) => metadata,
+			_ => return None,
+		};
+
+		let pallets = metadata
+			.pallets
+			.into_iter()
+			.filter_map(|pallet| Some((pallet.name, pallet.storage?)))
+			.collect();
+
+		Some(Self {
+			spec_version,
+			types: Rc::new(metadata.types),
+			pallets,
+		})
+	}
+
+	/// Returns storage metadata of the pallet with supplied name.
+	pub fn pallet(&self, pallet: &str) -> Option<&PortablePalletStorageMetadata> {
+		self.pallets
+			.iter()
+			.find(|(name, _)| name.as_ref() == pallet)
+			.map(|(_, storage)| storage)
+	}
+
+	/// Encodes the metadata to be persisted, starting with its `spec_version`.
+	fn to_bytes(&self) -> Vec<u8> {
+		(self.spec_version, &*self.types, &self.pallets).encode()
+	}
+
+	/// Decodes the metadata encoded by `to_bytes`.
+	fn from_bytes(mut input: &[u8]) -> Option<Self> {
+		let (spec_version, types, pallets) = Decode::decode(&mut input).ok()?;
+
+		Some(Self {
+			spec_version,
+			types: Rc::new(types),
+			pallets,
+		})
+	}
+}
+
+/// Raw storage key of the storage metadata persisted by `StoreStorageMetadata`.
+pub fn stored_storage_metadata_key() -> [u8; 32] {
+	frame_support::storage::storage_prefix(b"StorageReader", b"StorageMetadata")
+}
+
+/// Persists the storage metadata of the runtime `T` on the runtime upgrade, so that
+/// `RuntimeStorageMetadata` reads it instead of building it from the runtime metadata on each
+/// precompile call. To be added to the migrations executed by `frame_executive::Executive`.
+///
+/// The metadata isn't persisted at genesis, so it's built on each call until the first upgrade.
+pub struct StoreStorageMetadata<T>(PhantomData<T>);
+
+impl<T: frame_system::Config + RuntimeMetadataSource> OnRuntimeUpgrade for StoreStorageMetadata<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let spec_version = T::Version::get().spec_version;
+		match CachedStorageMetadata::from_runtime_metadata(spec_version, T::runtime_metadata()) {
+			Some(metadata) => {
+				sp_io::storage::set(&stored_storage_metadata_key(), &metadata.to_bytes())
+			}
+			None => sp_io::storage::clear(&stored_storage_metadata_key()),
+		}
+
+		SubstrateWeight::<T>::runtime_metadata_build().saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+environmental::environmental!(storage_metadata_cache: Option<Rc<CachedStorageMetadata>>);
+
+/// Executes `f` with the storage metadata cache, so the runtime metadata is built at most once
+/// while executing it. Nested calls share the cache of the outermost one.
+///
+/// The cache doesn't outlive `f`, so building the metadata is charged the same way whether
+/// the runtime is executed natively or in Wasm.
+pub fn with_storage_metadata_cache<R>(f: impl FnOnce() -> R) -> R {
+	storage_metadata_cache::using_once(&mut None, f)
+}
+
+/// `PalletStorageMetadataProvider` built from the `RuntimeMetadataV14` of the runtime `T`.
+/// Pallets and storage entries can be hidden using the `StorageEntryFilter` `F`.
+///
+/// The storage metadata is read from the one persisted by `StoreStorageMetadata` for the current
+/// `spec_version`, or else built from the runtime metadata. It's done once per
+/// `with_storage_metadata_cache` scope, and its cost is reported by `metadata_preparation_weight`
+/// until it's cached.
+pub struct RuntimeStorageMetadata<T, F = ()>(PhantomData<(T, F)>);
+
+impl<T: frame_system::Config + RuntimeMetadataSource, F> RuntimeStorageMetadata<T, F> {
+	/// Returns storage metadata cached in the current scope if it was built for the given `spec_version`.
+	fn cached(spec_version: u32) -> Option<Rc<CachedStorageMetadata>> {
+		storage_metadata_cache::with(|cache| cache.clone())
+			.flatten()
+			.filter(|cached| cached.spec_version == spec_version)
+	}
+
+	/// Returns `true` if the storage metadata persisted by `StoreStorageMetadata` was built for the
+	/// given `spec_version`, reading only the version it starts with.
+	fn is_stored(spec_version: u32) -> bool {
+		let mut stored_version = [0; 4];
+		sp_io::storage::read(&stored_storage_metadata_key(), &mut stored_version, 0).is_some()
+			&& u32::from_le_bytes(stored_version) == spec_version
+	}
+
+	/// Returns the storage metadata persisted by `StoreStorageMetadata` if it was built for the
+	/// given `spec_version`.
+	fn stored(spec_version: u32) -> Option<CachedStorageMetadata> {
+		let stored = sp_io::storage::get(&stored_storage_metadata_key())?;
+
+		CachedStorageMetadata::from_bytes(&stored)
+			.filter(|stored| stored.spec_version == spec_version)
+	}
+
+	/// Returns storage metadata of all runtime pallets reading or building and caching it if needed.
+	pub fn storage_metadata() -> Option<Rc<CachedStorageMetadata>> {
+		let spec_version = T::Version::get().spec_version;
+
+		if let Some(metadata) = Self::cached(spec_version) {
+			return Some(metadata);
+		}
+
+		let metadata = match Self::stored(spec_version) {
+			Some(metadata) => metadata,
+			None => {
+				CachedStorageMetadata::from_runtime_metadata(spec_version, T::runtime_metadata())?
+			}
+		};
+		let metadata = Rc::new(metadata);
+		storage_metadata_cache::with(|cache| *cache = Some(metadata.clone()));
+
+		Some(metadata)
+	}
+}
+
+impl<T, F> PalletStorageMetadataProvider for RuntimeStorageMetadata<T, F>
+where
+	T: frame_system::Config + RuntimeMetadataSource,
+	F: StorageEntryFilter,
+{
+	fn metadata_preparation_weight() -> Weight {
+		let spec_version = T::Version::get().spec_version;
+		if Self::cached(spec_version).is_some() {
+			Weight::zero()
+		} else if Self::is_stored(spec_version) {
+			SubstrateWeight::<T>::stored_storage_metadata_read()
+		} else {
+			SubstrateWeight::<T>::runtime_metadata_build()
+		}
+	}
+
+	fn pallet_storage_metadata(pallet: &str) -> Option<PortablePalletStorageMetadata> {
+		if !F::allows_pallet(pallet) {
+			return None;
+		}
+
+		let mut storage = Self::storage_metadata()?.pallet(pallet)?.clone();
+		storage
+			.entries
+			.retain(|entry| F::allows_entry(pallet, entry.name.as_ref()));
+
+		Some(storage)
+	}
+
+	fn pallet_storage_types(pallet: &str) -> Option<Rc<PortableRegistry>> {
+		if !F::allows_pallet(pallet) {
+			return None;
+		}
+
+		let metadata = Self::storage_metadata()?;
+		metadata.pallet(pallet)?;
+
+		Some(metadata.types.clone())
+	}
+}
@@ -1,16 +1,18 @@
 use codec::{Decode, Encode};
 use fp_evm::Precompile;
-use frame_support::{StorageDoubleMap, StorageMap, StorageValue};
+use frame_support::{traits::OnRuntimeUpgrade, StorageDoubleMap, StorageMap, StorageValue};
 
 use crate::common::output::OutputStorageValue;
 use pallet_evm_test_vector_support::MockHandle;
-use sp_core::{H160, U256};
-use sp_std::prelude::*;
+use scale_info::PortableRegistry;
+use sp_core::{H160, H256, U256};
+use sp_std::{prelude::*, rc::Rc};
 
 use crate::common::params::Params;
 
-use super::{key::*, *};
+use super::{abi::Token, input::MetaStorageBatchReaderInput, key::*, *};
 use crate::mock::{test_storage::*, *};
+use crate::weights::{SubstrateWeight, WeightInfo};
 
 pub fn ext() -> sp_io::TestExternalities {
 	let t = frame_system::GenesisConfig::default()
@@ -81,7 +83,7 @@ fn invalid_input() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Err::<Option<Bytes>, _>(
@@ -93,7 +95,7 @@ fn invalid_input() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Err::<Option<Bytes>, _>(
@@ -105,7 +107,7 @@ fn invalid_input() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Err::<Option<Bytes>, _>(PrecompileFailure::from(super::Error::InvalidKey).into())
@@ -120,7 +122,7 @@ fn invalid_input() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Err::<Option<Bytes>, _>(PrecompileFailure::from(super::Error::InvalidKey).into())
@@ -137,7 +139,7 @@ fn entity_access() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(Some(Bytes::default()))
@@ -147,7 +149,7 @@ fn entity_access() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(None::<Bytes>)
@@ -157,7 +159,7 @@ fn entity_access() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(Some(Bytes::with_len(123)))
@@ -255,7 +257,7 @@ fn map_access() {
                     Params::None,
                 );
                 assert_decoded_eq!(
-                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX)),
+                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX)),
                     Ok(Some(Bytes::from_to(100, 1000)))
                 );
 
@@ -264,7 +266,7 @@ fn map_access() {
                         &mut MockHandle::new(
                         input
                             .with_replaced_key(MapKey::new_single(Bytes(name.as_bytes().to_vec())))
-                            .encode(), Some(30_000), DUMMY_CTX)
+                            .encode(), Some(40_000), DUMMY_CTX)
                     ),
                     Ok(Some(Bytes(name.as_bytes().to_vec())))
                 );
@@ -274,7 +276,7 @@ fn map_access() {
                     MetaStorageReader::<Runtime>::execute(
                         &mut MockHandle::new(input
                             .with_replaced_key(MapKey::new_single(non_existent_key))
-                            .encode(), Some(30_000), DUMMY_CTX)
+                            .encode(), Some(40_000), DUMMY_CTX)
                     ),
                     Ok(None::<Bytes>)
                 );
@@ -303,7 +305,7 @@ fn map_access_with_params() {
                     Params::Len(4),
                 );
                 assert_decoded_eq!(
-                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX)),
+                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX)),
                     Ok(Some(CompactU32(900)))
                 );
 
@@ -314,7 +316,7 @@ fn map_access_with_params() {
 					Params::Offset(10),
 				);
                 assert_decoded_eq!(
-                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX)),
+                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX)),
                     Ok(Some(RawBytes(Bytes::from_to(108, 1000))))
                 );
 
@@ -325,7 +327,7 @@ fn map_access_with_params() {
                     Params::OffsetAndLen { offset: 10, len: 50 },
                 );
                 assert_decoded_eq!(
-                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX)),
+                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX)),
                     Ok(Some(RawBytes(Bytes::from_to(108, 158))))
                 );
 
@@ -336,7 +338,7 @@ fn map_access_with_params() {
                     Params::Len(100_000_000),
                 );
                 assert_decoded_eq!(
-                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX)),
+                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX)),
                     Ok(Some(Bytes::from_to(100, 1000)))
                 );
 
@@ -347,7 +349,7 @@ fn map_access_with_params() {
                     Params::Offset(100_000_000),
                 );
                 assert_decoded_eq!(
-                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX)),
+                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX)),
                     Ok(Some(RawBytes(Bytes::from_to(0, 0))))
                 );
 
@@ -358,7 +360,7 @@ fn map_access_with_params() {
                     Params::OffsetAndLen { offset: 10_000_000, len: 10_000_000 }
                 );
                 assert_decoded_eq!(
-                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX)),
+                    MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX)),
 					Ok(Some(RawBytes(Bytes::from_to(0, 0))))
                 );
             }
@@ -384,7 +386,7 @@ fn double_map_access() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(Some(Bytes::with_len(30)))
@@ -396,7 +398,7 @@ fn double_map_access() {
 				input
 					.with_replaced_key(MapKey::new_double(non_existent_key, Bytes::with_len(20)))
 					.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(None::<Bytes>)
@@ -411,7 +413,7 @@ fn double_map_access() {
 						non_existent_second_key
 					))
 					.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(None::<Bytes>)
@@ -427,7 +429,7 @@ fn double_map_access() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(Some(Bytes::default()))
@@ -452,7 +454,7 @@ fn double_map_access_with_params() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(Some(RawBytes(Bytes::from_to(108, 1000))))
@@ -470,7 +472,7 @@ fn double_map_access_with_params() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(Some(RawBytes(Bytes::from_to(108, 118))))
@@ -488,7 +490,7 @@ fn double_map_access_with_params() {
 		assert_decoded_eq!(
 			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
-				Some(30_000),
+				Some(40_000),
 				DUMMY_CTX
 			)),
 			Ok(Some(RawBytes(Bytes(CompactU32(900).encode()))))
@@ -502,13 +504,10 @@ fn costs() {
 		let input =
 			MetaStorageReaderInput::new("TestStorage", "SingleDefault", NoKey, Params::None);
 
-		let mut handle = MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX);
+		let mut handle = MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX);
 		let res = MetaStorageReader::<Runtime>::execute(&mut handle).unwrap();
-		let entry_meta = <Runtime as PalletStorageMetadataProvider>::pallet_storage_entry_metadata(
-			"TestStorage",
-			"SingleDefault",
-		)
-		.unwrap();
+		let (entry_meta, lookup_gas_cost) =
+			lookup_entry_metadata::<Runtime>("TestStorage", "SingleDefault").unwrap();
 		assert_eq!(
 			handle.gas_used,
 			MetaStorageReader::<Runtime>::base_gas_cost(
@@ -517,14 +516,15 @@ fn costs() {
 				&NoKey.into(),
 				&entry_meta.ty
 			)
-			.unwrap() + MetaStorageReader::<Runtime>::output_gas_cost(res.output.len() - 1)
+			.unwrap() + lookup_gas_cost
+				+ MetaStorageReader::<Runtime>::output_gas_cost(res.output.len() - 1)
 		);
 		assert!(handle.gas_used > RawStorageReader::<Runtime>::base_gas_cost());
 
 		let input =
 			MetaStorageReaderInput::new("TestStorage", "SingleDefault", NoKey, Params::Len(100));
 
-		let mut handle = MockHandle::new(input.encode(), Some(30_000), DUMMY_CTX);
+		let mut handle = MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX);
 		MetaStorageReader::<Runtime>::execute(&mut handle).unwrap();
 		assert_eq!(
 			handle.gas_used,
@@ -534,7 +534,862 @@ fn costs() {
 				&NoKey.into(),
 				&entry_meta.ty
 			)
-			.unwrap() + MetaStorageReader::<Runtime>::output_gas_cost(102)
+			.unwrap() + lookup_gas_cost
+				+ MetaStorageReader::<Runtime>::output_gas_cost(102)
+		);
+	});
+}
+
+#[test]
+fn batch_access() {
+	ext().execute_with(|| {
+		MapWithBlake2_128Concat::insert(Bytes::with_len(10), Bytes::from_to(100, 1000));
+		MapWithBlake2_128Concat::insert(Bytes::with_len(20), Bytes::with_len(30));
+
+		let input = MetaStorageBatchReaderInput::new(
+			"TestStorage",
+			"MapWithBlake2_128Concat",
+			vec![
+				(MapKey::new_single(Bytes::with_len(10)), Params::None),
+				(MapKey::new_single(Bytes::with_len(11)), Params::None),
+				(MapKey::new_single(Bytes::with_len(20)), Params::Offset(10)),
+			],
+		);
+		let PrecompileOutput {
+			output,
+			exit_status,
+			..
+		} = MetaStorageBatchReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(200_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(exit_status, ExitSucceed::Returned);
+
+		let values: Vec<_> = OutputStorageValue::decode_list_from_bytes(&output)
+			.unwrap()
+			.into_iter()
+			.map(OutputStorageValue::into_item)
+			.collect();
+		assert_eq!(values.len(), 3);
+		assert_eq!(
+			Bytes::decode(&mut &values[0].as_ref().unwrap()[..]).unwrap(),
+			Bytes::from_to(100, 1000)
+		);
+		assert_eq!(values[1], None);
+		assert_eq!(
+			RawBytes::decode(&mut &values[2].as_ref().unwrap()[..]).unwrap(),
+			RawBytes(Bytes::from_to(9, 30))
+		);
+
+		let input = MetaStorageBatchReaderInput::new(
+			"TestStorage",
+			"MapDefault",
+			vec![
+				(MapKey::new_single(Bytes::with_len(10)), Params::None),
+				(MapKey::new_single(Bytes::with_len(11)), Params::None),
+			],
+		);
+		let PrecompileOutput { output, .. } = MetaStorageBatchReader::<Runtime>::execute(
+			&mut MockHandle::new(input.encode(), Some(200_000), DUMMY_CTX),
+		)
+		.unwrap();
+
+		for value in OutputStorageValue::decode_list_from_bytes(&output).unwrap() {
+			assert_eq!(
+				Bytes::decode(&mut &value.into_item().unwrap()[..]).unwrap(),
+				Bytes::default()
+			);
+		}
+	})
+}
+
+#[test]
+fn batch_invalid_input() {
+	ext().execute_with(|| {
+		let input =
+			MetaStorageBatchReaderInput::new("TestStorage", "Abcde", vec![(NoKey, Params::None)]);
+		assert_eq!(
+			MetaStorageBatchReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Err(super::Error::PalletStorageEntryNotFound.into())
+		);
+
+		let input = MetaStorageBatchReaderInput::new(
+			"TestStorage",
+			"DoubleMap",
+			vec![
+				(
+					MapKey::new_double(Bytes::with_len(10), Bytes::with_len(20)),
+					Params::None,
+				),
+				(MapKey::new_single(Bytes::with_len(10)), Params::None),
+			],
+		);
+		assert_eq!(
+			MetaStorageBatchReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Err(super::Error::InvalidKey.into())
+		);
+	})
+}
+
+#[test]
+fn batch_costs() {
+	ext().execute_with(|| {
+		let keys: Vec<(Key, Params)> = (0..20)
+			.map(|i| (MapKey::new_single(Bytes::with_len(i)).into(), Params::None))
+			.collect();
+		let input = MetaStorageBatchReaderInput::new("TestStorage", "MapDefault", keys.clone());
+		let (entry_meta, lookup_gas_cost) =
+			lookup_entry_metadata::<Runtime>("TestStorage", "MapDefault").unwrap();
+
+		let mut handle = MockHandle::new(input.encode(), Some(1_000_000), DUMMY_CTX);
+		let res = MetaStorageBatchReader::<Runtime>::execute(&mut handle).unwrap();
+		let output_gas_cost: u64 = OutputStorageValue::decode_list_from_bytes(&res.output)
+			.unwrap()
+			.into_iter()
+			.map(|value| {
+				MetaStorageBatchReader::<Runtime>::output_gas_cost(value.into_item().unwrap().len())
+			})
+			.sum();
+		let base_gas_cost = MetaStorageBatchReader::<Runtime>::base_gas_cost(
+			"TestStorage",
+			"MapDefault",
+			&keys,
+			&entry_meta.ty,
+		)
+		.unwrap() + lookup_gas_cost;
+		assert_eq!(handle.gas_used, base_gas_cost + output_gas_cost);
+
+		let single_reads_base_gas_cost: u64 = keys
+			.iter()
+			.map(|(key, _)| {
+				MetaStorageReader::<Runtime>::base_gas_cost(
+					"TestStorage",
+					"MapDefault",
+					key,
+					&entry_meta.ty,
+				)
+				.unwrap()
+			})
+			.sum();
+		assert!(base_gas_cost < single_reads_base_gas_cost);
+
+		assert_eq!(
+			MetaStorageBatchReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(base_gas_cost),
+				DUMMY_CTX
+			)),
+			Err(ExitError::OutOfGas.into())
+		);
+	})
+}
+
+fn word(value: u8) -> Vec<u8> {
+	let mut word = vec![0; 32];
+	word[31] = value;
+	word
+}
+
+#[test]
+fn abi_encoding() {
+	use abi::{encode_params, Token};
+
+	assert_eq!(
+		encode_params(&[Token::uint(1), Token::Bytes(b"abc".to_vec())]),
+		[
+			word(1),
+			word(0x40),
+			word(3),
+			[&b"abc"[..], &[0; 29][..]].concat()
+		]
+		.concat()
+	);
+	assert_eq!(
+		Token::Array(vec![Token::uint(1), Token::uint(2)]).encode(),
+		[word(0x20), word(2), word(1), word(2)].concat()
+	);
+	assert_eq!(
+		Token::Tuple(vec![Token::uint(1), Token::Array(vec![Token::uint(2)])]).encode(),
+		[word(0x20), word(1), word(0x40), word(1), word(2)].concat()
+	);
+	assert_eq!(
+		Token::int(&(-2i8).to_be_bytes()),
+		Token::Word([&[0xff; 31][..], &[0xfe][..]].concat().try_into().unwrap())
+	);
+}
+
+#[test]
+fn abi_output_format() {
+	ext().execute_with(|| {
+		let input = MetaStorageReaderInput::new("TestStorage", "Typed", NoKey, Params::None)
+			.with_format(OutputFormat::Abi);
+		assert_eq!(input.encode().last(), Some(&1));
+
+		let zeroed = Token::Tuple(vec![
+			Token::uint(0),
+			Token::bool(false),
+			Token::uint(0),
+			Token::uint(0),
+			Token::uint(0),
+			Token::Array(vec![]),
+			Token::Tuple(vec![Token::uint(0), Token::Bytes(vec![])]),
+			Token::Tuple(vec![Token::uint(0), Token::Bytes(vec![])]),
+		]);
+		let out = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(40_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(
+			out.output,
+			abi::encode_params(&[Token::bool(false), zeroed])
+		);
+
+		Typed::put(TypedStruct {
+			number: 7,
+			flag: true,
+			signed: -2,
+			compact: 1000,
+			array: [1, 2, 3, 4],
+			list: vec![1, 2],
+			variant: TypedEnum::WithFields(5, vec![9, 9]),
+			optional: Some(H160::repeat_byte(1)),
+		});
+
+		let mut h160_word = [0; 32];
+		h160_word[..20].copy_from_slice(&[1; 20]);
+		let mut array_word = [0; 32];
+		array_word[..4].copy_from_slice(&[1, 2, 3, 4]);
+		let value = Token::Tuple(vec![
+			Token::uint(7),
+			Token::bool(true),
+			Token::int(&(-2i8).to_be_bytes()),
+			Token::uint(1000),
+			Token::Word(array_word),
+			Token::Array(vec![Token::uint(1), Token::uint(2)]),
+			Token::Tuple(vec![
+				Token::uint(1),
+				Token::Bytes(abi::encode_params(&[
+					Token::uint(5),
+					Token::Bytes(vec![9, 9]),
+				])),
+			]),
+			Token::Tuple(vec![
+				Token::uint(1),
+				Token::Bytes(abi::encode_params(&[Token::Tuple(vec![Token::Word(
+					h160_word,
+				)])])),
+			]),
+		]);
+		let out = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(40_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(out.output, abi::encode_params(&[Token::bool(true), value]));
+
+		TypedMap::insert(1, vec![TypedEnum::Empty, TypedEnum::WithFields(1, vec![])]);
+		let input = MetaStorageReaderInput::new(
+			"TestStorage",
+			"TypedMap",
+			MapKey::new_single(1u32),
+			Params::None,
+		)
+		.with_format(OutputFormat::Abi);
+		let out = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(40_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(
+			out.output,
+			abi::encode_params(&[
+				Token::bool(true),
+				Token::Array(vec![
+					Token::Tuple(vec![Token::uint(0), Token::Bytes(vec![])]),
+					Token::Tuple(vec![
+						Token::uint(1),
+						Token::Bytes(abi::encode_params(&[Token::uint(1), Token::Bytes(vec![])])),
+					]),
+				])
+			])
+		);
+	})
+}
+
+#[test]
+fn abi_output_format_invalid_input() {
+	ext().execute_with(|| {
+		let input = MetaStorageReaderInput::new("TestStorage", "Typed", NoKey, Params::Len(10))
+			.with_format(OutputFormat::Abi);
+		assert_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Err(super::Error::ParamsNotSupported.into())
+		);
+
+		// `Single` value type is `Bytes` (`Vec<u8>`), so the raw value claiming 25 bytes doesn't match it
+		frame_support::storage::unhashed::put_raw(&Single::hashed_key(), &[100]);
+		let input = MetaStorageReaderInput::new("TestStorage", "Single", NoKey, Params::None)
+			.with_format(OutputFormat::Abi);
+		assert_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Err(super::Error::AbiEncoding.into())
+		);
+	})
+}
+
+#[test]
+fn abi_output_format_costs() {
+	ext().execute_with(|| {
+		TypedMap::insert(1, vec![TypedEnum::Empty; 100]);
+		let input = MetaStorageReaderInput::new(
+			"TestStorage",
+			"TypedMap",
+			MapKey::new_single(1u32),
+			Params::None,
+		)
+		.with_format(OutputFormat::Abi);
+		let (entry_meta, lookup_gas_cost) =
+			lookup_entry_metadata::<Runtime>("TestStorage", "TypedMap").unwrap();
+		let key = MapKey::new_single(1u32).into();
+		let raw_value = TypedMap::get(1).unwrap().encode();
+
+		let registry =
+			<Runtime as PalletStorageMetadataProvider>::pallet_storage_types("TestStorage")
+				.unwrap();
+		let mut encoder = abi::AbiEncoder::new(&registry, u64::MAX);
+		let abi_output = encoder
+			.encode_value(abi::value_type_id(&entry_meta.ty), Some(&raw_value))
+			.unwrap();
+		// Vector itself and its field-less items
+		assert_eq!(encoder.visited_nodes(), 101);
+
+		let base_gas_cost = MetaStorageReader::<Runtime>::base_gas_cost(
+			"TestStorage",
+			"TypedMap",
+			&key,
+			&entry_meta.ty,
+		)
+		.unwrap() + lookup_gas_cost
+			+ MetaStorageReader::<Runtime>::output_gas_cost(raw_value.len());
+		let total_gas_cost = base_gas_cost
+			+ MetaStorageReader::<Runtime>::abi_gas_cost(encoder.visited_nodes(), abi_output.len());
+
+		let mut handle = MockHandle::new(input.encode(), Some(total_gas_cost), DUMMY_CTX);
+		let out = MetaStorageReader::<Runtime>::execute(&mut handle).unwrap();
+		assert_eq!(out.output, abi_output);
+		assert_eq!(handle.gas_used, total_gas_cost);
+
+		assert_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(total_gas_cost - 1),
+				DUMMY_CTX
+			)),
+			Err(ExitError::OutOfGas.into())
+		);
+		assert_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(base_gas_cost + MetaStorageReader::<Runtime>::abi_gas_cost(10, 0)),
+				DUMMY_CTX
+			)),
+			Err(ExitError::OutOfGas.into())
+		);
+	})
+}
+
+#[test]
+fn hashed_prefix_output_format() {
+	use frame_support::{Blake2_128, StorageHasher};
+
+	ext().execute_with(|| {
+		let input = MetaStorageReaderInput::new(
+			"TestStorage",
+			"DoubleMap",
+			MapKey::new_single(Bytes::with_len(10)),
+			Params::None,
+		)
+		.with_format(OutputFormat::HashedPrefix);
+		let out = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(40_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		let prefix = OutputStorageValue::decode_from_bytes(&out.output)
+			.into_item()
+			.unwrap();
+		assert_eq!(
+			prefix[..],
+			[
+				&pallet_entry_prefix("TestStorage", "DoubleMap")[..],
+				&Blake2_128::hash(&Bytes::with_len(10).encode())[..]
+			]
+			.concat()[..]
+		);
+
+		let out = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input
+				.with_replaced_key(MapKey::new_double(Bytes::with_len(10), Bytes::with_len(20)))
+				.encode(),
+			Some(40_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(
+			OutputStorageValue::decode_from_bytes(&out.output).into_item(),
+			Some(DoubleMap::hashed_key_for(Bytes::with_len(10), Bytes::with_len(20)).into())
+		);
+
+		let out = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.with_replaced_key(NoKey).encode(),
+			Some(40_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(
+			OutputStorageValue::decode_from_bytes(&out.output).into_item(),
+			Some(
+				pallet_entry_prefix("TestStorage", "DoubleMap")
+					.to_vec()
+					.into()
+			)
+		);
+
+		assert_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input
+					.with_replaced_key(MapKey::from_encoded(vec![vec![1], vec![2], vec![3]]))
+					.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Err(super::Error::InvalidKey.into())
+		);
+	})
+}
+
+#[test]
+fn hashed_prefix_costs() {
+	ext().execute_with(|| {
+		let key: Key = MapKey::new_single(Bytes::with_len(10)).into();
+		let input =
+			MetaStorageReaderInput::new("TestStorage", "DoubleMap", key.clone(), Params::None)
+				.with_format(OutputFormat::HashedPrefix);
+		let (entry_meta, lookup_gas_cost) =
+			lookup_entry_metadata::<Runtime>("TestStorage", "DoubleMap").unwrap();
+
+		let mut handle = MockHandle::new(input.encode(), Some(40_000), DUMMY_CTX);
+		let out = MetaStorageReader::<Runtime>::execute(&mut handle).unwrap();
+		assert_eq!(
+			handle.gas_used,
+			MetaStorageReader::<Runtime>::prefix_gas_cost(
+				"TestStorage",
+				"DoubleMap",
+				&key,
+				&entry_meta.ty
+			)
+			.unwrap() + lookup_gas_cost
+				+ MetaStorageReader::<Runtime>::output_gas_cost(out.output.len() - 1)
+		);
+		// No storage reads are performed
+		assert!(handle.gas_used < RawStorageReader::<Runtime>::base_gas_cost());
+	})
+}
+
+#[test]
+fn runtime_metadata_provider() {
+	frame_support::parameter_types! {
+		pub const Listed: StorageEntryList = &[("TestStorage", Some("Single")), ("System", None)];
+	}
+	type Full = RuntimeStorageMetadata<Runtime>;
+	type Allowed = RuntimeStorageMetadata<Runtime, AllowList<Listed>>;
+	type Denied = RuntimeStorageMetadata<Runtime, DenyList<Listed>>;
+
+	ext().execute_with(|| {
+		let explicit =
+			<Runtime as PalletStorageMetadataProvider>::pallet_storage_metadata("TestStorage")
+				.unwrap();
+		let derived = Full::pallet_storage_metadata("TestStorage").unwrap();
+		assert_eq!(
+			derived
+				.entries
+				.iter()
+				.map(|entry| (
+					entry.name.clone(),
+					entry.modifier.clone(),
+					entry.default.clone()
+				))
+				.collect::<Vec<_>>(),
+			explicit
+				.entries
+				.iter()
+				.map(|entry| (
+					entry.name.clone(),
+					entry.modifier.clone(),
+					entry.default.clone()
+				))
+				.collect::<Vec<_>>()
+		);
+		assert!(Full::pallet_storage_metadata("Balances").is_some());
+		assert!(Full::pallet_storage_metadata("Abcde").is_none());
+
+		// Metadata is built once per cache scope and then reused
+		with_storage_metadata_cache(|| {
+			assert!(Full::metadata_preparation_weight().ref_time() > 0);
+			let metadata = Full::storage_metadata().unwrap();
+			assert_eq!(Full::metadata_preparation_weight(), Weight::zero());
+			assert!(Rc::ptr_eq(&metadata, &Allowed::storage_metadata().unwrap()));
+		});
+		assert_eq!(
+			Full::metadata_preparation_weight(),
+			SubstrateWeight::<Runtime>::runtime_metadata_build()
+		);
+
+		// Metadata persisted on the runtime upgrade is read instead of being built
+		let built = Full::storage_metadata().unwrap();
+		StoreStorageMetadata::<Runtime>::on_runtime_upgrade();
+		assert_eq!(
+			Full::metadata_preparation_weight(),
+			SubstrateWeight::<Runtime>::stored_storage_metadata_read()
+		);
+		let stored = Full::storage_metadata().unwrap();
+		assert_eq!(stored.types, built.types);
+		assert_eq!(stored.pallets, built.pallets);
+		with_storage_metadata_cache(|| {
+			Full::storage_metadata().unwrap();
+			assert_eq!(Full::metadata_preparation_weight(), Weight::zero());
+		});
+
+		// Value types are resolved using the whole runtime registry
+		TypedMap::insert(1, vec![TypedEnum::WithFields(2, vec![3])]);
+		let raw_value = TypedMap::get(1).unwrap().encode();
+		let encode = |provider_entry: PortableStorageEntryMetadata, registry: &PortableRegistry| {
+			abi::AbiEncoder::new(registry, u64::MAX)
+				.encode_value(abi::value_type_id(&provider_entry.ty), Some(&raw_value))
+				.unwrap()
+		};
+		assert_eq!(
+			encode(
+				Full::pallet_storage_entry_metadata("TestStorage", "TypedMap").unwrap(),
+				&Full::pallet_storage_types("TestStorage").unwrap()
+			),
+			encode(
+				<Runtime as PalletStorageMetadataProvider>::pallet_storage_entry_metadata(
+					"TestStorage",
+					"TypedMap"
+				)
+				.unwrap(),
+				&<Runtime as PalletStorageMetadataProvider>::pallet_storage_types("TestStorage")
+					.unwrap()
+			)
+		);
+
+		assert!(Allowed::pallet_storage_entry_metadata("TestStorage", "Single").is_some());
+		assert!(Allowed::pallet_storage_entry_metadata("TestStorage", "SingleDefault").is_none());
+		assert!(Allowed::pallet_storage_entry_metadata("System", "Account").is_some());
+		assert!(Allowed::pallet_storage_metadata("Balances").is_none());
+		assert!(Allowed::pallet_storage_types("Balances").is_none());
+
+		assert!(Denied::pallet_storage_entry_metadata("TestStorage", "Single").is_none());
+		assert!(Denied::pallet_storage_entry_metadata("TestStorage", "SingleDefault").is_some());
+		assert!(Denied::pallet_storage_metadata("System").is_none());
+		assert!(Denied::pallet_storage_metadata("Balances").is_some());
+	})
+}
+
+#[test]
+fn access_control() {
+	ext().execute_with(|| {
+		Restricted::put(Bytes::with_len(10));
+		Single::put(Bytes::with_len(20));
+
+		let input = MetaStorageReaderInput::new("TestStorage", "Restricted", NoKey, Params::None);
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Err::<Option<Bytes>, _>(PrecompileFailure::from(super::Error::AccessDenied).into())
+		);
+
+		let input = MetaStorageBatchReaderInput::new(
+			"TestStorage",
+			"Restricted",
+			vec![(Key::from(NoKey), Params::None)],
+		);
+		assert_eq!(
+			MetaStorageBatchReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Err(super::Error::AccessDenied.into())
+		);
+
+		let input = MetaStorageReaderInput::new("TestStorage", "Single", NoKey, Params::None);
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				DUMMY_CTX
+			)),
+			Ok(Some(Bytes::with_len(20)))
+		);
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(40_000),
+				evm::Context {
+					caller: H160::repeat_byte(0xde),
+					..DUMMY_CTX
+				}
+			)),
+			Err::<Option<Bytes>, _>(PrecompileFailure::from(super::Error::AccessDenied).into())
+		);
+	})
+}
+
+#[test]
+fn solidity_input() {
+	use crate::common::solidity::{self, Encoding};
+
+	ext().execute_with(|| {
+		Single::put(Bytes::with_len(20));
+		MapWithBlake2_128::insert(Bytes::with_len(10), Bytes::with_len(30));
+		DoubleMap::insert(
+			Bytes::with_len(10),
+			Bytes::with_len(20),
+			Bytes::with_len(30),
+		);
+
+		let input = MetaStorageReaderInput::new("TestStorage", "Single", NoKey, Params::None);
+		assert_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode_solidity(),
+				Some(40_000),
+				DUMMY_CTX
+			))
+			.unwrap()
+			.output,
+			abi::encode_params(&[
+				Token::bool(true),
+				Token::Bytes(Bytes::with_len(20).encode())
+			])
+		);
+
+		let inputs = [
+			MetaStorageReaderInput::new("TestStorage", "Single", NoKey, Params::None),
+			MetaStorageReaderInput::new(
+				"TestStorage",
+				"MapWithBlake2_128",
+				MapKey::new_single(Bytes::with_len(10)),
+				Params::None,
+			),
+			MetaStorageReaderInput::new(
+				"TestStorage",
+				"MapWithBlake2_128",
+				MapKey::new_single(Bytes::with_len(11)),
+				Params::None,
+			),
+			MetaStorageReaderInput::new(
+				"TestStorage",
+				"DoubleMap",
+				MapKey::new_double(Bytes::with_len(10), Bytes::with_len(20)),
+				Params::None,
+			),
+			MetaStorageReaderInput::new(
+				"TestStorage",
+				"DoubleMapDefault",
+				MapKey::new_double(Bytes::with_len(10), Bytes::with_len(20)),
+				Params::None,
+			),
+		];
+		let params = [
+			Params::None,
+			Params::Offset(3),
+			Params::Len(5),
+			Params::OffsetAndLen { offset: 2, len: 4 },
+		];
+
+		for input in inputs {
+			for params in params.clone() {
+				let input = MetaStorageReaderInput {
+					params,
+					..input.clone()
+				};
+				let solidity_input = input.encode_solidity();
+
+				let decoded = MetaStorageReaderInput::decode_solidity(&solidity_input).unwrap();
+				assert_eq!(decoded.encode(), input.encode());
+
+				let scale_output = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+					input.encode(),
+					Some(40_000),
+					DUMMY_CTX,
+				))
+				.unwrap()
+				.output;
+				let solidity_output = MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+					solidity_input,
+					Some(40_000),
+					DUMMY_CTX,
+				))
+				.unwrap()
+				.output;
+
+				assert_eq!(
+					solidity_output,
+					solidity::encode_output(
+						&OutputStorageValue::decode_from_bytes(&scale_output),
+						Encoding::Solidity
+					)
+				);
+			}
+		}
+
+		// Truncated call isn't recognized as Solidity input
+		let input = MetaStorageReaderInput::new("TestStorage", "Single", NoKey, Params::None);
+		let solidity_input = input.encode_solidity();
+		assert!(MetaStorageReaderInput::decode_solidity(
+			&solidity_input[..solidity_input.len() - 1]
+		)
+		.is_none());
+	})
+}
+
+#[test]
+fn proof_access() {
+	use crate::common::proof::StorageProof;
+
+	let mut ext = ext();
+	ext.execute_with(|| {
+		Single::put(Bytes::with_len(20));
+		DoubleMap::insert(
+			Bytes::with_len(10),
+			Bytes::with_len(20),
+			Bytes::with_len(30),
+		);
+	});
+	ext.commit_all().unwrap();
+
+	let keys = [
+		Single::hashed_key().to_vec(),
+		DoubleMap::hashed_key_for(Bytes::with_len(10), Bytes::with_len(20)),
+		DoubleMapDefault::hashed_key_for(Bytes::with_len(10), Bytes::with_len(20)),
+	];
+	let backend = ext.as_backend();
+	let state_root = *backend.root();
+	let proof = StorageProof::new(
+		state_root,
+		sp_state_machine::prove_read(backend, &keys)
+			.unwrap()
+			.into_iter_nodes(),
+	);
+
+	ext.execute_with(|| {
+		// Values are read from the proof, not from the storage
+		Single::kill();
+		DoubleMap::remove(Bytes::with_len(10), Bytes::with_len(20));
+
+		let input = MetaStorageReaderInput::new("TestStorage", "Single", NoKey, Params::None)
+			.with_proof(proof.clone());
+		let decoded = MetaStorageReaderInput::decode(&mut &input.encode()[..]).unwrap();
+		assert_eq!(decoded.format, OutputFormat::Scale);
+		assert_eq!(decoded.proof, Some(proof.clone()));
+
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Ok(Some(Bytes::with_len(20)))
+		);
+
+		let input = MetaStorageReaderInput::new(
+			"TestStorage",
+			"DoubleMap",
+			MapKey::new_double(Bytes::with_len(10), Bytes::with_len(20)),
+			Params::None,
+		)
+		.with_proof(proof.clone());
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Ok(Some(Bytes::with_len(30)))
+		);
+
+		let input = MetaStorageReaderInput::new(
+			"TestStorage",
+			"DoubleMapDefault",
+			MapKey::new_double(Bytes::with_len(10), Bytes::with_len(20)),
+			Params::None,
+		)
+		.with_proof(proof.clone());
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Ok(Some(Bytes::default()))
+		);
+
+		let input = MetaStorageReaderInput::new(
+			"TestStorage",
+			"DoubleMap",
+			MapKey::new_double(Bytes::with_len(10), Bytes::with_len(20)),
+			Params::None,
+		)
+		.with_proof(StorageProof::new(H256::repeat_byte(1), proof.nodes.clone()));
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Err::<Option<Bytes>, _>(PrecompileFailure::from(super::Error::InvalidProof).into())
+		);
+
+		let input = MetaStorageReaderInput::new("TestStorage", "Single", NoKey, Params::None)
+			.with_format(OutputFormat::HashedPrefix)
+			.with_proof(proof.clone());
+		assert_decoded_eq!(
+			MetaStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Err::<Option<Bytes>, _>(
+				PrecompileFailure::from(super::Error::ProofNotSupported).into()
+			)
 		);
 	});
 }
@@ -2,7 +2,7 @@ use frame_metadata::StorageHasher;
 use frame_support::weights::Weight;
 use sp_std::prelude::*;
 
-use super::weights::WeightInfo;
+use crate::weights::WeightInfo;
 
 /// Hashes given bytes using supplied hasher.
 pub(crate) fn hash_bytes_with(bytes: &[u8], hasher: &StorageHasher) -> Vec<u8> {
@@ -1,3 +1,7 @@
+use crate::{
+	filter::{DenyCallers, DenyKeyPrefixes, WellKnownKeys},
+	meta_storage_reader::{DenyList, StorageEntryList},
+};
 use codec::{Decode, Encode};
 use frame_support::{
 	construct_runtime, decl_module, decl_storage,
@@ -64,6 +68,25 @@ impl Default for LargeBytes {
 	}
 }
 
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, scale_info::TypeInfo)]
+pub enum TypedEnum {
+	Empty,
+	WithFields(u64, Vec<u8>),
+}
+
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, scale_info::TypeInfo)]
+pub struct TypedStruct {
+	pub number: u32,
+	pub flag: bool,
+	pub signed: i8,
+	#[codec(compact)]
+	pub compact: u128,
+	pub array: [u8; 4],
+	pub list: Vec<u16>,
+	pub variant: TypedEnum,
+	pub optional: Option<H160>,
+}
+
 pub mod test_storage {
 	use super::*;
 
@@ -74,6 +97,11 @@ pub mod test_storage {
 
 			pub LargeSingleDefault: LargeBytes;
 
+			pub Restricted: Option<Bytes>;
+
+			pub Typed: Option<TypedStruct>;
+			pub TypedMap: map hasher(twox_64_concat) u32 => Option<Vec<TypedEnum>>;
+
 			pub MapWithBlake2_128: map hasher(opaque_blake2_128) Bytes => Option<Bytes>;
 			pub MapWithBlake2_256: map hasher(opaque_blake2_256) Bytes => Option<Bytes>;
 			pub MapWithBlake2_128Concat: map hasher(blake2_128_concat) Bytes => Option<Bytes>;
@@ -124,9 +152,19 @@ construct_runtime! {
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		EVM: pallet_evm::{Pallet, Config, Call, Storage, Event<T>},
 		TestStorage: test_storage::{Pallet, Call, Storage},
+		StorageSnapshots: pallet_evm_storage_snapshots::{Pallet, Call, Storage, Event},
 	}
 }
 
+impl pallet_evm_storage_snapshots::Config for Runtime {
+	type Event = ();
+	type RegisterOrigin = frame_system::EnsureRoot<H160>;
+	type Retention = ConstU32<4>;
+	type MaxKeys = ConstU32<4>;
+	type MaxKeyLen = ConstU32<128>;
+	type WeightInfo = ();
+}
+
 impl pallet_timestamp::Config for Runtime {
 	type Moment = u64;
 	type OnTimestampSet = ();
@@ -168,6 +206,8 @@ where
 
 parameter_types! {
 	pub const ByteReadWeight: Weight = Weight::from_ref_time(10);
+	pub const DeniedEntries: StorageEntryList = &[("TestStorage", Some("Restricted"))];
+	pub DeniedCallers: Vec<H160> = vec![H160::repeat_byte(0xde)];
 }
 
 pub struct GasWeightMappingx1000;
@@ -192,6 +232,11 @@ impl pallet_evm::Config for Runtime {
 	type Event = ();
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type ByteReadWeight = ByteReadWeight;
+	type StorageReadFilter = (
+		DenyKeyPrefixes<WellKnownKeys>,
+		DenyList<DeniedEntries>,
+		DenyCallers<DeniedCallers>,
+	);
 	type ChainId = ();
 	type BlockGasLimit = ();
 	type OnChargeTransaction = ();
@@ -231,3 +276,9 @@ crate::impl_pallet_storage_metadata_provider!(
 	for Runtime:
 		"TestStorage" => TestStorage
 );
+
+impl crate::meta_storage_reader::RuntimeMetadataSource for Runtime {
+	fn runtime_metadata() -> frame_metadata::RuntimeMetadataPrefixed {
+		Runtime::metadata()
+	}
+}
@@ -1,15 +1,52 @@
 use codec::{Decode, Encode};
 use sp_std::prelude::*;
 
-use crate::common::params::Params;
+use crate::{
+	common::{
+		params::Params,
+		proof::StorageProof,
+		solidity::{self, CallArgs},
+	},
+	meta_storage_reader::abi::Token,
+};
+
+/// Signature of the Solidity function accepted by the `RawStorageReader` precompile.
+pub const READ_RAW_SIGNATURE: &str = "readRaw(bytes,uint32,uint32)";
 
 /// Input for the `RawStorageReader` precompile.
-#[derive(Encode, Decode, Debug, Clone)]
+/// Storage proof is encoded only if it's supplied.
+#[derive(Debug, Clone)]
 pub struct RawStorageReaderInput {
 	/// Raw key bytes.
 	pub key: Vec<u8>,
 	/// Additional params (offset and length).
 	pub params: Params,
+	/// Optional proof to read the value from instead of the storage.
+	pub proof: Option<StorageProof>,
+}
+
+impl Encode for RawStorageReaderInput {
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		self.key.encode_to(dest);
+		self.params.encode_to(dest);
+
+		if let Some(proof) = &self.proof {
+			proof.encode_to(dest);
+		}
+	}
+}
+
+impl Decode for RawStorageReaderInput {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let key = Decode::decode(input)?;
+		let params = Decode::decode(input)?;
+		let proof = match input.remaining_len()? {
+			Some(0) => None,
+			_ => Some(Decode::decode(input)?),
+		};
+
+		Ok(Self { key, params, proof })
+	}
 }
 
 impl RawStorageReaderInput {
@@ -21,6 +58,40 @@ impl RawStorageReaderInput {
 		Self {
 			key: key.into(),
 			params: params.into(),
+			proof: None,
 		}
 	}
+
+	/// Sets the proof to read the value from instead of the storage.
+	pub fn with_proof(mut self, proof: StorageProof) -> Self {
+		self.proof = Some(proof);
+		self
+	}
+
+	/// Decodes input from the `readRaw(bytes key, uint32 offset, uint32 len)` Solidity call.
+	/// Zero `offset` or `len` stand for the absent param.
+	/// Returns `None` if the input isn't a valid `readRaw` call.
+	pub fn decode_solidity(input: &[u8]) -> Option<Self> {
+		let args = CallArgs::new(input, READ_RAW_SIGNATURE)?;
+
+		Some(Self {
+			key: args.bytes(0)?,
+			params: solidity::params_from_offset_and_len(args.u32(1)?, args.u32(2)?),
+			proof: None,
+		})
+	}
+
+	/// Encodes input as the `readRaw(bytes key, uint32 offset, uint32 len)` Solidity call.
+	pub fn encode_solidity(&self) -> Vec<u8> {
+		let (offset, len) = solidity::params_to_offset_and_len(&self.params);
+
+		solidity::encode_call(
+			READ_RAW_SIGNATURE,
+			&[
+				Token::Bytes(self.key.clone()),
+				Token::uint(offset.into()),
+				Token::uint(len.into()),
+			],
+		)
+	}
 }
@@ -1,9 +1,10 @@
 use core::marker::PhantomData;
 
-use codec::Decode;
 use evm::{ExitError, ExitSucceed};
 use fp_evm::{Precompile, PrecompileFailure};
-use pallet_evm::{GasWeightMapping, PrecompileHandle, PrecompileOutput, PrecompileResult};
+use pallet_evm::{
+	GasWeightMapping, PrecompileHandle, PrecompileOutput, PrecompileResult, StorageReadFilter,
+};
 
 use frame_support::{log::debug, traits::Get};
 use sp_std::borrow::Cow;
@@ -12,12 +13,16 @@ pub mod input;
 #[cfg(test)]
 mod tests;
 
-use crate::common::output::RawStorageValue;
+use crate::{
+	common::{output::RawStorageValue, proof::StorageProof, solidity},
+	weights::{SubstrateWeight, WeightInfo},
+};
 use input::RawStorageReaderInput;
 
 /// Precompile allowing to read any storage data using provided raw key.
 /// Unlike `MetaStorageReader`, default members won't be instantiated in case of absence.
 /// If you need this behavior, consider using `MetaStorageReader` instead.
+/// Access to the key is checked using the configured `StorageReadFilter`.
 ///
 /// Output:
 /// - 1 byte representing presence (1) or absence (0) of the value
@@ -28,6 +33,16 @@ use input::RawStorageReaderInput;
 /// - raw key bytes
 /// - byte representing params: 0 - no additional params, 1 - offset, 2 - length, 3 - offset and length
 /// - corresponding compact encoded offset, length or offset followed by length
+/// - optional storage proof: state root (32 bytes) followed by the compact encoded amount of trie nodes
+///   and the nodes each represented as its compact encoded length followed by bytes
+///
+/// If the storage proof is supplied, value is read from the proof verified against its state root
+/// instead of the storage (see `StorageProof`). Proofs can't be produced by the precompile itself,
+/// as the runtime doesn't have access to the trie nodes, so they have to be generated off-chain.
+///
+/// Alternatively, input can be supplied as the Solidity ABI encoded `readRaw(bytes key, uint32 offset, uint32 len)`
+/// call (see `StorageReader.sol`), zero `offset` or `len` stand for the absent param.
+/// Output of such call is encoded as `abi.encode(bool present, bytes value)`.
 ///
 /// # Example call
 ///
@@ -55,7 +70,7 @@ pub struct RawStorageReader<T>(PhantomData<T>);
 
 impl<T: pallet_evm::Config> Precompile for RawStorageReader<T> {
 	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
-		let mut input = handle.input();
+		let input = handle.input();
 		let target_gas = handle.gas_limit();
 
 		debug!(
@@ -63,13 +78,19 @@ impl<T: pallet_evm::Config> Precompile for RawStorageReader<T> {
 			input, target_gas
 		);
 
-		let RawStorageReaderInput { key, params } =
-			RawStorageReaderInput::decode(&mut input).map_err(Error::Decoding)?;
+		let (RawStorageReaderInput { key, params, proof }, encoding) =
+			solidity::decode_input(input, RawStorageReaderInput::decode_solidity)
+				.map_err(Error::Decoding)?;
+
+		if !T::StorageReadFilter::allows_raw_key(&handle.context().caller, &key) {
+			return Err(Error::AccessDenied.into());
+		}
 
-		let base_gas_cost = Self::base_gas_cost();
+		let base_gas_cost =
+			Self::base_gas_cost().saturating_add(Self::proof_gas_cost(proof.as_ref()));
 		crate::ensure_enough_gas!(target_gas >= base_gas_cost);
 
-		let raw_output = Self::read(&key);
+		let raw_output = Self::read_with_proof(&key, proof.as_ref()).ok_or(Error::InvalidProof)?;
 
 		let total_gas_cost = base_gas_cost.saturating_add(Self::output_gas_cost(raw_output.len()));
 		crate::ensure_enough_gas!(target_gas >= total_gas_cost);
@@ -80,7 +101,7 @@ impl<T: pallet_evm::Config> Precompile for RawStorageReader<T> {
 
 		Ok(PrecompileOutput {
 			exit_status: ExitSucceed::Returned,
-			output: output.encode_to_bytes(),
+			output: solidity::encode_output(&output, encoding),
 		})
 	}
 }
@@ -93,15 +114,45 @@ impl<T: pallet_evm::Config> RawStorageReader<T> {
 		sp_io::storage::get(&raw_key).into()
 	}
 
+	/// Reads the value from the supplied proof, or from the storage if there's no proof.
+	/// Returns `None` if the proof is invalid.
+	pub(super) fn read_with_proof(
+		raw_key: &[u8],
+		proof: Option<&StorageProof>,
+	) -> Option<RawStorageValue> {
+		match proof {
+			Some(proof) => {
+				debug!("`RawStorageReader` read from proof: {:?}", raw_key);
+
+				proof.read(raw_key)
+			}
+			None => Some(Self::read(raw_key)),
+		}
+	}
+
+	/// Gas cost for verifying the supplied proof.
+	pub(super) fn proof_gas_cost(proof: Option<&StorageProof>) -> u64 {
+		proof.map_or(0, |proof| {
+			T::GasWeightMapping::weight_to_gas(proof.verification_weight::<SubstrateWeight<T>>())
+		})
+	}
+
 	/// Base gas cost for performing a single read operation based on input.
+	/// Uses benchmarked weight of reading an empty value.
 	pub(super) fn base_gas_cost() -> u64 {
-		T::GasWeightMapping::weight_to_gas(T::DbWeight::get().reads(1))
+		T::GasWeightMapping::weight_to_gas(SubstrateWeight::<T>::raw_read(0))
 	}
 
 	/// Gas cost based on an output length.
+	/// Includes benchmarked per-byte read weight along with the configured `ByteReadWeight`.
 	pub(super) fn output_gas_cost(output_len: usize) -> u64 {
+		let measured_weight = SubstrateWeight::<T>::raw_read(output_len as u32)
+			.saturating_sub(SubstrateWeight::<T>::raw_read(0));
+
 		T::GasWeightMapping::weight_to_gas(
-			T::ByteReadWeight::get().saturating_mul(output_len as u64),
+			T::ByteReadWeight::get()
+				.saturating_mul(output_len as u64)
+				.saturating_add(measured_weight),
 		)
 	}
 }
@@ -110,6 +161,8 @@ impl<T: pallet_evm::Config> RawStorageReader<T> {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
 	Decoding(codec::Error),
+	AccessDenied,
+	InvalidProof,
 }
 
 impl From<Error> for PrecompileFailure {
@@ -118,6 +171,12 @@ impl From<Error> for PrecompileFailure {
 			Error::Decoding(_) => PrecompileFailure::Error {
 				exit_status: ExitError::Other(Cow::Borrowed("Failed to decode")),
 			},
+			Error::AccessDenied => PrecompileFailure::Error {
+				exit_status: ExitError::Other(Cow::Borrowed("Storage access denied")),
+			},
+			Error::InvalidProof => PrecompileFailure::Error {
+				exit_status: ExitError::Other(Cow::Borrowed("Invalid storage proof")),
+			},
 		}
 	}
 }
@@ -1,9 +1,9 @@
 use codec::{Decode, Encode};
 use fp_evm::Precompile;
-use frame_support::{assert_noop, StorageMap};
+use frame_support::{assert_noop, StorageMap, StorageValue};
 use pallet_evm_test_vector_support::MockHandle;
 
-use sp_core::{H160, U256};
+use sp_core::{H160, H256, U256};
 
 use crate::common::params::Params;
 
@@ -142,16 +142,32 @@ fn cost() {
 
 		assert_eq!(
 			handle.gas_used,
-			<Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
-				<Runtime as frame_system::Config>::DbWeight::get().reads(1)
-					+ <Runtime as pallet_evm::Config>::ByteReadWeight::get()
+			<Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(SubstrateWeight::<
+				Runtime,
+			>::raw_read(0))
+				+ <Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
+					<Runtime as pallet_evm::Config>::ByteReadWeight::get()
 						* (out.output.len() as u64 - 1)
-			)
+						+ (SubstrateWeight::<Runtime>::raw_read(out.output.len() as u32 - 1)
+							- SubstrateWeight::<Runtime>::raw_read(0))
+				)
 		);
-
-		let invalid_cost = <Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
-			<Runtime as frame_system::Config>::DbWeight::get().reads(1),
+		// Reading is charged per byte of the value as well
+		assert!(
+			RawStorageReader::<Runtime>::output_gas_cost(100_000)
+				> <Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
+					<Runtime as pallet_evm::Config>::ByteReadWeight::get() * 100_000
+				)
+		);
+		// Value is read from the storage
+		assert!(
+			RawStorageReader::<Runtime>::base_gas_cost()
+				> <Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
+					<Runtime as frame_system::Config>::DbWeight::get().reads(1)
+				)
 		);
+
+		let invalid_cost = RawStorageReader::<Runtime>::base_gas_cost();
 		assert_noop!(
 			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
 				input.encode(),
@@ -162,3 +178,245 @@ fn cost() {
 		);
 	});
 }
+
+#[test]
+fn access_control() {
+	ext().execute_with(|| {
+		sp_io::storage::set(sp_core::storage::well_known_keys::CODE, &[1, 2, 3]);
+		MapWithBlake2_128::insert(Bytes::with_len(10), Bytes::with_len(100));
+
+		let input =
+			RawStorageReaderInput::new(sp_core::storage::well_known_keys::CODE, Params::None);
+		assert_noop!(
+			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(10_000),
+				DUMMY_CTX
+			)),
+			Error::AccessDenied
+		);
+
+		// Entries denied by their names can't be read using their hashed keys
+		Restricted::put(Bytes::with_len(10));
+		let input = RawStorageReaderInput::new(Restricted::hashed_key(), Params::None);
+		assert_noop!(
+			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(10_000),
+				DUMMY_CTX
+			)),
+			Error::AccessDenied
+		);
+
+		let input = RawStorageReaderInput::new(
+			MapWithBlake2_128::hashed_key_for(Bytes::with_len(10)),
+			Params::None,
+		);
+		assert!(RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(10_000),
+			DUMMY_CTX
+		))
+		.is_ok());
+		assert_noop!(
+			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(10_000),
+				evm::Context {
+					caller: H160::repeat_byte(0xde),
+					..DUMMY_CTX
+				}
+			)),
+			Error::AccessDenied
+		);
+	});
+}
+
+#[test]
+fn pallet_entry_list_raw_keys() {
+	use crate::meta_storage_reader::{AllowList, DenyList, StorageEntryList};
+	use pallet_evm::StorageReadFilter;
+	use sp_io::hashing::twox_128;
+
+	frame_support::parameter_types! {
+		pub const Listed: StorageEntryList = &[("TestStorage", Some("Restricted")), ("System", None)];
+	}
+	type Allowed = AllowList<Listed>;
+	type Denied = DenyList<Listed>;
+
+	let caller = H160::zero();
+	let restricted = Restricted::hashed_key();
+	let system = [twox_128(b"System"), twox_128(b"Number")].concat();
+	let other = MapWithBlake2_128::hashed_key_for(Bytes::with_len(10));
+
+	assert!(Allowed::allows_raw_key(&caller, &restricted));
+	assert!(Allowed::allows_raw_key(&caller, &system));
+	assert!(!Allowed::allows_raw_key(&caller, &other));
+	assert!(!Allowed::allows_raw_key(&caller, &restricted[..16]));
+
+	assert!(!Denied::allows_raw_key(&caller, &restricted));
+	assert!(!Denied::allows_raw_key(&caller, &system));
+	assert!(Denied::allows_raw_key(&caller, &other));
+	assert!(Denied::allows_raw_key(
+		&caller,
+		sp_core::storage::well_known_keys::CODE
+	));
+}
+
+#[test]
+fn solidity_input() {
+	use crate::{
+		common::{
+			output::OutputStorageValue,
+			solidity::{self, Encoding},
+		},
+		meta_storage_reader::abi::{self, Token},
+	};
+
+	ext().execute_with(|| {
+		MapWithBlake2_128::insert(Bytes::with_len(10), Bytes::with_len(100));
+
+		let keys = [
+			MapWithBlake2_128::hashed_key_for(Bytes::with_len(10)),
+			vec![1, 2, 3],
+		];
+		let params = [
+			Params::None,
+			Params::Offset(10),
+			Params::Len(20),
+			Params::OffsetAndLen {
+				offset: 10,
+				len: 20,
+			},
+		];
+
+		for key in keys {
+			for params in params.clone() {
+				let input = RawStorageReaderInput::new(key.clone(), params);
+				let solidity_input = input.encode_solidity();
+
+				let decoded = RawStorageReaderInput::decode_solidity(&solidity_input).unwrap();
+				assert_eq!(decoded.encode(), input.encode());
+
+				let scale_output = RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+					input.encode(),
+					Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+					DUMMY_CTX,
+				))
+				.unwrap()
+				.output;
+				let solidity_output = RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+					solidity_input,
+					Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+					DUMMY_CTX,
+				))
+				.unwrap()
+				.output;
+
+				assert_eq!(
+					solidity_output,
+					solidity::encode_output(
+						&OutputStorageValue::decode_from_bytes(&scale_output),
+						Encoding::Solidity
+					)
+				);
+			}
+		}
+
+		let input = RawStorageReaderInput::new(vec![1, 2, 3], Params::None);
+		assert_eq!(
+			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode_solidity(),
+				Some(RawStorageReader::<Runtime>::base_gas_cost() + 5_000),
+				DUMMY_CTX,
+			))
+			.unwrap()
+			.output,
+			abi::encode_params(&[Token::bool(false), Token::Bytes(vec![])])
+		);
+	});
+}
+
+#[test]
+fn proof_access() {
+	use crate::common::proof::StorageProof;
+
+	let mut ext = ext();
+	ext.execute_with(|| MapWithBlake2_128::insert(Bytes::with_len(10), Bytes::with_len(100)));
+	ext.commit_all().unwrap();
+
+	let key = MapWithBlake2_128::hashed_key_for(Bytes::with_len(10));
+	let non_existent_key = MapWithBlake2_128::hashed_key_for(Bytes::with_len(11));
+	let backend = ext.as_backend();
+	let state_root = *backend.root();
+	let proof = StorageProof::new(
+		state_root,
+		sp_state_machine::prove_read(backend, [&key, &non_existent_key])
+			.unwrap()
+			.into_iter_nodes(),
+	);
+
+	ext.execute_with(|| {
+		// Value is read from the proof, not from the storage
+		MapWithBlake2_128::remove(Bytes::with_len(10));
+
+		let input =
+			RawStorageReaderInput::new(&key[..], Params::Offset(10)).with_proof(proof.clone());
+		let decoded = RawStorageReaderInput::decode(&mut &input.encode()[..]).unwrap();
+		assert_eq!(decoded.proof, Some(proof.clone()));
+
+		let mut handle = MockHandle::new(input.encode(), Some(1_000_000), DUMMY_CTX);
+		let out = RawStorageReader::<Runtime>::execute(&mut handle).unwrap();
+		assert_eq!(out.output[0], 1);
+		assert_eq!(&out.output[1..], &Bytes::with_len(100).encode()[10..]);
+		assert_eq!(
+			handle.gas_used,
+			RawStorageReader::<Runtime>::base_gas_cost()
+				+ RawStorageReader::<Runtime>::proof_gas_cost(Some(&proof))
+				+ RawStorageReader::<Runtime>::output_gas_cost(Bytes::with_len(100).encode().len())
+		);
+
+		let input = RawStorageReaderInput::new(&key[..], Params::None).with_proof(proof.clone());
+		assert_noop!(
+			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(RawStorageReader::<Runtime>::base_gas_cost()),
+				DUMMY_CTX
+			)),
+			evm::ExitError::OutOfGas
+		);
+
+		let input = RawStorageReaderInput::new(&non_existent_key[..], Params::None)
+			.with_proof(proof.clone());
+		let out = RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+			input.encode(),
+			Some(1_000_000),
+			DUMMY_CTX,
+		))
+		.unwrap();
+		assert_eq!(out.output, vec![0]);
+
+		let invalid_root_proof = StorageProof::new(H256::repeat_byte(1), proof.nodes.clone());
+		let input =
+			RawStorageReaderInput::new(&key[..], Params::None).with_proof(invalid_root_proof);
+		assert_noop!(
+			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Error::InvalidProof
+		);
+
+		let incomplete_proof = StorageProof::new(state_root, proof.nodes[..1].to_vec());
+		let input = RawStorageReaderInput::new(&key[..], Params::None).with_proof(incomplete_proof);
+		assert_noop!(
+			RawStorageReader::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Error::InvalidProof
+		);
+	});
+}
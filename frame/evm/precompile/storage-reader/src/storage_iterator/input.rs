@@ -0,0 +1,120 @@
+use codec::{Decode, Encode};
+use sp_std::{borrow::Cow, prelude::*};
+
+pub use crate::common::params::Params;
+use crate::meta_storage_reader::key::Key;
+
+/// Prefix of the keys to be iterated.
+#[derive(Encode, Decode, Debug, Clone)]
+pub enum IterationPrefix<'a> {
+	/// Raw prefix bytes.
+	Raw(Vec<u8>),
+	/// Prefix of the pallet storage entry.
+	PalletEntry {
+		/// Target pallet name (for ex. `System`)
+		pallet: Cow<'a, str>,
+		/// Target pallet storage entry (for ex. `Account`)
+		entry: Cow<'a, str>,
+	},
+	/// Prefix of the pallet storage entry members addressed by the partial key
+	/// (i.e. first N keys of the `DoubleMap`/`NMap`).
+	PalletEntryKey {
+		/// Target pallet name (for ex. `System`)
+		pallet: Cow<'a, str>,
+		/// Target pallet storage entry (for ex. `Account`)
+		entry: Cow<'a, str>,
+		/// Partial key hashed according to the storage entry metadata.
+		key: Key,
+	},
+}
+
+/// Input for the `StorageIterator` precompile.
+#[derive(Encode, Decode, Debug, Clone)]
+pub struct StorageIteratorInput<'a> {
+	/// Prefix of the keys to be iterated.
+	pub prefix: IterationPrefix<'a>,
+	/// Raw key to start iteration after (exclusive). Must start with the prefix.
+	pub start_key: Option<Vec<u8>>,
+	/// Max amount of the (key, value) pairs to be returned.
+	#[codec(compact)]
+	pub page_size: u32,
+	/// Additional params (offset and length) applied to each value.
+	pub params: Params,
+}
+
+impl<'a> StorageIteratorInput<'a> {
+	/// Constructs `StorageIteratorInput` with supplied arguments.
+	///
+	/// - prefix of the keys to be iterated
+	/// - optional raw key to start iteration after
+	/// - max amount of the (key, value) pairs to be returned
+	/// - optional offset and length to be applied to each value bytes
+	pub fn new(
+		prefix: IterationPrefix<'a>,
+		start_key: Option<Vec<u8>>,
+		page_size: u32,
+		params: impl Into<Params>,
+	) -> Self {
+		Self {
+			prefix,
+			start_key,
+			page_size,
+			params: params.into(),
+		}
+	}
+
+	/// Constructs `StorageIteratorInput` iterating over the pallet storage entry members.
+	pub fn pallet_entry(
+		pallet: impl Into<Cow<'a, str>>,
+		entry: impl Into<Cow<'a, str>>,
+		start_key: Option<Vec<u8>>,
+		page_size: u32,
+		params: impl Into<Params>,
+	) -> Self {
+		Self::new(
+			IterationPrefix::PalletEntry {
+				pallet: pallet.into(),
+				entry: entry.into(),
+			},
+			start_key,
+			page_size,
+			params,
+		)
+	}
+
+	/// Constructs `StorageIteratorInput` iterating over the pallet storage entry members addressed by the partial key.
+	pub fn pallet_entry_key(
+		pallet: impl Into<Cow<'a, str>>,
+		entry: impl Into<Cow<'a, str>>,
+		key: impl Into<Key>,
+		start_key: Option<Vec<u8>>,
+		page_size: u32,
+		params: impl Into<Params>,
+	) -> Self {
+		Self::new(
+			IterationPrefix::PalletEntryKey {
+				pallet: pallet.into(),
+				entry: entry.into(),
+				key: key.into(),
+			},
+			start_key,
+			page_size,
+			params,
+		)
+	}
+
+	/// Constructs `StorageIteratorInput` iterating over the keys starting with the raw prefix.
+	pub fn raw(
+		prefix: impl Into<Vec<u8>>,
+		start_key: Option<Vec<u8>>,
+		page_size: u32,
+		params: impl Into<Params>,
+	) -> Self {
+		Self::new(
+			IterationPrefix::Raw(prefix.into()),
+			start_key,
+			page_size,
+			params,
+		)
+	}
+}
@@ -0,0 +1,268 @@
+use core::marker::PhantomData;
+
+use codec::{Decode, Encode};
+use evm::{ExitError, ExitSucceed};
+use fp_evm::{Precompile, PrecompileFailure};
+use pallet_evm::{
+	GasWeightMapping, PrecompileHandle, PrecompileOutput, PrecompileResult, StorageReadFilter,
+};
+
+use frame_support::{log::debug, traits::Get};
+use sp_std::{borrow::Cow, prelude::*};
+
+pub mod input;
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	common::output::RawStorageValue,
+	meta_storage_reader::{
+		key::{pallet_entry_prefix, pallet_entry_prefix_hashing_weight},
+		lookup_entry_metadata, with_storage_metadata_cache, PalletStorageMetadataProvider,
+		PortableStorageEntryMetadata,
+	},
+	raw_storage_reader::RawStorageReader,
+};
+use input::{IterationPrefix, StorageIteratorInput};
+
+/// Precompile allowing to iterate over the storage keys sharing the same prefix.
+/// Prefix can be either supplied as raw bytes or derived from the pallet storage entry
+/// (optionally followed by the partial key, i.e. first N keys of the `DoubleMap`/`NMap`).
+/// Returns at most `page_size` (key, value) pairs following the start key (or the prefix itself).
+/// To get the next page, supply the last returned key as the start key.
+/// Each visited key is charged as a storage read along with the returned key and value bytes.
+/// Iteration fails once it reaches the key denied by the configured `StorageReadFilter`.
+///
+/// Output:
+/// - compact encoded amount of the (key, value) pairs
+/// - each pair represented as:
+///     - compact encoded length of the raw key followed by its bytes
+///     - compact encoded length of the value followed by its bytes (with applied offset and length)
+///
+/// Input:
+/// - byte representing prefix kind: 0 - raw prefix, 1 - pallet storage entry, 2 - pallet storage entry with the partial key
+/// - for raw prefix: compact encoded length of the prefix followed by its bytes
+/// - for pallet storage entry: compact encoded length of the pallet name followed by its bytes,
+/// compact encoded length of the pallet's storage member name followed by its bytes
+/// - for pallet storage entry with the partial key: same as for pallet storage entry followed by
+/// the key encoded the same way as for `MetaStorageReader`
+/// - byte representing presence (1) or absence (0) of the start key
+/// - compact encoded length of the start key followed by its bytes (if present)
+/// - compact encoded page size
+/// - byte representing params: 0 - no additional params, 1 - offset, 2 - length, 3 - offset and length
+/// - corresponding compact encoded offset, length or offset followed by length
+///
+/// # Example call
+///
+/// ```rust
+///  # use codec::{Encode, Decode};
+///  # use pallet_evm_precompile_storage_reader::storage_iterator::input::StorageIteratorInput;
+///  # use pallet_evm_precompile_storage_reader::params::Params;
+///  # let input =
+///  StorageIteratorInput::raw(
+///		vec![1, 2, 3],
+///		Some(vec![1, 2, 3, 4]),
+///		10,
+///		Params::None,
+///	 );
+///  // will be encoded as the following sequence of bytes
+///  # assert_eq!(
+///  vec![
+///     0, // - Raw prefix will be used
+///     12, 1, 2, 3, // - Prefix bytes: length(bytes) << 2 followed by bytes
+///     1, // - Start key will be provided
+///     16, 1, 2, 3, 4, // - Start key bytes: length(bytes) << 2 followed by bytes
+///     40, // - Page size (10 << 2)
+///     0 // - No params will be used
+///  ]
+///  # , input.encode());
+/// ```
+///
+#[derive(Default, Debug, Clone, Copy)]
+pub struct StorageIterator<T>(PhantomData<T>);
+
+impl<T: pallet_evm::Config + PalletStorageMetadataProvider> Precompile for StorageIterator<T> {
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		with_storage_metadata_cache(|| Self::execute_cached(handle))
+	}
+}
+
+impl<T: pallet_evm::Config + PalletStorageMetadataProvider> StorageIterator<T> {
+	/// Executes the precompile sharing the runtime metadata between lookups.
+	fn execute_cached(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let mut input = handle.input();
+		let target_gas = handle.gas_limit();
+
+		debug!(
+			"`StorageIterator` input: {:?}, target gas: {:?}",
+			input, target_gas
+		);
+
+		let StorageIteratorInput {
+			prefix,
+			start_key,
+			page_size,
+			params,
+		} = StorageIteratorInput::decode(&mut input).map_err(Error::Decoding)?;
+
+		let caller = handle.context().caller;
+		if let IterationPrefix::PalletEntry { pallet, entry }
+		| IterationPrefix::PalletEntryKey { pallet, entry, .. } = &prefix
+		{
+			if !T::StorageReadFilter::allows_pallet_entry(&caller, pallet, entry) {
+				return Err(Error::AccessDenied.into());
+			}
+		}
+
+		let mut total_gas_cost = Self::base_gas_cost(&prefix)?;
+		crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+		let raw_prefix = Self::raw_prefix(&prefix)?;
+		if matches!(&start_key, Some(key) if !key.starts_with(&raw_prefix)) {
+			return Err(Error::InvalidStartKey.into());
+		}
+
+		let mut pairs = Vec::new();
+		// Without the start key, the prefix itself is the first key to be visited
+		let mut next_key = match start_key {
+			Some(start_key) => {
+				total_gas_cost = total_gas_cost.saturating_add(Self::key_gas_cost());
+				crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+				Self::next_key(&start_key, &raw_prefix)
+			}
+			None => Some(raw_prefix),
+		};
+
+		while pairs.len() < page_size as usize {
+			let key = match next_key.take() {
+				Some(key) => key,
+				None => break,
+			};
+
+			if !T::StorageReadFilter::allows_raw_key(&caller, &key) {
+				return Err(Error::AccessDenied.into());
+			}
+
+			let value = RawStorageReader::<T>::read(&key);
+			let returned_len = match value {
+				RawStorageValue::Item(ref bytes) => key.len().saturating_add(bytes.len()),
+				RawStorageValue::None => 0,
+			};
+			total_gas_cost = total_gas_cost
+				.saturating_add(RawStorageReader::<T>::base_gas_cost())
+				.saturating_add(RawStorageReader::<T>::output_gas_cost(returned_len));
+			crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+			let value = value.apply_params(&params).into_item();
+			// Look up the following key only if the page won't be filled by the current pair
+			if pairs.len() + (value.is_some() as usize) < page_size as usize {
+				total_gas_cost = total_gas_cost.saturating_add(Self::key_gas_cost());
+				crate::ensure_enough_gas!(target_gas >= total_gas_cost);
+
+				next_key = Self::next_key(&key, &raw_prefix);
+			}
+
+			if let Some(value) = value {
+				pairs.push((key, value.to_vec()));
+			}
+		}
+
+		handle.record_cost(total_gas_cost)?;
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: pairs.encode(),
+		})
+	}
+}
+
+impl<T: pallet_evm::Config + PalletStorageMetadataProvider> StorageIterator<T> {
+	/// Returns the key following the supplied one if it starts with the given prefix.
+	pub(super) fn next_key(key: &[u8], prefix: &[u8]) -> Option<Vec<u8>> {
+		sp_io::storage::next_key(key).filter(|next_key| next_key.starts_with(prefix))
+	}
+
+	/// Converts supplied prefix to the raw prefix bytes.
+	pub(super) fn raw_prefix(prefix: &IterationPrefix) -> Result<Vec<u8>, Error> {
+		let raw_prefix = match prefix {
+			IterationPrefix::Raw(prefix) => prefix.clone(),
+			IterationPrefix::PalletEntry { pallet, entry } => {
+				pallet_entry_prefix(pallet, entry).to_vec()
+			}
+			IterationPrefix::PalletEntryKey { pallet, entry, key } => {
+				let (entry_meta, _) = Self::entry_metadata(pallet, entry)?;
+
+				key.to_pallet_entry_storage_prefix(pallet, entry, &entry_meta.ty)
+					.ok_or(Error::InvalidKey)?
+			}
+		};
+
+		Ok(raw_prefix)
+	}
+
+	/// Base gas cost for starting the iteration. Includes entry metadata lookup and prefix hashing (if needed).
+	pub(super) fn base_gas_cost(prefix: &IterationPrefix) -> Result<u64, Error> {
+		let (hashing_weight, lookup_gas_cost) = match prefix {
+			IterationPrefix::Raw(_) => return Ok(0),
+			IterationPrefix::PalletEntry { pallet, entry } => {
+				let (_, lookup_gas_cost) = Self::entry_metadata(pallet, entry)?;
+
+				(
+					pallet_entry_prefix_hashing_weight(pallet, entry),
+					lookup_gas_cost,
+				)
+			}
+			IterationPrefix::PalletEntryKey { pallet, entry, key } => {
+				let (entry_meta, lookup_gas_cost) = Self::entry_metadata(pallet, entry)?;
+
+				(
+					key.full_prefix_hashing_weight(pallet, entry, &entry_meta.ty)
+						.ok_or(Error::InvalidKey)?,
+					lookup_gas_cost,
+				)
+			}
+		};
+
+		Ok(T::GasWeightMapping::weight_to_gas(hashing_weight).saturating_add(lookup_gas_cost))
+	}
+
+	/// Gas cost for looking up the next key.
+	pub(super) fn key_gas_cost() -> u64 {
+		T::GasWeightMapping::weight_to_gas(T::DbWeight::get().reads(1))
+	}
+
+	/// Looks up the entry metadata returning it along with the lookup gas cost.
+	fn entry_metadata(
+		pallet: &str,
+		entry: &str,
+	) -> Result<(PortableStorageEntryMetadata, u64), Error> {
+		lookup_entry_metadata::<T>(pallet, entry).ok_or(Error::PalletStorageEntryNotFound)
+	}
+}
+
+/// An error produced by the `StorageIterator`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+	PalletStorageEntryNotFound,
+	InvalidKey,
+	InvalidStartKey,
+	AccessDenied,
+	Decoding(codec::Error),
+}
+
+impl From<Error> for PrecompileFailure {
+	fn from(err: Error) -> Self {
+		let msg = match err {
+			Error::PalletStorageEntryNotFound => "Pallet storage entry not found",
+			Error::InvalidKey => "Invalid key",
+			Error::InvalidStartKey => "Start key doesn't match the prefix",
+			Error::AccessDenied => "Storage access denied",
+			Error::Decoding(_) => "Failed to decode input",
+		};
+
+		PrecompileFailure::Error {
+			exit_status: ExitError::Other(Cow::Borrowed(msg)),
+		}
+	}
+}
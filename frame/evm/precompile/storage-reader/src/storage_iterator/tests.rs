@@ -0,0 +1,265 @@
+use codec::{Decode, Encode};
+use fp_evm::Precompile;
+use frame_support::{assert_noop, StorageDoubleMap, StorageMap, StorageValue};
+use pallet_evm_test_vector_support::MockHandle;
+
+use sp_core::{H160, U256};
+
+use crate::{common::params::Params, meta_storage_reader::key::MapKey};
+
+use super::*;
+use crate::mock::{test_storage::*, *};
+
+const DUMMY_CTX: evm::Context = evm::Context {
+	address: H160([0; 20]),
+	caller: H160([0; 20]),
+	apparent_value: U256([0; 4]),
+};
+
+pub fn ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+
+	t.into()
+}
+
+type Pairs = Vec<(Vec<u8>, Vec<u8>)>;
+
+fn iterate(input: StorageIteratorInput) -> Pairs {
+	let out = StorageIterator::<Runtime>::execute(&mut MockHandle::new(
+		input.encode(),
+		Some(1_000_000),
+		DUMMY_CTX,
+	))
+	.unwrap();
+
+	Decode::decode(&mut &out.output[..]).unwrap()
+}
+
+#[test]
+fn pallet_entry_iteration() {
+	ext().execute_with(|| {
+		for i in 0..5 {
+			MapWithBlake2_128Concat::insert(Bytes::with_len(i), Bytes::with_len(i + 10));
+		}
+
+		let mut pairs = Pairs::new();
+		let mut start_key = None;
+		loop {
+			let page = iterate(StorageIteratorInput::pallet_entry(
+				"TestStorage",
+				"MapWithBlake2_128Concat",
+				start_key,
+				2,
+				Params::None,
+			));
+			assert!(page.len() <= 2);
+
+			start_key = page.last().map(|(key, _)| key.clone());
+			pairs.extend(page);
+
+			if start_key.is_none() {
+				break;
+			}
+		}
+
+		let mut expected: Pairs = (0..5)
+			.map(|i| {
+				(
+					MapWithBlake2_128Concat::hashed_key_for(Bytes::with_len(i)),
+					Bytes::with_len(i + 10).encode(),
+				)
+			})
+			.collect();
+		expected.sort();
+		assert_eq!(pairs, expected);
+	})
+}
+
+#[test]
+fn raw_prefix_iteration_with_params() {
+	ext().execute_with(|| {
+		Single::put(Bytes::with_len(10));
+		SingleDefault::put(Bytes::with_len(20));
+
+		let prefix = Single::hashed_key()[..16].to_vec();
+		let pairs = iterate(StorageIteratorInput::raw(
+			prefix.clone(),
+			None,
+			10,
+			Params::OffsetAndLen { offset: 1, len: 2 },
+		));
+
+		let mut expected = vec![
+			(Single::hashed_key().to_vec(), vec![0, 1]),
+			(SingleDefault::hashed_key().to_vec(), vec![0, 1]),
+		];
+		expected.sort();
+		assert_eq!(pairs, expected);
+
+		let pairs = iterate(StorageIteratorInput::raw(
+			prefix.clone(),
+			Some(expected[0].0.clone()),
+			10,
+			Params::None,
+		));
+		assert_eq!(pairs.len(), 1);
+		assert_eq!(pairs[0].0, expected[1].0);
+
+		let pairs = iterate(StorageIteratorInput::raw(prefix, None, 0, Params::None));
+		assert!(pairs.is_empty());
+	})
+}
+
+#[test]
+fn partial_key_iteration() {
+	ext().execute_with(|| {
+		DoubleMap::insert(Bytes::with_len(1), Bytes::with_len(2), Bytes::with_len(3));
+		DoubleMap::insert(Bytes::with_len(1), Bytes::with_len(4), Bytes::with_len(5));
+		DoubleMap::insert(Bytes::with_len(6), Bytes::with_len(7), Bytes::with_len(8));
+
+		let pairs = iterate(StorageIteratorInput::pallet_entry_key(
+			"TestStorage",
+			"DoubleMap",
+			MapKey::new_single(Bytes::with_len(1)),
+			None,
+			10,
+			Params::None,
+		));
+
+		let mut expected = vec![
+			(
+				DoubleMap::hashed_key_for(Bytes::with_len(1), Bytes::with_len(2)),
+				Bytes::with_len(3).encode(),
+			),
+			(
+				DoubleMap::hashed_key_for(Bytes::with_len(1), Bytes::with_len(4)),
+				Bytes::with_len(5).encode(),
+			),
+		];
+		expected.sort();
+		assert_eq!(pairs, expected);
+
+		let pairs = iterate(StorageIteratorInput::pallet_entry_key(
+			"TestStorage",
+			"DoubleMap",
+			MapKey::new_double(Bytes::with_len(1), Bytes::with_len(2)),
+			None,
+			10,
+			Params::None,
+		));
+		assert_eq!(pairs.len(), 1);
+
+		let input = StorageIteratorInput::pallet_entry_key(
+			"TestStorage",
+			"MapDefault",
+			MapKey::new_double(Bytes::with_len(1), Bytes::with_len(2)),
+			None,
+			10,
+			Params::None,
+		);
+		assert_noop!(
+			StorageIterator::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Error::InvalidKey
+		);
+	})
+}
+
+#[test]
+fn invalid_input() {
+	ext().execute_with(|| {
+		let input =
+			StorageIteratorInput::pallet_entry("TestStorage", "Abcde", None, 10, Params::None);
+		assert_noop!(
+			StorageIterator::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Error::PalletStorageEntryNotFound
+		);
+
+		let input = StorageIteratorInput::pallet_entry(
+			"TestStorage",
+			"Single",
+			Some(vec![1, 2, 3]),
+			10,
+			Params::None,
+		);
+		assert_noop!(
+			StorageIterator::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Error::InvalidStartKey
+		);
+	})
+}
+
+#[test]
+fn cost() {
+	ext().execute_with(|| {
+		Single::put(Bytes::with_len(10));
+
+		let prefix = IterationPrefix::PalletEntry {
+			pallet: "TestStorage".into(),
+			entry: "Single".into(),
+		};
+		let input = StorageIteratorInput::new(prefix.clone(), None, 10, Params::None);
+		let mut handle = MockHandle::new(input.encode(), Some(1_000_000), DUMMY_CTX);
+		let out = StorageIterator::<Runtime>::execute(&mut handle).unwrap();
+		let pairs = Pairs::decode(&mut &out.output[..]).unwrap();
+		assert_eq!(pairs.len(), 1);
+
+		let expected_cost = StorageIterator::<Runtime>::base_gas_cost(&prefix).unwrap()
+			+ RawStorageReader::<Runtime>::base_gas_cost()
+			+ RawStorageReader::<Runtime>::output_gas_cost(pairs[0].0.len() + pairs[0].1.len())
+			+ StorageIterator::<Runtime>::key_gas_cost();
+		assert_eq!(handle.gas_used, expected_cost);
+
+		assert_noop!(
+			StorageIterator::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(expected_cost - 1),
+				DUMMY_CTX
+			)),
+			evm::ExitError::OutOfGas
+		);
+	})
+}
+
+#[test]
+fn access_control() {
+	ext().execute_with(|| {
+		Restricted::put(Bytes::with_len(10));
+		sp_io::storage::set(sp_core::storage::well_known_keys::CODE, &[1, 2, 3]);
+
+		let input =
+			StorageIteratorInput::pallet_entry("TestStorage", "Restricted", None, 10, Params::None);
+		assert_noop!(
+			StorageIterator::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Error::AccessDenied
+		);
+
+		// Iteration is refused once the denied key is reached
+		let input = StorageIteratorInput::raw(b":".to_vec(), None, 10, Params::None);
+		assert_noop!(
+			StorageIterator::<Runtime>::execute(&mut MockHandle::new(
+				input.encode(),
+				Some(1_000_000),
+				DUMMY_CTX
+			)),
+			Error::AccessDenied
+		);
+	})
+}
@@ -1,31 +1,17 @@
-//! Autogenerated weights for pallet_evm_precompile_storage_reader
+//! Weights for pallet_evm_precompile_storage_reader
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 3.0.0
-//! DATE: 2022-08-23, STEPS: `[250, ]`, REPEAT: 50, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
-
-// Executed Command:
-// ./target/release/dock-node
-// benchmark
-// --execution
-// wasm
-// --chain
-// dev
-// --wasm-execution
-// compiled
-// --pallet
-// pallet-evm-precompile-storage-reader
-// --extra
-// --extrinsic
-// *
-// --repeat
-// 50
-// --steps
-// 250
-// --template
-// ./node/module-weight-template.hbs
-// --output
-// ./a.rs
+//! The hashing weights (`blake2_*`, `twox_*` and `identity`) were generated using the Substrate
+//! benchmark CLI on 2022-08-23 (STEPS: 250, REPEAT: 50, EXECUTION: Wasm, CHAIN: dev).
+//!
+//! `raw_read`, `entry_metadata_lookup`, `runtime_metadata_build` and
+//! `stored_storage_metadata_read` are NOT benchmarked yet: their values are conservative
+//! estimates. They are to be replaced by the output of
+//!
+//! ```text
+//! ./scripts/benchmark.sh pallet_evm_precompile_storage_reader '*'
+//! ```
+//!
+//! run with the template node built in release mode with the `runtime-benchmarks` feature.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -45,6 +31,10 @@ pub trait WeightInfo {
 	fn twox_256(l: u32) -> Weight;
 	fn twox_64_concat(l: u32) -> Weight;
 	fn identity(l: u32) -> Weight;
+	fn raw_read(l: u32) -> Weight;
+	fn entry_metadata_lookup(e: u32) -> Weight;
+	fn runtime_metadata_build() -> Weight;
+	fn stored_storage_metadata_read() -> Weight;
 }
 
 /// Weights for pallet_evm_precompile_storage_reader using the Substrate node and recommended hardware.
@@ -77,6 +67,22 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	fn identity(_l: u32) -> Weight {
 		Weight::from_ref_time(690_000 as u64)
 	}
+	fn raw_read(l: u32) -> Weight {
+		Weight::from_ref_time(1_342_000 as u64)
+			.saturating_add(Weight::from_ref_time(200 as u64).saturating_mul(l as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+	}
+	fn entry_metadata_lookup(e: u32) -> Weight {
+		Weight::from_ref_time(604_000 as u64)
+			.saturating_add(Weight::from_ref_time(37_000 as u64).saturating_mul(e as u64))
+	}
+	fn runtime_metadata_build() -> Weight {
+		Weight::from_ref_time(2_000_000_000 as u64)
+	}
+	// Storage: StorageReader StorageMetadata (r:1 w:0)
+	fn stored_storage_metadata_read() -> Weight {
+		Weight::from_ref_time(500_000_000 as u64).saturating_add(T::DbWeight::get().reads(1 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -108,4 +114,20 @@ impl WeightInfo for () {
 	fn identity(_l: u32) -> Weight {
 		Weight::from_ref_time(690_000 as u64)
 	}
+	fn raw_read(l: u32) -> Weight {
+		Weight::from_ref_time(1_342_000 as u64)
+			.saturating_add(Weight::from_ref_time(200 as u64).saturating_mul(l as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+	}
+	fn entry_metadata_lookup(e: u32) -> Weight {
+		Weight::from_ref_time(604_000 as u64)
+			.saturating_add(Weight::from_ref_time(37_000 as u64).saturating_mul(e as u64))
+	}
+	fn runtime_metadata_build() -> Weight {
+		Weight::from_ref_time(2_000_000_000 as u64)
+	}
+	fn stored_storage_metadata_read() -> Weight {
+		Weight::from_ref_time(500_000_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+	}
 }
@@ -85,9 +85,9 @@ pub use evm::{
 #[cfg(feature = "std")]
 use fp_evm::GenesisAccount;
 pub use fp_evm::{
-	Account, CallInfo, CreateInfo, ExecutionInfo, FeeCalculator, InvalidEvmTransactionError,
-	LinearCostPrecompile, Log, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
-	PrecompileResult, PrecompileSet, Vicinity,
+	Account, CallInfo, CallOrCreateInfo, CreateInfo, ExecutionInfo, FeeCalculator,
+	InvalidEvmTransactionError, LinearCostPrecompile, Log, Precompile, PrecompileFailure,
+	PrecompileHandle, PrecompileOutput, PrecompileResult, PrecompileSet, StateOverride, Vicinity,
 };
 
 pub use self::{
@@ -120,6 +120,9 @@ pub mod pallet {
 		/// `ByteReadWeight`
 		type ByteReadWeight: Get<Weight>;
 
+		/// Access-control policy of the storage reader precompiles.
+		type StorageReadFilter: StorageReadFilter;
+
 		/// Allow the origin to call on behalf of given address.
 		type CallOrigin: EnsureAddressOrigin<Self::Origin>;
 		/// Allow the origin to withdraw on behalf of given address.
@@ -420,6 +423,8 @@ pub mod pallet {
 		GasLimitTooHigh,
 		/// Undefined error.
 		Undefined,
+		/// State override sets both the whole storage and a storage diff.
+		ConflictingStateOverride,
 	}
 
 	impl<T> From<InvalidEvmTransactionError> for Error<T> {
@@ -634,6 +639,47 @@ impl GasWeightMapping for () {
 	}
 }
 
+/// Access-control policy consulted by the storage reader precompiles before reading the storage
+/// on behalf of the caller.
+pub trait StorageReadFilter {
+	/// Returns `true` if the caller is allowed to read the value stored under the raw storage key.
+	fn allows_raw_key(caller: &H160, key: &[u8]) -> bool;
+
+	/// Returns `true` if the caller is allowed to read the storage entry of the pallet.
+	fn allows_pallet_entry(caller: &H160, pallet: &str, entry: &str) -> bool;
+}
+
+/// Allows reading any storage.
+impl StorageReadFilter for () {
+	fn allows_raw_key(_caller: &H160, _key: &[u8]) -> bool {
+		true
+	}
+
+	fn allows_pallet_entry(_caller: &H160, _pallet: &str, _entry: &str) -> bool {
+		true
+	}
+}
+
+/// Tuple of filters allows reading only if all of its members allow it.
+macro_rules! impl_storage_read_filter_for_tuples {
+	($($filter: ident),+) => {
+		impl<$($filter: StorageReadFilter),+> StorageReadFilter for ($($filter,)+) {
+			fn allows_raw_key(caller: &H160, key: &[u8]) -> bool {
+				$($filter::allows_raw_key(caller, key))&&+
+			}
+
+			fn allows_pallet_entry(caller: &H160, pallet: &str, entry: &str) -> bool {
+				$($filter::allows_pallet_entry(caller, pallet, entry))&&+
+			}
+		}
+	};
+}
+
+impl_storage_read_filter_for_tuples!(A);
+impl_storage_read_filter_for_tuples!(A, B);
+impl_storage_read_filter_for_tuples!(A, B, C);
+impl_storage_read_filter_for_tuples!(A, B, C, D);
+
 static LONDON_CONFIG: EvmConfig = EvmConfig::london();
 
 impl<T: Config> Pallet<T> {
@@ -678,6 +724,55 @@ impl<T: Config> Pallet<T> {
 		<AccountCodes<T>>::insert(address, code);
 	}
 
+	/// Apply the account state override, the result is expected to be discarded afterwards.
+	/// The override can't set both the whole storage and a storage diff.
+	pub fn apply_state_override(state_override: &StateOverride) -> Result<(), Error<T>> {
+		if state_override.state.is_some() && state_override.state_diff.is_some() {
+			return Err(Error::<T>::ConflictingStateOverride);
+		}
+
+		let address = state_override.address;
+		let account_id = T::AddressMapping::into_account_id(address);
+
+		if let Some(balance) = state_override.balance {
+			let balance = UniqueSaturatedInto::<u128>::unique_saturated_into(balance);
+			T::Currency::make_free_balance_be(&account_id, balance.unique_saturated_into());
+		}
+
+		if let Some(nonce) = state_override.nonce {
+			let nonce = UniqueSaturatedInto::<u128>::unique_saturated_into(nonce);
+			frame_system::Account::<T>::mutate(&account_id, |account| {
+				account.nonce = nonce.unique_saturated_into();
+			});
+		}
+
+		if let Some(code) = &state_override.code {
+			if code.is_empty() {
+				if <AccountCodes<T>>::contains_key(address) {
+					let _ = frame_system::Pallet::<T>::dec_sufficients(&account_id);
+				}
+				<AccountCodes<T>>::remove(address);
+			} else {
+				Self::create_account(address, code.clone());
+			}
+		}
+
+		if let Some(state) = &state_override.state {
+			let _ = <AccountStorages<T>>::clear_prefix(address, u32::MAX, None);
+			for (index, value) in state {
+				<AccountStorages<T>>::insert(address, index, value);
+			}
+		}
+
+		if let Some(state_diff) = &state_override.state_diff {
+			for (index, value) in state_diff {
+				<AccountStorages<T>>::insert(address, index, value);
+			}
+		}
+
+		Ok(())
+	}
+
 	/// Get the account basic in EVM format.
 	pub fn account_basic(address: &H160) -> (Account, frame_support::weights::Weight) {
 		let account_id = T::AddressMapping::into_account_id(*address);
@@ -139,6 +139,7 @@ impl crate::Config for Test {
 	type Currency = Balances;
 	type Runner = crate::runner::stack::Runner<Self>;
 	type ByteReadWeight = ByteReadWeight;
+	type StorageReadFilter = ();
 
 	type Event = Event;
 	type PrecompilesType = ();
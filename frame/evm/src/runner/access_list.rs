@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Access list of the EVM executed by the stack runner.
+//!
+//! The accounts and storage slots are read from the ones the stack runner marks as accessed to
+//! charge the cold accesses (EIP-2929), so unlike the tracers it doesn't require the EVM to emit
+//! its tracing events. They are only tracked by the configs charging the cold accesses, from
+//! Berlin on.
+
+use evm::executor::stack::Accessed;
+pub use fp_evm::tracing::AccessListInfo;
+use sp_core::{H160, H256};
+use sp_std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	vec::Vec,
+};
+
+environmental::environmental!(recorder: AccessListRecorder);
+
+/// Executes `f` while recording the accounts and storage slots accessed by the stack runner.
+pub fn record_access_list<R, F: FnOnce() -> R>(f: F) -> (R, AccessListRecorder) {
+	let mut access_list = AccessListRecorder::default();
+	let result = recorder::using(&mut access_list, f);
+	(result, access_list)
+}
+
+/// Records the accesses of a substate. The accesses of the reverted substates are recorded as
+/// well, as done by geth.
+pub(crate) fn record_accessed(accessed: &Accessed) {
+	recorder::with(|recorder| {
+		for address in &accessed.accessed_addresses {
+			recorder.accessed.entry(*address).or_default();
+		}
+		for (address, index) in &accessed.accessed_storage {
+			recorder
+				.accessed
+				.entry(*address)
+				.or_default()
+				.insert(*index);
+		}
+	});
+}
+
+/// Records the sender or recipient of a transaction, which is accessed by every transaction.
+pub(crate) fn record_transactor(address: H160) {
+	recorder::with(|recorder| {
+		recorder.transactors.insert(address);
+	});
+}
+
+/// Collects the accounts and storage slots accessed by the execution, as done by geth's access
+/// list tracer.
+#[derive(Default)]
+pub struct AccessListRecorder {
+	/// Sender and recipient of the transaction, which are accessed by every transaction.
+	transactors: BTreeSet<H160>,
+	accessed: BTreeMap<H160, BTreeSet<H256>>,
+}
+
+impl AccessListRecorder {
+	/// Returns the accessed accounts along with their accessed storage slots. The accounts of
+	/// which no slot was accessed are left out if they are the sender or recipient of the
+	/// transaction, or match `is_excluded` (for ex. the precompiles).
+	pub fn finish(self, is_excluded: impl Fn(H160) -> bool) -> Vec<(H160, Vec<H256>)> {
+		let transactors = self.transactors;
+		self.accessed
+			.into_iter()
+			.filter(|(address, slots)| {
+				!slots.is_empty() || !(transactors.contains(address) || is_excluded(*address))
+			})
+			.map(|(address, slots)| (address, slots.into_iter().collect()))
+			.collect()
+	}
+}
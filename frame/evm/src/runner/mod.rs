@@ -15,7 +15,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod access_list;
 pub mod stack;
+#[cfg(feature = "tracing")]
+pub mod tracing;
 
 use crate::Config;
 use fp_evm::{CallInfo, CreateInfo};
@@ -18,9 +18,9 @@
 //! EVM stack-based runner.
 
 use crate::{
-	runner::Runner as RunnerT, AccountCodes, AccountStorages, AddressMapping, BalanceOf,
-	BlockHashMapping, Config, Error, Event, FeeCalculator, OnChargeEVMTransaction, Pallet,
-	RunnerError,
+	runner::{access_list, Runner as RunnerT},
+	AccountCodes, AccountStorages, AddressMapping, BalanceOf, BlockHashMapping, Config, Error,
+	Event, FeeCalculator, OnChargeEVMTransaction, Pallet, RunnerError,
 };
 use evm::{
 	backend::Backend as BackendT,
@@ -161,6 +161,11 @@ where
 
 		let state = executor.into_state();
 
+		access_list::record_transactor(source);
+		if let Some(accessed) = state.substate.metadata.accessed() {
+			access_list::record_accessed(accessed);
+		}
+
 		for address in state.substate.deletes {
 			log::debug!(
 				target: "evm",
@@ -288,7 +293,10 @@ where
 			config,
 			&precompiles,
 			is_transactional,
-			|executor| executor.transact_call(source, target, value, input, gas_limit, access_list),
+			|executor| {
+				access_list::record_transactor(target);
+				executor.transact_call(source, target, value, input, gas_limit, access_list)
+			},
 		)
 	}
 
@@ -332,6 +340,7 @@ where
 			is_transactional,
 			|executor| {
 				let address = executor.create_address(evm::CreateScheme::Legacy { caller: source });
+				access_list::record_transactor(address);
 				let (reason, _) =
 					executor.transact_create(source, value, init, gas_limit, access_list);
 				(reason, address)
@@ -385,6 +394,7 @@ where
 					code_hash,
 					salt,
 				});
+				access_list::record_transactor(address);
 				let (reason, _) =
 					executor.transact_create2(source, value, init, salt, gas_limit, access_list);
 				(reason, address)
@@ -438,6 +448,9 @@ impl<'config> SubstrateStackSubstate<'config> {
 	pub fn exit_revert(&mut self) -> Result<(), ExitError> {
 		let mut exited = *self.parent.take().expect("Cannot discard on root substate");
 		mem::swap(&mut exited, self);
+		if let Some(accessed) = exited.metadata.accessed() {
+			access_list::record_accessed(accessed);
+		}
 		self.metadata.swallow_revert(exited.metadata)?;
 
 		sp_io::storage::rollback_transaction();
@@ -447,6 +460,9 @@ impl<'config> SubstrateStackSubstate<'config> {
 	pub fn exit_discard(&mut self) -> Result<(), ExitError> {
 		let mut exited = *self.parent.take().expect("Cannot discard on root substate");
 		mem::swap(&mut exited, self);
+		if let Some(accessed) = exited.metadata.accessed() {
+			access_list::record_accessed(accessed);
+		}
 		self.metadata.swallow_discard(exited.metadata)?;
 
 		sp_io::storage::rollback_transaction();
@@ -0,0 +1,492 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracers of the EVM executed by the stack runner, built on `evm`'s event listener hooks.
+
+use core::cell::RefCell;
+use evm::{
+	tracing::{Event as EvmEvent, EventListener as EvmListener},
+	CreateScheme, ExitReason,
+};
+use evm_gasometer::tracing::{Event as GasometerEvent, EventListener as GasometerListener};
+use evm_runtime::tracing::{Event as RuntimeEvent, EventListener as RuntimeListener};
+pub use fp_evm::tracing::{CallFrame, CallType, StructLog, StructLoggerTrace, Trace, TracerConfig};
+use sp_core::{H160, H256, U256};
+use sp_std::{collections::btree_map::BTreeMap, rc::Rc, vec::Vec};
+
+/// Executes `f` with the tracer configured by `config` listening to the EVM events.
+///
+/// The returned tracer is to be finished with the gas used by the traced operation.
+pub fn trace<R, F: FnOnce() -> R>(config: TracerConfig, f: F) -> (R, Tracer) {
+	let tracer = Rc::new(RefCell::new(Tracer::new(config)));
+
+	let mut evm_listener = ListenerProxy(Rc::clone(&tracer));
+	let mut runtime_listener = ListenerProxy(Rc::clone(&tracer));
+	let mut gasometer_listener = ListenerProxy(Rc::clone(&tracer));
+
+	let result = evm::tracing::using(&mut evm_listener, || {
+		evm_runtime::tracing::using(&mut runtime_listener, || {
+			evm_gasometer::tracing::using(&mut gasometer_listener, f)
+		})
+	});
+
+	let tracer = tracer.replace(Tracer::new(config));
+	(result, tracer)
+}
+
+/// Forwards the events of each of the listened crates to the shared tracer.
+struct ListenerProxy<T>(Rc<RefCell<T>>);
+
+impl EvmListener for ListenerProxy<Tracer> {
+	fn event(&mut self, event: EvmEvent) {
+		match &mut *self.0.borrow_mut() {
+			Tracer::StructLogger(tracer) => tracer.evm_event(event),
+			Tracer::CallTracer(tracer) => tracer.evm_event(event),
+		}
+	}
+}
+
+impl RuntimeListener for ListenerProxy<Tracer> {
+	fn event(&mut self, event: RuntimeEvent) {
+		if let Tracer::StructLogger(tracer) = &mut *self.0.borrow_mut() {
+			tracer.runtime_event(event);
+		}
+	}
+}
+
+impl GasometerListener for ListenerProxy<Tracer> {
+	fn event(&mut self, event: GasometerEvent) {
+		match &mut *self.0.borrow_mut() {
+			Tracer::StructLogger(tracer) => tracer.gasometer_event(event),
+			Tracer::CallTracer(tracer) => tracer.gasometer_event(event),
+		}
+	}
+}
+
+/// Tracer collecting the events of the traced execution.
+pub enum Tracer {
+	StructLogger(StructLogger),
+	CallTracer(CallTracer),
+}
+
+impl Tracer {
+	fn new(config: TracerConfig) -> Self {
+		match config {
+			TracerConfig::StructLogger {
+				disable_stack,
+				disable_memory,
+				disable_storage,
+			} => Tracer::StructLogger(StructLogger {
+				disable_stack,
+				disable_memory,
+				disable_storage,
+				depth: 0,
+				storages: BTreeMap::new(),
+				struct_logs: Vec::new(),
+				awaiting_cost: false,
+				exit: None,
+			}),
+			TracerConfig::CallTracer => Tracer::CallTracer(CallTracer {
+				frames: Vec::new(),
+				awaiting_gas: false,
+				stipend: None,
+				root: None,
+				transact: None,
+			}),
+		}
+	}
+
+	/// Builds the trace of the operation which used `used_gas`.
+	pub fn finish(self, used_gas: U256) -> Trace {
+		match self {
+			Tracer::StructLogger(tracer) => Trace::StructLogger(tracer.finish(used_gas)),
+			Tracer::CallTracer(tracer) => Trace::CallTracer(tracer.finish(used_gas)),
+		}
+	}
+}
+
+/// Collects the state of the EVM before each executed step.
+pub struct StructLogger {
+	disable_stack: bool,
+	disable_memory: bool,
+	disable_storage: bool,
+	depth: u32,
+	/// Storage slots accessed so far by each of the contracts.
+	storages: BTreeMap<H160, BTreeMap<H256, H256>>,
+	struct_logs: Vec<StructLog>,
+	/// Whether the gas of the last step is yet to be recorded by the gasometer.
+	awaiting_cost: bool,
+	/// Exit reason and data returned by the traced operation.
+	exit: Option<(ExitReason, Vec<u8>)>,
+}
+
+impl StructLogger {
+	fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::Call { .. } | EvmEvent::Create { .. } => {
+				self.depth += 1;
+				self.awaiting_cost = false;
+			}
+			EvmEvent::Exit {
+				reason,
+				return_value,
+			} => {
+				self.depth = self.depth.saturating_sub(1);
+				self.awaiting_cost = false;
+				if self.depth == 0 {
+					self.exit = Some((reason.clone(), return_value.to_vec()));
+				}
+			}
+			_ => (),
+		}
+	}
+
+	fn runtime_event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::Step {
+				opcode,
+				position,
+				stack,
+				memory,
+				..
+			} => {
+				self.struct_logs.push(StructLog {
+					pc: position.as_ref().map_or(0, |pc| *pc as u64),
+					op: opcode.0,
+					gas: 0,
+					gas_cost: 0,
+					depth: self.depth,
+					stack: (!self.disable_stack).then(|| stack.data().clone()),
+					memory: (!self.disable_memory).then(|| memory.data().clone()),
+					storage: None,
+				});
+				self.awaiting_cost = true;
+			}
+			RuntimeEvent::SLoad {
+				address,
+				index,
+				value,
+			}
+			| RuntimeEvent::SStore {
+				address,
+				index,
+				value,
+			} => {
+				let storage = self.storages.entry(address).or_default();
+				storage.insert(index, value);
+
+				if !self.disable_storage {
+					if let Some(struct_log) = self.struct_logs.last_mut() {
+						struct_log.storage = Some(storage.clone());
+					}
+				}
+			}
+			_ => (),
+		}
+	}
+
+	fn gasometer_event(&mut self, event: GasometerEvent) {
+		if !self.awaiting_cost {
+			return;
+		}
+
+		// Snapshots are taken before the cost is recorded.
+		let (snapshot, cost) = match event {
+			GasometerEvent::RecordCost { cost, snapshot } => (snapshot, cost),
+			GasometerEvent::RecordDynamicCost {
+				gas_cost,
+				memory_gas,
+				snapshot,
+				..
+			} => (
+				snapshot,
+				gas_cost.saturating_add(
+					snapshot
+						.as_ref()
+						.map_or(0, |snapshot| memory_gas.saturating_sub(snapshot.memory_gas)),
+				),
+			),
+			_ => return,
+		};
+
+		if let (Some(snapshot), Some(struct_log)) = (snapshot, self.struct_logs.last_mut()) {
+			struct_log.gas = snapshot
+				.gas_limit
+				.saturating_sub(snapshot.used_gas)
+				.saturating_sub(snapshot.memory_gas);
+			struct_log.gas_cost = cost;
+		}
+		self.awaiting_cost = false;
+	}
+
+	fn finish(self, used_gas: U256) -> StructLoggerTrace {
+		let (failed, return_value) = match self.exit {
+			Some((reason, return_value)) => (!reason.is_succeed(), return_value),
+			None => (true, Vec::new()),
+		};
+
+		StructLoggerTrace {
+			gas: used_gas,
+			failed,
+			return_value,
+			struct_logs: self.struct_logs,
+		}
+	}
+}
+
+/// Collects the tree of the executed calls.
+pub struct CallTracer {
+	/// Calls being executed, the innermost last.
+	frames: Vec<CallFrame>,
+	/// Whether the gas allotted to the innermost call is yet to be recorded.
+	awaiting_gas: bool,
+	/// Gas returned to the caller by the latest gasometer event.
+	stipend: Option<u64>,
+	/// Completed root call.
+	root: Option<CallFrame>,
+	/// Root call of the transaction, used if it fails before the execution starts.
+	transact: Option<CallFrame>,
+}
+
+impl CallTracer {
+	fn frame(
+		call_type: CallType,
+		from: H160,
+		to: H160,
+		value: Option<U256>,
+		input: &[u8],
+		gas: u64,
+	) -> CallFrame {
+		CallFrame {
+			call_type,
+			from,
+			to,
+			value,
+			gas: gas.into(),
+			gas_used: U256::zero(),
+			input: input.to_vec(),
+			output: Vec::new(),
+			exit_reason: None,
+			calls: Vec::new(),
+		}
+	}
+
+	fn enter(&mut self, frame: CallFrame) {
+		self.frames.push(frame);
+		self.awaiting_gas = true;
+		self.stipend = None;
+	}
+
+	fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::TransactCall {
+				caller,
+				address,
+				value,
+				data,
+				gas_limit,
+			} => {
+				self.transact = Some(Self::frame(
+					CallType::Call,
+					caller,
+					address,
+					Some(value),
+					data,
+					gas_limit,
+				));
+			}
+			EvmEvent::TransactCreate {
+				caller,
+				value,
+				init_code,
+				gas_limit,
+				address,
+			} => {
+				self.transact = Some(Self::frame(
+					CallType::Create,
+					caller,
+					address,
+					Some(value),
+					init_code,
+					gas_limit,
+				));
+			}
+			EvmEvent::TransactCreate2 {
+				caller,
+				value,
+				init_code,
+				gas_limit,
+				address,
+				..
+			} => {
+				self.transact = Some(Self::frame(
+					CallType::Create2,
+					caller,
+					address,
+					Some(value),
+					init_code,
+					gas_limit,
+				));
+			}
+			EvmEvent::Call {
+				code_address,
+				transfer,
+				input,
+				target_gas,
+				is_static,
+				context,
+			} => {
+				let call_type = if is_static {
+					CallType::StaticCall
+				} else if code_address == context.address {
+					CallType::Call
+				} else if transfer.is_some() {
+					CallType::CallCode
+				} else {
+					CallType::DelegateCall
+				};
+				let (from, value) = match call_type {
+					CallType::CallCode => (
+						context.address,
+						transfer.as_ref().map(|transfer| transfer.value),
+					),
+					CallType::DelegateCall => (context.address, None),
+					CallType::StaticCall => (context.caller, None),
+					_ => (
+						context.caller,
+						Some(
+							transfer
+								.as_ref()
+								.map_or_else(U256::zero, |transfer| transfer.value),
+						),
+					),
+				};
+
+				self.enter(Self::frame(
+					call_type,
+					from,
+					code_address,
+					value,
+					input,
+					target_gas.unwrap_or_default(),
+				));
+			}
+			EvmEvent::Create {
+				caller,
+				address,
+				scheme,
+				value,
+				init_code,
+				target_gas,
+			} => {
+				let call_type = match scheme {
+					CreateScheme::Create2 { .. } => CallType::Create2,
+					_ => CallType::Create,
+				};
+
+				self.enter(Self::frame(
+					call_type,
+					caller,
+					address,
+					Some(value),
+					init_code,
+					target_gas.unwrap_or_default(),
+				));
+			}
+			EvmEvent::Suicide {
+				address,
+				target,
+				balance,
+			} => {
+				if let Some(parent) = self.frames.last_mut() {
+					let mut frame = Self::frame(
+						CallType::SelfDestruct,
+						address,
+						target,
+						Some(balance),
+						&[],
+						0,
+					);
+					frame.exit_reason = Some(ExitReason::Succeed(evm::ExitSucceed::Suicided));
+					parent.calls.push(frame);
+				}
+			}
+			EvmEvent::Exit {
+				reason,
+				return_value,
+			} => {
+				let mut frame = match self.frames.pop() {
+					Some(frame) => frame,
+					// The transaction failed before entering the root call.
+					None => match self.transact.clone() {
+						Some(frame) => frame,
+						None => return,
+					},
+				};
+
+				// Remaining gas is returned to the caller as a stipend, unless the call errored.
+				frame.gas_used = match reason {
+					ExitReason::Succeed(_) | ExitReason::Revert(_) => frame
+						.gas
+						.saturating_sub(self.stipend.unwrap_or_default().into()),
+					_ => frame.gas,
+				};
+				frame.output = return_value.to_vec();
+				frame.exit_reason = Some(reason.clone());
+
+				self.awaiting_gas = false;
+				self.stipend = None;
+				match self.frames.last_mut() {
+					Some(parent) => parent.calls.push(frame),
+					None => self.root = Some(frame),
+				}
+			}
+			_ => (),
+		}
+	}
+
+	fn gasometer_event(&mut self, event: GasometerEvent) {
+		match event {
+			// The gas allotted to the entered call is recorded as the caller's cost.
+			GasometerEvent::RecordCost { cost, .. } => {
+				if self.awaiting_gas {
+					if let Some(frame) = self.frames.last_mut() {
+						frame.gas = cost.into();
+					}
+					self.awaiting_gas = false;
+				}
+				self.stipend = None;
+			}
+			GasometerEvent::RecordStipend { stipend, .. } => self.stipend = Some(stipend),
+			GasometerEvent::RecordDynamicCost { .. } | GasometerEvent::RecordTransaction { .. } => {
+				self.stipend = None
+			}
+			_ => (),
+		}
+	}
+
+	fn finish(self, used_gas: U256) -> Option<CallFrame> {
+		let mut root = self.root.or_else(|| self.transact.clone())?;
+		// The root call reports the whole transaction gas, including its intrinsic cost.
+		if let Some(transact) = self.transact {
+			root.gas = transact.gas;
+		}
+		root.gas_used = used_gas;
+
+		Some(root)
+	}
+}
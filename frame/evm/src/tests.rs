@@ -562,3 +562,112 @@ fn runner_max_fee_per_gas_gte_max_priority_fee_per_gas() {
 		assert!(res.is_err());
 	});
 }
+
+#[test]
+fn record_access_list() {
+	new_test_ext().execute_with(|| {
+		let contract = H160::from_str("1000000000000000000000000000000000000003").unwrap();
+		let other = H160::from_str("7700000000000000000000000000000000000002").unwrap();
+		let mut code = vec![
+			0x60, 0x01, // PUSH1 1
+			0x54, // SLOAD
+			0x50, // POP
+			0x73, // PUSH20 other
+		];
+		code.extend_from_slice(other.as_bytes());
+		code.extend_from_slice(&[
+			0x31, // BALANCE
+			0x50, // POP
+			0x00, // STOP
+		]);
+		<AccountCodes<Test>>::insert(contract, code);
+
+		let (result, recorder) = crate::runner::access_list::record_access_list(|| {
+			<Test as Config>::Runner::call(
+				H160::default(),
+				contract,
+				Vec::new(),
+				U256::zero(),
+				1000000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				true,  // must be validated
+				&<Test as Config>::config().clone(),
+			)
+		});
+		assert!(result.expect("call succeeds").exit_reason.is_succeed());
+
+		// The caller is left out, not having any accessed slot.
+		assert_eq!(
+			recorder.finish(|_| false),
+			vec![(contract, vec![H256::from_low_u64_be(1)]), (other, vec![])]
+		);
+	});
+}
+
+#[test]
+fn apply_state_override() {
+	new_test_ext().execute_with(|| {
+		let addr = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		<AccountStorages<Test>>::insert(addr, H256::repeat_byte(1), H256::repeat_byte(1));
+		<AccountStorages<Test>>::insert(addr, H256::repeat_byte(2), H256::repeat_byte(2));
+
+		assert_ok!(EVM::apply_state_override(&StateOverride {
+			address: addr,
+			balance: Some(U256::from(42)),
+			nonce: Some(U256::from(7)),
+			code: Some(vec![0x01]),
+			state: None,
+			state_diff: Some(vec![(H256::repeat_byte(1), H256::repeat_byte(3))]),
+		}));
+
+		let (account, _) = EVM::account_basic(&addr);
+		assert_eq!(account.balance, U256::from(42));
+		assert_eq!(account.nonce, U256::from(7));
+		assert_eq!(EVM::account_codes(addr), vec![0x01]);
+		assert_eq!(
+			EVM::account_storages(addr, H256::repeat_byte(1)),
+			H256::repeat_byte(3)
+		);
+		assert_eq!(
+			EVM::account_storages(addr, H256::repeat_byte(2)),
+			H256::repeat_byte(2)
+		);
+
+		// Full state replaces the whole storage.
+		assert_ok!(EVM::apply_state_override(&StateOverride {
+			address: addr,
+			state: Some(vec![(H256::repeat_byte(4), H256::repeat_byte(4))]),
+			..Default::default()
+		}));
+
+		assert_eq!(
+			EVM::account_storages(addr, H256::repeat_byte(1)),
+			H256::zero()
+		);
+		assert_eq!(
+			EVM::account_storages(addr, H256::repeat_byte(2)),
+			H256::zero()
+		);
+		assert_eq!(
+			EVM::account_storages(addr, H256::repeat_byte(4)),
+			H256::repeat_byte(4)
+		);
+		// Fields which aren't set are left untouched.
+		assert_eq!(EVM::account_codes(addr), vec![0x01]);
+
+		// Whole storage and storage diff can't be set together.
+		assert!(matches!(
+			EVM::apply_state_override(&StateOverride {
+				address: addr,
+				state: Some(vec![]),
+				state_diff: Some(vec![]),
+				..Default::default()
+			}),
+			Err(Error::<Test>::ConflictingStateOverride)
+		));
+	});
+}
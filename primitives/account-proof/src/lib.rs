@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of the proofs returned by the Frontier `eth_getProof` RPC.
+//!
+//! Frontier keeps the EVM state in the Substrate state trie rather than in a Merkle-Patricia
+//! trie, so the RPC follows the shape of EIP-1186 but returns Substrate read proofs:
+//!
+//! - `storageHash` is the state root of the requested block, which all the proofs are checked
+//!   against. It has to be obtained from a trusted header.
+//! - `accountProof` holds the trie nodes of the storage entries holding the account nonce and
+//!   balance (the `System::Account` entry of the account mapped to the address in most
+//!   runtimes), and of the `EVM::AccountCodes` entry of the address.
+//! - `storageProof[i].proof` holds the trie nodes of the `EVM::AccountStorages` entry of the
+//!   requested slot.
+//!
+//! A proof is verified by reading the entries from it: [`verify_account`], [`verify_code`] and
+//! [`verify_storage`] fail unless all the trie nodes leading from the state root to the entry
+//! are part of the proof, and return the proven value otherwise. The caller then compares it
+//! with the value returned by the RPC, for ex. the `keccak256` of the proven code with
+//! `codeHash`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use sp_core::{Blake2Hasher, H160, H256};
+use sp_std::prelude::*;
+use sp_trie::LayoutV1;
+// Frontier
+use fp_storage::{EVM_ACCOUNT_CODES, EVM_ACCOUNT_STORAGES, PALLET_EVM};
+
+/// Reason of a failed proof verification.
+#[derive(Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum ProofError {
+	/// The proof doesn't contain the trie nodes leading to the entry from the state root.
+	IncompleteProof,
+	/// The proven value can't be decoded.
+	InvalidValue,
+}
+
+/// Returns the raw key of the `System::Account` entry of the account, which holds its nonce and
+/// balance unless the runtime stores the balances elsewhere.
+pub fn system_account_key<AccountId: Encode>(account_id: &AccountId) -> Vec<u8> {
+	let mut key = storage_prefix(b"System", b"Account");
+	key.extend(blake2_128_concat(&account_id.encode()));
+	key
+}
+
+/// Returns the raw key of the `EVM::AccountCodes` entry of the address.
+pub fn account_code_key(address: H160) -> Vec<u8> {
+	let mut key = storage_prefix(PALLET_EVM, EVM_ACCOUNT_CODES);
+	key.extend(blake2_128_concat(address.as_bytes()));
+	key
+}
+
+/// Returns the raw key of the `EVM::AccountStorages` entry of the slot of the address.
+pub fn account_storage_key(address: H160, index: H256) -> Vec<u8> {
+	let mut key = storage_prefix(PALLET_EVM, EVM_ACCOUNT_STORAGES);
+	key.extend(blake2_128_concat(address.as_bytes()));
+	key.extend(blake2_128_concat(index.as_bytes()));
+	key
+}
+
+/// Reads the raw value stored under the key from the proof, `None` if the proof shows there is
+/// no such entry.
+pub fn read_proof_value(
+	state_root: &H256,
+	proof: &[Vec<u8>],
+	key: &[u8],
+) -> Result<Option<Vec<u8>>, ProofError> {
+	let db = sp_trie::StorageProof::new(proof.iter().cloned()).into_memory_db::<Blake2Hasher>();
+
+	sp_trie::read_trie_value::<LayoutV1<Blake2Hasher>, _>(&db, state_root, key)
+		.map_err(|_| ProofError::IncompleteProof)
+}
+
+/// Verifies the proof of the entry holding the account nonce and balance, decoding it as
+/// `AccountInfo` (for ex. `frame_system::AccountInfo` of the runtime).
+pub fn verify_account<AccountInfo: Decode>(
+	state_root: &H256,
+	proof: &[Vec<u8>],
+	account_key: &[u8],
+) -> Result<Option<AccountInfo>, ProofError> {
+	read_proof_value(state_root, proof, account_key)?
+		.map(|raw| AccountInfo::decode(&mut &raw[..]).map_err(|_| ProofError::InvalidValue))
+		.transpose()
+}
+
+/// Verifies the proof of the code of the address, returning the code, empty if there is none.
+pub fn verify_code(
+	state_root: &H256,
+	proof: &[Vec<u8>],
+	address: H160,
+) -> Result<Vec<u8>, ProofError> {
+	match read_proof_value(state_root, proof, &account_code_key(address))? {
+		Some(raw) => Vec::<u8>::decode(&mut &raw[..]).map_err(|_| ProofError::InvalidValue),
+		None => Ok(Vec::new()),
+	}
+}
+
+/// Verifies the proof of the storage slot of the address, returning the slot value, zero if
+/// it was never set.
+pub fn verify_storage(
+	state_root: &H256,
+	proof: &[Vec<u8>],
+	address: H160,
+	index: H256,
+) -> Result<H256, ProofError> {
+	match read_proof_value(state_root, proof, &account_storage_key(address, index))? {
+		Some(raw) => H256::decode(&mut &raw[..]).map_err(|_| ProofError::InvalidValue),
+		None => Ok(H256::zero()),
+	}
+}
+
+fn storage_prefix(module: &[u8], storage: &[u8]) -> Vec<u8> {
+	[
+		sp_io::hashing::twox_128(module),
+		sp_io::hashing::twox_128(storage),
+	]
+	.concat()
+}
+
+fn blake2_128_concat(data: &[u8]) -> Vec<u8> {
+	let mut hashed = sp_io::hashing::blake2_128(data).to_vec();
+	hashed.extend_from_slice(data);
+	hashed
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::storage::StateVersion;
+	use sp_state_machine::{prove_read, InMemoryBackend};
+
+	const ADDRESS: H160 = H160::repeat_byte(0x11);
+
+	fn state() -> InMemoryBackend<Blake2Hasher> {
+		let entries = vec![
+			(
+				system_account_key(&ADDRESS),
+				Some((7u32, 1_000u128).encode()),
+			),
+			(account_code_key(ADDRESS), Some(vec![0x60, 0x00].encode())),
+			(
+				account_storage_key(ADDRESS, H256::repeat_byte(1)),
+				Some(H256::repeat_byte(2).encode()),
+			),
+		];
+		(vec![(None, entries)], StateVersion::V1).into()
+	}
+
+	fn proof(keys: &[Vec<u8>]) -> (H256, Vec<Vec<u8>>) {
+		let state = state();
+		let root = *state.root();
+		(
+			root,
+			prove_read(state, keys).unwrap().into_iter_nodes().collect(),
+		)
+	}
+
+	#[test]
+	fn verifies_account_and_code() {
+		let (root, proof) = proof(&[system_account_key(&ADDRESS), account_code_key(ADDRESS)]);
+
+		assert_eq!(
+			verify_account::<(u32, u128)>(&root, &proof, &system_account_key(&ADDRESS)),
+			Ok(Some((7, 1_000)))
+		);
+		assert_eq!(verify_code(&root, &proof, ADDRESS), Ok(vec![0x60, 0x00]));
+	}
+
+	#[test]
+	fn verifies_storage() {
+		let (root, proof) = proof(&[
+			account_storage_key(ADDRESS, H256::repeat_byte(1)),
+			account_storage_key(ADDRESS, H256::repeat_byte(3)),
+		]);
+
+		assert_eq!(
+			verify_storage(&root, &proof, ADDRESS, H256::repeat_byte(1)),
+			Ok(H256::repeat_byte(2))
+		);
+		assert_eq!(
+			verify_storage(&root, &proof, ADDRESS, H256::repeat_byte(3)),
+			Ok(H256::zero())
+		);
+	}
+
+	#[test]
+	fn rejects_incomplete_proofs() {
+		let (root, proof) = proof(&[account_code_key(ADDRESS)]);
+
+		assert_eq!(
+			verify_code(&H256::repeat_byte(1), &proof, ADDRESS),
+			Err(ProofError::IncompleteProof)
+		);
+		// The proof of the code doesn't reach the storage entries.
+		assert_eq!(
+			verify_storage(&root, &proof, ADDRESS, H256::repeat_byte(1)),
+			Err(ProofError::IncompleteProof)
+		);
+	}
+}
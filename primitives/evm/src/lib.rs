@@ -18,6 +18,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod precompile;
+pub mod tracing;
 mod validation;
 
 use codec::{Decode, Encode};
@@ -25,7 +26,7 @@ pub use evm::ExitReason;
 use frame_support::weights::Weight;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
-use sp_core::{H160, U256};
+use sp_core::{H160, H256, U256};
 use sp_std::vec::Vec;
 
 pub use evm::backend::{Basic as Account, Log};
@@ -84,6 +85,25 @@ pub struct GenesisAccount {
 	pub code: Vec<u8>,
 }
 
+/// Account state overridden before executing a call, used to simulate it on top of
+/// a modified state. Overrides are only applied by the RPC and never committed.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct StateOverride {
+	/// Overridden account address.
+	pub address: H160,
+	/// Account balance.
+	pub balance: Option<U256>,
+	/// Account nonce.
+	pub nonce: Option<U256>,
+	/// Account code.
+	pub code: Option<Vec<u8>>,
+	/// Full account storage, replacing the existing one.
+	pub state: Option<Vec<(H256, H256)>>,
+	/// Account storage slots, leaving the rest of the storage untouched.
+	pub state_diff: Option<Vec<(H256, H256)>>,
+}
+
 /// Trait that outputs the current transaction gas price.
 pub trait FeeCalculator {
 	/// Return the minimal required gas price.
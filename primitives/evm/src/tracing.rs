@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traces of the EVM execution, produced by the runtime and formatted by the RPC.
+
+use codec::{Decode, Encode};
+use evm::ExitReason;
+use sp_core::{H160, H256, U256};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// Tracer to attach to the traced execution.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TracerConfig {
+	/// Collects the state of the EVM at each executed step.
+	StructLogger {
+		disable_stack: bool,
+		disable_memory: bool,
+		disable_storage: bool,
+	},
+	/// Collects the tree of the executed calls.
+	CallTracer,
+}
+
+/// State of the EVM before an executed step.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: u64,
+	/// Executed opcode.
+	pub op: u8,
+	/// Gas remaining before the step.
+	pub gas: u64,
+	/// Gas cost of the step.
+	pub gas_cost: u64,
+	/// Call depth, starting from 1.
+	pub depth: u32,
+	/// Stack, unless disabled.
+	pub stack: Option<Vec<H256>>,
+	/// Memory, unless disabled.
+	pub memory: Option<Vec<u8>>,
+	/// Storage slots of the executing contract accessed so far, only set on `SLOAD` and `SSTORE`.
+	pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+/// Trace collected by the struct logger.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct StructLoggerTrace {
+	/// Gas used by the traced operation.
+	pub gas: U256,
+	/// Whether the traced operation failed.
+	pub failed: bool,
+	/// Data returned by the traced operation.
+	pub return_value: Vec<u8>,
+	/// Executed steps.
+	pub struct_logs: Vec<StructLog>,
+}
+
+/// Kind of the traced call.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum CallType {
+	Call,
+	CallCode,
+	DelegateCall,
+	StaticCall,
+	Create,
+	Create2,
+	SelfDestruct,
+}
+
+/// Call collected by the call tracer.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct CallFrame {
+	pub call_type: CallType,
+	pub from: H160,
+	pub to: H160,
+	/// Transferred value, `None` for the calls which can't transfer it.
+	pub value: Option<U256>,
+	pub gas: U256,
+	pub gas_used: U256,
+	pub input: Vec<u8>,
+	pub output: Vec<u8>,
+	/// Exit reason, `None` if the call didn't exit.
+	pub exit_reason: Option<ExitReason>,
+	/// Nested calls in the order of execution.
+	pub calls: Vec<CallFrame>,
+}
+
+/// Trace collected by the configured tracer.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Trace {
+	StructLogger(StructLoggerTrace),
+	/// Root call, `None` if nothing was executed.
+	CallTracer(Option<CallFrame>),
+}
+
+/// Result of an execution traced by the access list tracer.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AccessListInfo {
+	pub exit_reason: ExitReason,
+	/// Data returned by the call, empty for a create.
+	pub value: Vec<u8>,
+	pub used_gas: U256,
+	/// Accessed accounts along with their accessed storage slots, in ascending order.
+	pub access_list: Vec<(H160, Vec<H256>)>,
+}
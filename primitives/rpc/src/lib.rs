@@ -39,7 +39,7 @@ pub struct TransactionStatus {
 
 sp_api::decl_runtime_apis! {
 	/// API necessary for Ethereum-compatibility layer.
-	#[api_version(4)]
+	#[api_version(7)]
 	pub trait EthereumRuntimeRPCApi {
 		/// Returns runtime defined pallet_evm::ChainId.
 		fn chain_id() -> u64;
@@ -77,6 +77,7 @@ sp_api::decl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 		) -> Result<fp_evm::CallInfo, sp_runtime::DispatchError>;
+		#[changed_in(5)]
 		fn call(
 			from: H160,
 			to: H160,
@@ -89,6 +90,20 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
 		) -> Result<fp_evm::CallInfo, sp_runtime::DispatchError>;
+		/// Executes the call on top of the `state_overrides`, which are discarded afterwards.
+		fn call(
+			from: H160,
+			to: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			state_overrides: Vec<fp_evm::StateOverride>,
+		) -> Result<fp_evm::CallInfo, sp_runtime::DispatchError>;
 		/// Returns a frame_ethereum::create response.
 		#[changed_in(2)]
 		fn create(
@@ -111,6 +126,19 @@ sp_api::decl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 		) -> Result<fp_evm::CreateInfo, sp_runtime::DispatchError>;
+		#[changed_in(5)]
+		fn create(
+			from: H160,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			estimate: bool,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<fp_evm::CreateInfo, sp_runtime::DispatchError>;
+		/// Executes the create on top of the `state_overrides`, which are discarded afterwards.
 		fn create(
 			from: H160,
 			data: Vec<u8>,
@@ -121,6 +149,7 @@ sp_api::decl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			state_overrides: Vec<fp_evm::StateOverride>,
 		) -> Result<fp_evm::CreateInfo, sp_runtime::DispatchError>;
 		/// Return the current block. Legacy.
 		#[changed_in(2)]
@@ -164,6 +193,54 @@ sp_api::decl_runtime_apis! {
 		) -> Vec<ethereum::TransactionV2>;
 		/// Return the elasticity multiplier.
 		fn elasticity() -> Option<Permill>;
+		/// Returns the raw keys of the storage entries holding the nonce and the balance
+		/// of the account mapped to the address.
+		fn account_storage_keys(address: H160) -> Vec<Vec<u8>>;
+		/// Executes the call, or the create if `to` is `None`, collecting the accounts and
+		/// storage slots it accessed.
+		fn create_access_list(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<fp_evm::tracing::AccessListInfo, sp_runtime::DispatchError>;
+	}
+
+	/// API necessary for the `debug` and `trace` RPCs, re-executing transactions with a tracer
+	/// attached.
+	#[api_version(2)]
+	pub trait DebugRuntimeApi {
+		/// Applies the `extrinsics` of the initialized block up to the Ethereum transaction
+		/// with the given hash and traces its execution.
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction_hash: H256,
+			tracer: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::Trace, sp_runtime::DispatchError>;
+		/// Traces the execution of the call, or of the create if `to` is `None`.
+		fn trace_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			tracer: fp_evm::tracing::TracerConfig,
+		) -> Result<fp_evm::tracing::Trace, sp_runtime::DispatchError>;
+		/// Applies the `extrinsics` of the initialized block and traces the execution of each
+		/// Ethereum transaction, returned in the block order along with its hash.
+		fn trace_block(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			tracer: fp_evm::tracing::TracerConfig,
+		) -> Result<Vec<(H256, fp_evm::tracing::Trace)>, sp_runtime::DispatchError>;
 	}
 
 	#[api_version(2)]
@@ -34,6 +34,33 @@ pub struct RunCmd {
 	#[clap(long, default_value = "10000")]
 	pub max_past_logs: u32,
 
+	/// Maximum number of blocks in a log query, or in the replay of a logs subscription starting
+	/// in the past, unlimited if unset.
+	#[clap(long)]
+	pub max_block_range: Option<u32>,
+
+	/// Timeout of a log query, in seconds.
+	#[clap(long, default_value = "10")]
+	pub logs_request_timeout: u64,
+
+	/// Expose the `debug` and `trace` RPC namespaces. The runtime must be built with the
+	/// `tracing` feature.
+	#[clap(long)]
+	pub enable_tracing_api: bool,
+
+	/// Maximum number of blocks in a `trace_filter` query.
+	#[clap(long, default_value = "1000")]
+	pub trace_max_block_range: u64,
+
+	/// Trace the finalized blocks in the background, caching the traces served by the `trace`
+	/// RPC.
+	#[clap(long)]
+	pub enable_trace_cache: bool,
+
+	/// Index the logs of the finalized blocks by address and topic, speeding up `eth_getLogs`.
+	#[clap(long)]
+	pub enable_log_index: bool,
+
 	/// Maximum fee history cache size.
 	#[clap(long, default_value = "2048")]
 	pub fee_history_limit: u64,
@@ -1,12 +1,13 @@
 //! A collection of node-specific RPC methods.
 
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 use jsonrpsee::RpcModule;
 // Substrate
 use sc_client_api::{
 	backend::{AuxStore, Backend, StateBackend, StorageProvider},
 	client::BlockchainEvents,
+	BlockBackend, ProofProvider,
 };
 #[cfg(feature = "manual-seal")]
 use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApiServer};
@@ -51,6 +52,14 @@ pub struct FullDeps<C, P, A: ChainApi> {
 	pub backend: Arc<fc_db::Backend<Block>>,
 	/// Maximum number of logs in a query.
 	pub max_past_logs: u32,
+	/// Maximum number of blocks in a log query or a logs subscription replay.
+	pub max_block_range: Option<u32>,
+	/// Whether to expose the `debug` and `trace` RPC namespaces.
+	pub enable_tracing_api: bool,
+	/// Maximum number of blocks in a `trace_filter` query.
+	pub trace_max_block_range: u64,
+	/// Timeout of a log query.
+	pub logs_request_timeout: Duration,
 	/// Fee history cache.
 	pub fee_history_cache: FeeHistoryCache,
 	/// Maximum fee history cache size.
@@ -108,7 +117,7 @@ where
 	BE: Backend<Block> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
 	C: ProvideRuntimeApi<Block> + StorageProvider<Block, BE> + AuxStore,
-	C: BlockchainEvents<Block>,
+	C: BlockchainEvents<Block> + BlockBackend<Block> + ProofProvider<Block>,
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError>,
 	C: Send + Sync + 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
@@ -116,12 +125,14 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
 	C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
+	C::Api: fp_rpc::DebugRuntimeApi<Block>,
 	P: TransactionPool<Block = Block> + 'static,
 	A: ChainApi<Block = Block> + 'static,
 {
 	use fc_rpc::{
-		Eth, EthApiServer, EthDevSigner, EthFilter, EthFilterApiServer, EthPubSub,
-		EthPubSubApiServer, EthSigner, Net, NetApiServer, Web3, Web3ApiServer,
+		Debug, DebugApiServer, Eth, EthApiServer, EthDevSigner, EthFilter, EthFilterApiServer,
+		EthPubSub, EthPubSubApiServer, EthSigner, Net, NetApiServer, Trace, TraceApiServer, TxPool,
+		TxPoolApiServer, Web3, Web3ApiServer,
 	};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
@@ -138,6 +149,10 @@ where
 		filter_pool,
 		backend,
 		max_past_logs,
+		max_block_range,
+		enable_tracing_api,
+		trace_max_block_range,
+		logs_request_timeout,
 		fee_history_cache,
 		fee_history_cache_limit,
 		overrides,
@@ -158,7 +173,7 @@ where
 		Eth::new(
 			client.clone(),
 			pool.clone(),
-			graph,
+			graph.clone(),
 			Some(frontier_template_runtime::TransactionConverter),
 			network.clone(),
 			signers,
@@ -174,15 +189,23 @@ where
 		.into_rpc(),
 	)?;
 
+	if enable_tracing_api {
+		io.merge(Debug::new(client.clone(), backend.clone()).into_rpc())?;
+		io.merge(Trace::new(client.clone(), backend.clone(), trace_max_block_range).into_rpc())?;
+	}
+	io.merge(TxPool::new(client.clone(), graph).into_rpc())?;
+
 	if let Some(filter_pool) = filter_pool {
 		io.merge(
 			EthFilter::new(
 				client.clone(),
-				backend,
+				backend.clone(),
 				filter_pool,
 				500_usize, // max stored filters
 				max_past_logs,
-				block_data_cache,
+				max_block_range,
+				logs_request_timeout,
+				block_data_cache.clone(),
 			)
 			.into_rpc(),
 		)?;
@@ -195,6 +218,9 @@ where
 			network.clone(),
 			subscription_task_executor,
 			overrides,
+			backend,
+			block_data_cache,
+			max_block_range,
 		)
 		.into_rpc(),
 	)?;
@@ -16,10 +16,11 @@ use sc_keystore::LocalKeystore;
 use sc_service::{error::Error as ServiceError, BasePath, Configuration, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_core::U256;
+use sp_runtime::generic::BlockId;
 // Frontier
 use fc_consensus::FrontierBlockImport;
 use fc_db::Backend as FrontierBackend;
-use fc_mapping_sync::{MappingSyncWorker, SyncStrategy};
+use fc_mapping_sync::{LogIndexSyncWorker, MappingSyncWorker, SyncStrategy, TraceSyncWorker};
 use fc_rpc::{EthTask, OverrideHandle};
 use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 // Runtime
@@ -366,6 +367,10 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 		let overrides = overrides.clone();
 		let fee_history_cache = fee_history_cache.clone();
 		let max_past_logs = cli.run.max_past_logs;
+		let max_block_range = cli.run.max_block_range;
+		let enable_tracing_api = cli.run.enable_tracing_api;
+		let trace_max_block_range = cli.run.trace_max_block_range;
+		let logs_request_timeout = Duration::from_secs(cli.run.logs_request_timeout);
 
 		Box::new(move |deny_unsafe, subscription_task_executor| {
 			let deps = crate::rpc::FullDeps {
@@ -379,6 +384,10 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 				filter_pool: filter_pool.clone(),
 				backend: frontier_backend.clone(),
 				max_past_logs,
+				max_block_range,
+				enable_tracing_api,
+				trace_max_block_range,
+				logs_request_timeout,
 				fee_history_cache: fee_history_cache.clone(),
 				fee_history_cache_limit,
 				overrides: overrides.clone(),
@@ -411,6 +420,8 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 		overrides,
 		fee_history_cache,
 		fee_history_cache_limit,
+		cli.run.enable_trace_cache,
+		cli.run.enable_log_index,
 	);
 
 	let (block_import, grandpa_link) = consensus_result;
@@ -593,6 +604,10 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 		let overrides = overrides.clone();
 		let fee_history_cache = fee_history_cache.clone();
 		let max_past_logs = cli.run.max_past_logs;
+		let max_block_range = cli.run.max_block_range;
+		let enable_tracing_api = cli.run.enable_tracing_api;
+		let trace_max_block_range = cli.run.trace_max_block_range;
+		let logs_request_timeout = Duration::from_secs(cli.run.logs_request_timeout);
 
 		Box::new(move |deny_unsafe, subscription_task_executor| {
 			let deps = crate::rpc::FullDeps {
@@ -606,6 +621,10 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 				filter_pool: filter_pool.clone(),
 				backend: frontier_backend.clone(),
 				max_past_logs,
+				max_block_range,
+				enable_tracing_api,
+				trace_max_block_range,
+				logs_request_timeout,
 				fee_history_cache: fee_history_cache.clone(),
 				fee_history_cache_limit,
 				overrides: overrides.clone(),
@@ -639,6 +658,8 @@ pub fn new_full(mut config: Configuration, cli: &Cli) -> Result<TaskManager, Ser
 		overrides,
 		fee_history_cache,
 		fee_history_cache_limit,
+		cli.run.enable_trace_cache,
+		cli.run.enable_log_index,
 	);
 
 	if role.is_authority() {
@@ -734,16 +755,19 @@ fn spawn_frontier_tasks(
 	overrides: Arc<OverrideHandle<Block>>,
 	fee_history_cache: FeeHistoryCache,
 	fee_history_cache_limit: FeeHistoryCacheLimit,
+	enable_trace_cache: bool,
+	enable_log_index: bool,
 ) {
 	task_manager.spawn_essential_handle().spawn(
 		"frontier-mapping-sync-worker",
 		None,
 		MappingSyncWorker::new(
 			client.import_notification_stream(),
+			client.finality_notification_stream(),
 			Duration::new(6, 0),
 			client.clone(),
-			backend,
-			frontier_backend,
+			backend.clone(),
+			frontier_backend.clone(),
 			3,
 			0,
 			SyncStrategy::Normal,
@@ -751,6 +775,59 @@ fn spawn_frontier_tasks(
 		.for_each(|()| future::ready(())),
 	);
 
+	// Spawn Frontier trace cache worker.
+	if enable_trace_cache {
+		task_manager.spawn_essential_handle().spawn(
+			"frontier-trace-sync-worker",
+			None,
+			TraceSyncWorker::new(
+				client.finality_notification_stream(),
+				Duration::new(6, 0),
+				client.clone(),
+				frontier_backend.clone(),
+				3,
+				0,
+			)
+			.for_each(|()| future::ready(())),
+		);
+	}
+
+	// Spawn Frontier log index worker.
+	if enable_log_index {
+		// The transaction statuses are read as the RPC reads them.
+		let transaction_statuses = {
+			let client = client.clone();
+			let overrides = overrides.clone();
+			move |id: &BlockId<Block>| {
+				let schema = fc_rpc::frontier_backend_client::onchain_storage_schema::<
+					Block,
+					FullClient,
+					FullBackend,
+				>(client.as_ref(), *id);
+				overrides
+					.schemas
+					.get(&schema)
+					.unwrap_or(&overrides.fallback)
+					.current_transaction_statuses(id)
+			}
+		};
+		task_manager.spawn_essential_handle().spawn(
+			"frontier-log-index-worker",
+			None,
+			LogIndexSyncWorker::new(
+				client.finality_notification_stream(),
+				Duration::new(6, 0),
+				client.clone(),
+				backend,
+				frontier_backend,
+				transaction_statuses,
+				128,
+				0,
+			)
+			.for_each(|()| future::ready(())),
+		);
+	}
+
 	// Spawn Frontier EthFilterApi maintenance task.
 	if let Some(filter_pool) = filter_pool {
 		// Each filter is allowed to stay in the pool for 100 blocks.
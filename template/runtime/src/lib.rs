@@ -37,11 +37,16 @@ use pallet_grandpa::{
 };
 use pallet_transaction_payment::CurrencyAdapter;
 // Frontier
+use fp_evm::tracing::{AccessListInfo, Trace, TracerConfig};
 use fp_rpc::TransactionStatus;
 use pallet_ethereum::{Call::transact, Transaction as EthereumTransaction};
 use pallet_evm::{
-	Account as EVMAccount, EnsureAddressTruncated, FeeCalculator, GasWeightMapping,
-	HashedAddressMapping, Runner,
+	runner::access_list::record_access_list, Account as EVMAccount, AddressMapping,
+	CallOrCreateInfo, EnsureAddressTruncated, FeeCalculator, GasWeightMapping,
+	HashedAddressMapping, PrecompileSet, Runner,
+};
+use pallet_evm_precompile_storage_reader::meta_storage_reader::{
+	DenyList, StorageEntryList, StoreStorageMetadata,
 };
 
 // A few exports that help ease life for downstream crates.
@@ -60,6 +65,7 @@ pub use pallet_timestamp::Call as TimestampCall;
 
 mod precompiles;
 use precompiles::FrontierPrecompiles;
+mod tracing;
 
 /// Type of block number.
 pub type BlockNumber = u32;
@@ -325,22 +331,28 @@ parameter_types! {
 	pub const ByteReadWeight: Weight = Weight::from_ref_time(100);
 }
 
+parameter_types! {
+	/// Storage of the consensus and the sudo pallets which isn't exposed to the contracts.
+	pub const HiddenStorage: StorageEntryList = &[
+		("Aura", None),
+		("Grandpa", None),
+		("Sudo", None),
+	];
+}
+
+// Exposes storage of the pallets using the runtime metadata, except for the hidden ones.
 pallet_evm_precompile_storage_reader::impl_pallet_storage_metadata_provider!(
-	for Runtime:
-		"System" => System,
-		"Timestamp" => Timestamp,
-		"Aura" => Aura,
-		"Grandpa" => Grandpa,
-		"Balances" => Balances,
-		"TransactionPayment" => TransactionPayment,
-		"Sudo" => Sudo,
-		"Ethereum" => Ethereum,
-		"EVM" => EVM,
-		"DynamicFee" => DynamicFee
+	for Runtime: from runtime metadata filtered by DenyList<HiddenStorage>
 );
 
 impl pallet_evm::Config for Runtime {
 	type ByteReadWeight = ByteReadWeight;
+	type StorageReadFilter = (
+		pallet_evm_precompile_storage_reader::filter::DenyKeyPrefixes<
+			pallet_evm_precompile_storage_reader::filter::WellKnownKeys,
+		>,
+		DenyList<HiddenStorage>,
+	);
 	type FeeCalculator = BaseFee;
 	type GasWeightMapping = FixedGasWeightMapping;
 	type BlockHashMapping = pallet_ethereum::EthereumBlockHashMapping<Self>;
@@ -401,6 +413,21 @@ impl pallet_hotfix_sufficients::Config for Runtime {
 	type WeightInfo = pallet_hotfix_sufficients::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const SnapshotsRetention: u32 = 256;
+	pub const MaxSnapshotKeys: u32 = 32;
+	pub const MaxSnapshotKeyLen: u32 = 128;
+}
+
+impl pallet_evm_storage_snapshots::Config for Runtime {
+	type Event = Event;
+	type RegisterOrigin = frame_system::EnsureRoot<AccountId>;
+	type Retention = SnapshotsRetention;
+	type MaxKeys = MaxSnapshotKeys;
+	type MaxKeyLen = MaxSnapshotKeyLen;
+	type WeightInfo = pallet_evm_storage_snapshots::weights::SubstrateWeight<Runtime>;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub enum Runtime where
@@ -421,6 +448,8 @@ construct_runtime!(
 		DynamicFee: pallet_dynamic_fee,
 		BaseFee: pallet_base_fee,
 		HotfixSufficients: pallet_hotfix_sufficients,
+		// Should be the last one to checkpoint values modified by other pallets' `on_finalize`
+		EVMStorageSnapshots: pallet_evm_storage_snapshots,
 	}
 );
 
@@ -483,6 +512,7 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	StoreStorageMetadata<Runtime>,
 >;
 
 impl fp_self_contained::SelfContainedCall for Call {
@@ -662,6 +692,7 @@ impl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			state_overrides: Vec<pallet_evm::StateOverride>,
 		) -> Result<pallet_evm::CallInfo, sp_runtime::DispatchError> {
 			let config = if estimate {
 				let mut config = <Runtime as pallet_evm::Config>::config().clone();
@@ -674,6 +705,10 @@ impl_runtime_apis! {
 			let is_transactional = false;
 			let validate = true;
 			let evm_config = config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config());
+			// Runtime API calls are never committed, so are the overrides.
+			for state_override in &state_overrides {
+				EVM::apply_state_override(state_override)?;
+			}
 			<Runtime as pallet_evm::Config>::Runner::call(
 				from,
 				to,
@@ -700,6 +735,7 @@ impl_runtime_apis! {
 			nonce: Option<U256>,
 			estimate: bool,
 			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			state_overrides: Vec<pallet_evm::StateOverride>,
 		) -> Result<pallet_evm::CreateInfo, sp_runtime::DispatchError> {
 			let config = if estimate {
 				let mut config = <Runtime as pallet_evm::Config>::config().clone();
@@ -712,6 +748,10 @@ impl_runtime_apis! {
 			let is_transactional = false;
 			let validate = true;
 			let evm_config = config.as_ref().unwrap_or(<Runtime as pallet_evm::Config>::config());
+			// Runtime API calls are never committed, so are the overrides.
+			for state_override in &state_overrides {
+				EVM::apply_state_override(state_override)?;
+			}
 			<Runtime as pallet_evm::Config>::Runner::create(
 				from,
 				data,
@@ -763,6 +803,116 @@ impl_runtime_apis! {
 		fn elasticity() -> Option<Permill> {
 			Some(BaseFee::elasticity())
 		}
+
+		fn account_storage_keys(address: H160) -> Vec<Vec<u8>> {
+			let account_id =
+				<Runtime as pallet_evm::Config>::AddressMapping::into_account_id(address);
+			// Balances are stored in the system account.
+			vec![frame_system::Account::<Runtime>::hashed_key_for(account_id)]
+		}
+
+		fn create_access_list(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<AccessListInfo, sp_runtime::DispatchError> {
+			let is_transactional = false;
+			let validate = true;
+			let evm_config = <Runtime as pallet_evm::Config>::config();
+			let (result, recorder) = record_access_list(|| match to {
+				Some(to) => <Runtime as pallet_evm::Config>::Runner::call(
+					from,
+					to,
+					data,
+					value,
+					gas_limit.unique_saturated_into(),
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list.unwrap_or_default(),
+					is_transactional,
+					validate,
+					evm_config,
+				)
+				.map(CallOrCreateInfo::Call),
+				None => <Runtime as pallet_evm::Config>::Runner::create(
+					from,
+					data,
+					value,
+					gas_limit.unique_saturated_into(),
+					max_fee_per_gas,
+					max_priority_fee_per_gas,
+					nonce,
+					access_list.unwrap_or_default(),
+					is_transactional,
+					validate,
+					evm_config,
+				)
+				.map(CallOrCreateInfo::Create),
+			});
+			let (exit_reason, value, used_gas) = match result.map_err(|err| err.error)? {
+				CallOrCreateInfo::Call(info) => (info.exit_reason, info.value, info.used_gas),
+				CallOrCreateInfo::Create(info) => (info.exit_reason, Vec::new(), info.used_gas),
+			};
+
+			// The precompiles are warm regardless of the access list.
+			let precompiles = <Runtime as pallet_evm::Config>::PrecompilesValue::get();
+			Ok(AccessListInfo {
+				exit_reason,
+				value,
+				used_gas,
+				access_list: recorder.finish(|address| precompiles.is_precompile(address)),
+			})
+		}
+	}
+
+	impl fp_rpc::DebugRuntimeApi<Block> for Runtime {
+		fn trace_transaction(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			transaction_hash: H256,
+			tracer: TracerConfig,
+		) -> Result<Trace, sp_runtime::DispatchError> {
+			tracing::trace_transaction(extrinsics, transaction_hash, tracer)
+		}
+
+		fn trace_block(
+			extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+			tracer: TracerConfig,
+		) -> Result<Vec<(H256, Trace)>, sp_runtime::DispatchError> {
+			tracing::trace_block(extrinsics, tracer)
+		}
+
+		fn trace_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+			tracer: TracerConfig,
+		) -> Result<Trace, sp_runtime::DispatchError> {
+			tracing::trace_call(
+				from,
+				to,
+				data,
+				value,
+				gas_limit,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list,
+				tracer,
+			)
+		}
 	}
 
 	impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
@@ -843,10 +993,13 @@ impl_runtime_apis! {
 			use frame_benchmarking::{Benchmarking, BenchmarkList};
 			use frame_support::traits::StorageInfoTrait;
 			use pallet_hotfix_sufficients::Pallet as PalletHotfixSufficients;
+			use pallet_evm_storage_snapshots::Pallet as PalletEvmStorageSnapshots;
 
 			let mut list = Vec::<BenchmarkList>::new();
 			list_benchmarks!(list, extra);
 			list_benchmark!(list, extra, pallet_hotfix_sufficients, PalletHotfixSufficients::<Runtime>);
+			list_benchmark!(list, extra, pallet_evm_storage_snapshots, PalletEvmStorageSnapshots::<Runtime>);
+			list_benchmark!(list, extra, pallet_evm_precompile_storage_reader, pallet_evm_precompile_storage_reader::benchmarks::Pallet::<Runtime>);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
 			(list, storage_info)
@@ -858,8 +1011,9 @@ impl_runtime_apis! {
 			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark, TrackedStorageKey};
 			use pallet_evm::Pallet as PalletEvmBench;
 			use pallet_hotfix_sufficients::Pallet as PalletHotfixSufficients;
+			use pallet_evm_storage_snapshots::Pallet as PalletEvmStorageSnapshots;
 			impl frame_system_benchmarking::Config for Runtime {}
-			impl pallet_evm_precompile_storage_reader::meta_storage_reader::benchmarks::Config for Runtime {}
+			impl pallet_evm_precompile_storage_reader::benchmarks::Config for Runtime {}
 
 			let whitelist: Vec<TrackedStorageKey> = vec![];
 
@@ -867,8 +1021,9 @@ impl_runtime_apis! {
 			let params = (&config, &whitelist);
 
 			add_benchmark!(params, batches, pallet_evm, PalletEvmBench::<Runtime>);
-			add_benchmark!(params, batches, pallet_evm_precompile_storage_reader, pallet_evm_precompile_storage_reader::meta_storage_reader::benchmarks::Pallet::<Runtime>);
+			add_benchmark!(params, batches, pallet_evm_precompile_storage_reader, pallet_evm_precompile_storage_reader::benchmarks::Pallet::<Runtime>);
 			add_benchmark!(params, batches, pallet_hotfix_sufficients, PalletHotfixSufficients::<Runtime>);
+			add_benchmark!(params, batches, pallet_evm_storage_snapshots, PalletEvmStorageSnapshots::<Runtime>);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
 			Ok(batches)
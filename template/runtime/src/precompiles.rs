@@ -1,6 +1,7 @@
 use pallet_evm::{Precompile, PrecompileHandle, PrecompileResult, PrecompileSet};
 use pallet_evm_precompile_storage_reader::{
-	meta_storage_reader::PalletStorageMetadataProvider, MetaStorageReader, RawStorageReader,
+	meta_storage_reader::PalletStorageMetadataProvider, HistoricalStorageReader,
+	MetaStorageBatchReader, MetaStorageReader, RawStorageReader, StorageIterator,
 };
 use sp_core::H160;
 use sp_std::marker::PhantomData;
@@ -18,7 +19,7 @@ where
 	pub fn new() -> Self {
 		Self(Default::default())
 	}
-	pub fn used_addresses() -> [H160; 9] {
+	pub fn used_addresses() -> [H160; 12] {
 		[
 			hash(1),
 			hash(2),
@@ -27,6 +28,9 @@ where
 			hash(5),
 			hash(6),
 			hash(7),
+			hash(8),
+			hash(9),
+			hash(10),
 			hash(1024),
 			hash(1025),
 		]
@@ -34,7 +38,7 @@ where
 }
 impl<R> PrecompileSet for FrontierPrecompiles<R>
 where
-	R: PalletStorageMetadataProvider + pallet_evm::Config,
+	R: PalletStorageMetadataProvider + pallet_evm::Config + pallet_evm_storage_snapshots::Config,
 {
 	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
 		match handle.code_address() {
@@ -46,6 +50,9 @@ where
 			a if a == hash(5) => Some(Modexp::execute(handle)),
 			a if a == hash(6) => Some(MetaStorageReader::<R>::execute(handle)),
 			a if a == hash(7) => Some(RawStorageReader::<R>::execute(handle)),
+			a if a == hash(8) => Some(MetaStorageBatchReader::<R>::execute(handle)),
+			a if a == hash(9) => Some(StorageIterator::<R>::execute(handle)),
+			a if a == hash(10) => Some(HistoricalStorageReader::<R>::execute(handle)),
 			// Non-Frontier specific nor Ethereum precompiles :
 			a if a == hash(1024) => Some(Sha3FIPS256::execute(handle)),
 			a if a == hash(1025) => Some(ECRecoverPublicKey::execute(handle)),
@@ -0,0 +1,198 @@
+//! Implementations of the tracing runtime APIs.
+//!
+//! Tracing requires the EVM to emit its tracing events, which slows down the regular execution,
+//! so it's only available if the runtime is built with the `tracing` feature. Otherwise, the
+//! tracing runtime APIs return an error.
+
+use fp_evm::tracing::{Trace, TracerConfig};
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::Block as BlockT;
+use sp_std::prelude::*;
+
+use crate::Block;
+
+#[cfg(feature = "tracing")]
+mod enabled {
+	use super::*;
+	use pallet_ethereum::{Call::transact, Transaction as EthereumTransaction};
+	use pallet_evm::{CallOrCreateInfo, Runner};
+	use sp_runtime::traits::UniqueSaturatedInto;
+
+	use crate::{Call, Ethereum, Executive, Runtime};
+
+	/// Executes the Ethereum `transaction` of the `call` with the `tracer` attached, on top of the
+	/// current state.
+	fn trace_ethereum_transaction(
+		call: &pallet_ethereum::Call<Runtime>,
+		transaction: &EthereumTransaction,
+		tracer: TracerConfig,
+	) -> Result<Trace, sp_runtime::DispatchError> {
+		let source = call.check_self_contained().and_then(Result::ok).ok_or(
+			sp_runtime::DispatchError::Other("invalid transaction signature"),
+		)?;
+		Ethereum::validate_transaction_in_block(source, transaction)
+			.map_err(|_| sp_runtime::DispatchError::Other("invalid transaction"))?;
+
+		let (result, tracer) = pallet_evm::runner::tracing::trace(tracer, || {
+			Ethereum::execute(source, transaction, None)
+		});
+		let used_gas = match result.map_err(|err| err.error)?.2 {
+			CallOrCreateInfo::Call(info) => info.used_gas,
+			CallOrCreateInfo::Create(info) => info.used_gas,
+		};
+
+		Ok(tracer.finish(used_gas))
+	}
+
+	/// Applies the `extrinsics` up to the Ethereum transaction with the given hash and traces it.
+	pub fn trace_transaction(
+		extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+		transaction_hash: H256,
+		tracer: TracerConfig,
+	) -> Result<Trace, sp_runtime::DispatchError> {
+		for ext in extrinsics {
+			if let Call::Ethereum(call @ transact { transaction }) = &ext.0.function {
+				if transaction.hash() == transaction_hash {
+					return trace_ethereum_transaction(call, transaction, tracer);
+				}
+			}
+
+			let _ = Executive::apply_extrinsic(ext);
+		}
+
+		Err(sp_runtime::DispatchError::Other(
+			"transaction not found in the block",
+		))
+	}
+
+	/// Applies the `extrinsics` and traces each of the Ethereum transactions.
+	pub fn trace_block(
+		extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+		tracer: TracerConfig,
+	) -> Result<Vec<(H256, Trace)>, sp_runtime::DispatchError> {
+		let mut traces = Vec::new();
+		for ext in extrinsics {
+			match &ext.0.function {
+				Call::Ethereum(call @ transact { transaction }) => {
+					traces.push((
+						transaction.hash(),
+						trace_ethereum_transaction(call, transaction, tracer)?,
+					));
+				}
+				_ => {
+					let _ = Executive::apply_extrinsic(ext);
+				}
+			}
+		}
+
+		Ok(traces)
+	}
+
+	/// Traces the execution of the call, or of the create if `to` is `None`.
+	#[allow(clippy::too_many_arguments)]
+	pub fn trace_call(
+		from: H160,
+		to: Option<H160>,
+		data: Vec<u8>,
+		value: U256,
+		gas_limit: U256,
+		max_fee_per_gas: Option<U256>,
+		max_priority_fee_per_gas: Option<U256>,
+		nonce: Option<U256>,
+		access_list: Option<Vec<(H160, Vec<H256>)>>,
+		tracer: TracerConfig,
+	) -> Result<Trace, sp_runtime::DispatchError> {
+		let is_transactional = false;
+		let validate = true;
+		let evm_config = <Runtime as pallet_evm::Config>::config();
+		let (used_gas, tracer) = match to {
+			Some(to) => {
+				let (result, tracer) = pallet_evm::runner::tracing::trace(tracer, || {
+					<Runtime as pallet_evm::Config>::Runner::call(
+						from,
+						to,
+						data,
+						value,
+						gas_limit.unique_saturated_into(),
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						access_list.unwrap_or_default(),
+						is_transactional,
+						validate,
+						evm_config,
+					)
+				});
+				(result.map_err(|err| err.error)?.used_gas, tracer)
+			}
+			None => {
+				let (result, tracer) = pallet_evm::runner::tracing::trace(tracer, || {
+					<Runtime as pallet_evm::Config>::Runner::create(
+						from,
+						data,
+						value,
+						gas_limit.unique_saturated_into(),
+						max_fee_per_gas,
+						max_priority_fee_per_gas,
+						nonce,
+						access_list.unwrap_or_default(),
+						is_transactional,
+						validate,
+						evm_config,
+					)
+				});
+				(result.map_err(|err| err.error)?.used_gas, tracer)
+			}
+		};
+
+		Ok(tracer.finish(used_gas))
+	}
+}
+
+#[cfg(feature = "tracing")]
+pub use enabled::*;
+
+#[cfg(not(feature = "tracing"))]
+mod disabled {
+	use super::*;
+
+	const TRACING_DISABLED: sp_runtime::DispatchError =
+		sp_runtime::DispatchError::Other("the runtime is built without the `tracing` feature");
+
+	/// Applies the `extrinsics` up to the Ethereum transaction with the given hash and traces it.
+	pub fn trace_transaction(
+		_extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+		_transaction_hash: H256,
+		_tracer: TracerConfig,
+	) -> Result<Trace, sp_runtime::DispatchError> {
+		Err(TRACING_DISABLED)
+	}
+
+	/// Applies the `extrinsics` and traces each of the Ethereum transactions.
+	pub fn trace_block(
+		_extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+		_tracer: TracerConfig,
+	) -> Result<Vec<(H256, Trace)>, sp_runtime::DispatchError> {
+		Err(TRACING_DISABLED)
+	}
+
+	/// Traces the execution of the call, or of the create if `to` is `None`.
+	#[allow(clippy::too_many_arguments)]
+	pub fn trace_call(
+		_from: H160,
+		_to: Option<H160>,
+		_data: Vec<u8>,
+		_value: U256,
+		_gas_limit: U256,
+		_max_fee_per_gas: Option<U256>,
+		_max_priority_fee_per_gas: Option<U256>,
+		_nonce: Option<U256>,
+		_access_list: Option<Vec<(H160, Vec<H256>)>>,
+		_tracer: TracerConfig,
+	) -> Result<Trace, sp_runtime::DispatchError> {
+		Err(TRACING_DISABLED)
+	}
+}
+
+#[cfg(not(feature = "tracing"))]
+pub use disabled::*;
@@ -0,0 +1,9 @@
+pragma solidity 0.8.2;
+
+contract EventEmitter {
+	event Constructed(address indexed owner);
+
+	constructor() {
+		emit Constructed(msg.sender);
+	}
+}
@@ -0,0 +1,60 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+import { AbiItem } from "web3-utils";
+
+import Storage from "../build/contracts/Storage.json";
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (Access List)", (context) => {
+	const STORAGE_CONTRACT_BYTECODE = Storage.bytecode;
+	const STORAGE_CONTRACT_ABI = Storage.abi as AbiItem[];
+	const KEY = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+	let contractAddress;
+
+	step("should create the access list of a call", async function () {
+		this.timeout(15000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: STORAGE_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x200000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+		contractAddress = (await context.web3.eth.getTransactionReceipt(tx.transactionHash)).contractAddress;
+
+		const contract = new context.web3.eth.Contract(STORAGE_CONTRACT_ABI, contractAddress);
+		const result = (
+			await customRequest(context.web3, "eth_createAccessList", [
+				{
+					from: GENESIS_ACCOUNT,
+					to: contractAddress,
+					data: contract.methods.setStorage(KEY, "0x" + "01".repeat(32)).encodeABI(),
+				},
+				"latest",
+			])
+		).result;
+
+		expect(result.accessList).to.be.deep.equal([{ address: contractAddress.toLowerCase(), storageKeys: [KEY] }]);
+		expect(Number(result.gasUsed)).to.be.greaterThan(21000);
+		expect(result.error).to.be.undefined;
+	});
+
+	step("should report the failure of the call", async function () {
+		const result = (
+			await customRequest(context.web3, "eth_createAccessList", [
+				{ from: GENESIS_ACCOUNT, to: contractAddress, data: "0xdeadbeef" },
+				"latest",
+			])
+		).result;
+
+		expect(result.accessList).to.be.empty;
+		expect(result.error).to.include("revert");
+	});
+});
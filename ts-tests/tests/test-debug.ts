@@ -0,0 +1,77 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import Test from "../build/contracts/Test.json";
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (Debug)", (context) => {
+	const TEST_CONTRACT_BYTECODE = Test.bytecode;
+	const TEST_CONTRACT_DEPLOYED_BYTECODE = Test.deployedBytecode;
+
+	let transactionHash;
+
+	step("should trace a transaction with the struct logger", async function () {
+		this.timeout(15000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: TEST_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		transactionHash = (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+		await createAndFinalizeBlock(context.web3);
+
+		const receipt = await context.web3.eth.getTransactionReceipt(transactionHash);
+		const trace = (await customRequest(context.web3, "debug_traceTransaction", [transactionHash])).result;
+
+		expect(trace.failed).to.be.false;
+		expect(trace.gas).to.be.equal(receipt.gasUsed);
+		expect(`0x${trace.returnValue}`).to.be.equal(TEST_CONTRACT_DEPLOYED_BYTECODE);
+		expect(trace.structLogs[0]).to.include({ pc: 0, op: "PUSH1", depth: 1 });
+		expect(trace.structLogs[trace.structLogs.length - 1].op).to.be.equal("RETURN");
+	});
+
+	step("should omit the disabled fields of the struct logs", async function () {
+		const trace = (
+			await customRequest(context.web3, "debug_traceTransaction", [
+				transactionHash,
+				{ disableStack: true, disableMemory: true },
+			])
+		).result;
+
+		expect(trace.structLogs[0]).to.not.have.property("stack");
+		expect(trace.structLogs[0]).to.not.have.property("memory");
+	});
+
+	step("should trace a call with the call tracer", async function () {
+		const trace = (
+			await customRequest(context.web3, "debug_traceCall", [
+				{
+					from: GENESIS_ACCOUNT,
+					data: TEST_CONTRACT_BYTECODE,
+				},
+				"latest",
+				{ tracer: "callTracer" },
+			])
+		).result;
+
+		expect(trace.type).to.be.equal("CREATE");
+		expect(trace.from).to.be.equal(GENESIS_ACCOUNT.toLowerCase());
+		expect(trace.output).to.be.equal(TEST_CONTRACT_DEPLOYED_BYTECODE);
+		expect(trace).to.not.have.property("error");
+	});
+
+	step("should reject unsupported tracers", async function () {
+		const result = await customRequest(context.web3, "debug_traceTransaction", [
+			transactionHash,
+			{ tracer: "prestateTracer" },
+		]);
+
+		expect(result.error.message).to.be.equal("tracer prestateTracer is not supported");
+	});
+});
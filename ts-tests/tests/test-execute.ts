@@ -50,6 +50,22 @@ describeWithFrontier("Frontier RPC (RPC execution)", (context) => {
 		);
 	});
 
+	// Goes through the runtime API version dispatch of `eth_call`, which has to accept the
+	// version of the current runtime.
+	step("should call an existing account", async function () {
+		const IDENTITY_PRECOMPILE = "0x0000000000000000000000000000000000000004";
+		const result = await customRequest(context.web3, "eth_call", [
+			{
+				from: GENESIS_ACCOUNT,
+				to: IDENTITY_PRECOMPILE,
+				data: "0x1234",
+			},
+		]);
+
+		expect((result as any).error).to.not.exist;
+		expect(result.result).to.be.equal("0x1234");
+	});
+
 	step("should estimateGas with gas limit under block gas limit", async function () {
 		const result = await customRequest(context.web3, "eth_estimateGas", [
 			{
@@ -88,4 +104,83 @@ describeWithFrontier("Frontier RPC (RPC execution)", (context) => {
 			"provided gas limit is too high (can be up to 10x the block gas limit)"
 		);
 	});
+
+	step("should estimateGas against the requested block", async function () {
+		for (const block of ["earliest", "latest", "pending"]) {
+			const result = await customRequest(context.web3, "eth_estimateGas", [
+				{
+					from: GENESIS_ACCOUNT,
+					data: TEST_CONTRACT_BYTECODE,
+				},
+				block,
+			]);
+
+			expect(result.result).to.be.equal("0x3043a");
+		}
+	});
+
+	step("shouldn't estimateGas against an unknown block", async function () {
+		const result = await customRequest(context.web3, "eth_estimateGas", [
+			{
+				from: GENESIS_ACCOUNT,
+				data: TEST_CONTRACT_BYTECODE,
+			},
+			"0x64",
+		]);
+
+		expect(result.result).to.not.exist;
+		expect((result as any).error.message).to.be.equal("header not found");
+	});
+
+	const OVERRIDDEN_ADDRESS = "0x1111111111111111111111111111111111111111";
+	// multiply(3)
+	const MULTIPLY_CALL_DATA = "0xc6888fa10000000000000000000000000000000000000000000000000000000000000003";
+
+	step("should call with overridden code", async function () {
+		const result = await customRequest(context.web3, "eth_call", [
+			{
+				from: GENESIS_ACCOUNT,
+				to: OVERRIDDEN_ADDRESS,
+				data: MULTIPLY_CALL_DATA,
+			},
+			"latest",
+			{ [OVERRIDDEN_ADDRESS]: { code: TEST_CONTRACT_DEPLOYED_BYTECODE } },
+		]);
+
+		expect(result.result).to.be.equal(
+			"0x0000000000000000000000000000000000000000000000000000000000000015"
+		);
+	});
+
+	step("should estimateGas with overridden code", async function () {
+		const result = await customRequest(context.web3, "eth_estimateGas", [
+			{
+				from: GENESIS_ACCOUNT,
+				to: OVERRIDDEN_ADDRESS,
+				data: MULTIPLY_CALL_DATA,
+			},
+			"latest",
+			{ [OVERRIDDEN_ADDRESS]: { code: TEST_CONTRACT_DEPLOYED_BYTECODE } },
+		]);
+
+		expect(Number(result.result)).to.be.greaterThan(21000);
+	});
+
+	step("shouldn't call with both state and stateDiff overridden", async function () {
+		const slot = "0x0000000000000000000000000000000000000000000000000000000000000000";
+		const result = await customRequest(context.web3, "eth_call", [
+			{
+				from: GENESIS_ACCOUNT,
+				to: OVERRIDDEN_ADDRESS,
+				data: MULTIPLY_CALL_DATA,
+			},
+			"latest",
+			{ [OVERRIDDEN_ADDRESS]: { state: { [slot]: slot }, stateDiff: { [slot]: slot } } },
+		]);
+
+		expect(result.result).to.not.exist;
+		expect((result as any).error.message).to.be.equal(
+			`account ${OVERRIDDEN_ADDRESS} has both 'state' and 'stateDiff'`
+		);
+	});
 });
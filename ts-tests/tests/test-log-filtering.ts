@@ -83,4 +83,17 @@ describeWithFrontier("Frontier RPC (Log filtering)", (context) => {
 			expect(request.result.length).to.be.eq(0);
 		}
 	});
+
+	step("EthApi::getLogsPaginated - should return the whole range at once within the limits.", async function () {
+		let tx = await sendTransaction(context);
+		await createAndFinalizeBlock(context.web3);
+		let receipt = await context.web3.eth.getTransactionReceipt(tx.transactionHash);
+
+		const filter = { fromBlock: "0x0", toBlock: "latest", address: receipt.contractAddress };
+		let logs = (await customRequest(context.web3, "eth_getLogs", [filter])).result;
+		let page = (await customRequest(context.web3, "eth_getLogsPaginated", [filter])).result;
+
+		expect(page.logs).to.be.deep.eq(logs);
+		expect(page.nextBlock).to.be.null;
+	});
 });
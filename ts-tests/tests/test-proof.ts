@@ -0,0 +1,32 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_BALANCE } from "./config";
+import { customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (Proof)", (context) => {
+	// keccak256 of the empty code
+	const EMPTY_CODE_HASH = "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+
+	step("should return the account with its proofs", async function () {
+		const account = (
+			await customRequest(context.web3, "eth_getProof", [GENESIS_ACCOUNT, ["0x0", "0x1"], "latest"])
+		).result;
+
+		expect(account.address).to.be.equal(GENESIS_ACCOUNT.toLowerCase());
+		expect(BigInt(account.balance).toString()).to.be.equal(GENESIS_ACCOUNT_BALANCE);
+		expect(account.nonce).to.be.equal("0x0");
+		expect(account.codeHash).to.be.equal(EMPTY_CODE_HASH);
+		expect(account.accountProof).to.not.be.empty;
+
+		expect(account.storageProof.length).to.be.equal(2);
+		expect(account.storageProof[1]).to.include({ key: "0x1", value: "0x0" });
+		expect(account.storageProof[1].proof).to.not.be.empty;
+	});
+
+	step("should not return proofs of the pending block", async function () {
+		const result = await customRequest(context.web3, "eth_getProof", [GENESIS_ACCOUNT, [], "pending"]);
+
+		expect(result.error.message).to.be.equal("proofs of the pending block are not available");
+	});
+});
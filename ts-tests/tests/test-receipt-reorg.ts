@@ -0,0 +1,63 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (Receipt Reorg)", (context) => {
+	const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+
+	let genesisHash;
+	let txHash;
+	let retractedBlockHash;
+
+	// Creates a block on top of the given Substrate block, without finalizing it.
+	async function createBlock(parentHash: string) {
+		const response = await customRequest(context.web3, "engine_createBlock", [true, false, parentHash]);
+		await new Promise((resolve) => setTimeout(resolve, 1000));
+		return response.result.hash;
+	}
+
+	step("should find the receipt in the best chain", async function () {
+		this.timeout(20000);
+		genesisHash = (await customRequest(context.web3, "chain_getBlockHash", [0])).result;
+
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: TEST_ACCOUNT,
+				value: "0x200",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		txHash = (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+		await createBlock(genesisHash);
+
+		const receipt = await context.web3.eth.getTransactionReceipt(txHash);
+		retractedBlockHash = (await context.web3.eth.getBlock(1)).hash;
+		expect(receipt).to.include({ blockNumber: 1, blockHash: retractedBlockHash });
+	});
+
+	step("should not return the receipt of a retracted block", async function () {
+		this.timeout(20000);
+		// A longer fork becomes the best chain, the transaction goes back to the pool.
+		const forkHash = await createBlock(genesisHash);
+		await createBlock(forkHash);
+
+		const receipt = await context.web3.eth.getTransactionReceipt(txHash);
+		if (receipt !== null) {
+			expect(receipt.blockHash).to.not.be.equal(retractedBlockHash);
+		}
+	});
+
+	step("should find the receipt in the new best chain", async function () {
+		this.timeout(20000);
+		const bestHash = (await customRequest(context.web3, "chain_getBlockHash", [])).result;
+		await createBlock(bestHash);
+
+		const receipt = await context.web3.eth.getTransactionReceipt(txHash);
+		expect(receipt).to.include({ blockNumber: 3, blockHash: (await context.web3.eth.getBlock(3)).hash });
+	});
+});
@@ -0,0 +1,46 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { GENESIS_ACCOUNT } from "./config";
+import { customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (Sign)", (context) => {
+	const TYPED_DATA = {
+		types: {
+			EIP712Domain: [
+				{ name: "name", type: "string" },
+				{ name: "chainId", type: "uint256" },
+			],
+			Permit: [
+				{ name: "owner", type: "address" },
+				{ name: "value", type: "uint256" },
+			],
+		},
+		primaryType: "Permit",
+		domain: { name: "Test", chainId: 42 },
+		message: { owner: GENESIS_ACCOUNT, value: "1000" },
+	};
+
+	// The node runs without the dev signer, so there is no account to sign with.
+	step("should not sign without a signer of the account", async function () {
+		const ethSign = await customRequest(context.web3, "eth_sign", [GENESIS_ACCOUNT, "0x1234"]);
+		expect(ethSign.error.message).to.be.equal("no signer available");
+
+		const personalSign = await customRequest(context.web3, "personal_sign", ["0x1234", GENESIS_ACCOUNT]);
+		expect(personalSign.error.message).to.be.equal("no signer available");
+
+		const signTypedData = await customRequest(context.web3, "eth_signTypedData_v4", [
+			GENESIS_ACCOUNT,
+			JSON.stringify(TYPED_DATA),
+		]);
+		expect(signTypedData.error.message).to.be.equal("no signer available");
+	});
+
+	step("should validate the typed data", async function () {
+		const result = await customRequest(context.web3, "eth_signTypedData_v4", [
+			GENESIS_ACCOUNT,
+			{ ...TYPED_DATA, message: { owner: GENESIS_ACCOUNT, value: "-1" } },
+		]);
+		expect(result.error.message).to.be.equal("invalid typed data: Permit.value: out of range of uint256");
+	});
+});
@@ -0,0 +1,51 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import EventEmitter from "../build/contracts/EventEmitter.json";
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontierWs } from "./util";
+
+describeWithFrontierWs("Frontier RPC (Subscription Backfill)", (context) => {
+	let nonce = 0;
+	let logs = [];
+
+	async function deployEventEmitter() {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: EventEmitter.bytecode,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+				nonce: nonce++,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3);
+	}
+
+	step("should replay the logs from fromBlock", async function () {
+		this.timeout(20000);
+		await deployEventEmitter();
+		await deployEventEmitter();
+
+		(context.web3.currentProvider as any).on("data", (message) => {
+			if (message.method === "eth_subscription") {
+				logs.push(message.params.result);
+			}
+		});
+		await customRequest(context.web3, "eth_subscribe", ["logs", { fromBlock: "0x2" }]);
+		await new Promise((resolve) => setTimeout(resolve, 1000));
+
+		expect(logs.map((log) => log.blockNumber)).to.be.deep.equal(["0x2"]);
+	});
+
+	step("should then stream the logs of the new blocks", async function () {
+		this.timeout(20000);
+		await deployEventEmitter();
+
+		expect(logs.map((log) => log.blockNumber)).to.be.deep.equal(["0x2", "0x3"]);
+		expect(logs.every((log) => log.removed === false)).to.be.true;
+	});
+});
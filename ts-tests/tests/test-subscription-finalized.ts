@@ -0,0 +1,61 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import EventEmitter from "../build/contracts/EventEmitter.json";
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontierWs } from "./util";
+
+describeWithFrontierWs("Frontier RPC (Finalized Subscriptions)", (context) => {
+	let subscriptions = {};
+	let heads = [];
+	let logs = [];
+
+	step("should subscribe to the finalized heads and logs", async function () {
+		(context.web3.currentProvider as any).on("data", (message) => {
+			if (message.method !== "eth_subscription") {
+				return;
+			}
+			if (message.params.subscription === subscriptions["heads"]) {
+				heads.push(message.params.result);
+			} else if (message.params.subscription === subscriptions["logs"]) {
+				logs.push(message.params.result);
+			}
+		});
+		subscriptions["heads"] = (await customRequest(context.web3, "eth_subscribe", ["newFinalizedHeads"])).result;
+		subscriptions["logs"] = (await customRequest(context.web3, "eth_subscribe", ["finalizedLogs", {}])).result;
+
+		expect(subscriptions["heads"]).to.not.be.undefined;
+		expect(subscriptions["logs"]).to.not.be.undefined;
+	});
+
+	step("should not stream the blocks until they are finalized", async function () {
+		this.timeout(20000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: EventEmitter.bytecode,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createAndFinalizeBlock(context.web3, false);
+		await createAndFinalizeBlock(context.web3, false);
+
+		expect(heads).to.be.empty;
+		expect(logs).to.be.empty;
+	});
+
+	step("should stream the blocks finalized at once in order", async function () {
+		this.timeout(20000);
+		const best = (await customRequest(context.web3, "chain_getBlockHash", [])).result;
+		await customRequest(context.web3, "engine_finalizeBlock", [best, null]);
+		await new Promise((resolve) => setTimeout(resolve, 1000));
+
+		expect(heads.map((head) => head.number)).to.be.deep.equal(["0x1", "0x2"]);
+		expect(logs.length).to.be.equal(1);
+		expect(logs[0]).to.include({ blockNumber: "0x1", blockHash: heads[0].hash, removed: false });
+	});
+});
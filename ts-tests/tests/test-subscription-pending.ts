@@ -0,0 +1,67 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { customRequest, describeWithFrontierWs } from "./util";
+
+describeWithFrontierWs("Frontier RPC (Pending Transactions Subscription)", (context) => {
+	const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+	const OTHER_ACCOUNT = "0x2222222222222222222222222222222222222222";
+
+	let nonce = 0;
+	let subscriptions = {};
+	let received = {};
+
+	async function sendTransaction(to: string) {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to,
+				value: "0x200",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+				nonce: nonce++,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		return (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+	}
+
+	step("should subscribe with the full transactions flag and filters", async function () {
+		(context.web3.currentProvider as any).on("data", (message) => {
+			if (message.method === "eth_subscription") {
+				const name = Object.keys(subscriptions).find((name) => subscriptions[name] === message.params.subscription);
+				received[name].push(message.params.result);
+			}
+		});
+		const params = {
+			hashes: [],
+			full: [true],
+			filtered: [{ fullTransactions: true, to: TEST_ACCOUNT }],
+		};
+		for (const name of Object.keys(params)) {
+			received[name] = [];
+			subscriptions[name] = (
+				await customRequest(context.web3, "eth_subscribe", ["newPendingTransactions", ...params[name]])
+			).result;
+			expect(subscriptions[name]).to.not.be.undefined;
+		}
+	});
+
+	step("should stream the pending transactions", async function () {
+		this.timeout(15000);
+		const toTestAccount = await sendTransaction(TEST_ACCOUNT);
+		const toOtherAccount = await sendTransaction(OTHER_ACCOUNT);
+		await new Promise((resolve) => setTimeout(resolve, 1000));
+
+		expect(received["hashes"]).to.be.deep.equal([toTestAccount, toOtherAccount]);
+		expect(received["full"].map((tx) => tx.hash)).to.be.deep.equal([toTestAccount, toOtherAccount]);
+		expect(received["full"][0]).to.include({
+			from: GENESIS_ACCOUNT.toLowerCase(),
+			to: TEST_ACCOUNT,
+			blockHash: null,
+			value: "0x200",
+		});
+		expect(received["filtered"].map((tx) => tx.hash)).to.be.deep.equal([toTestAccount]);
+	});
+});
@@ -0,0 +1,66 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import EventEmitter from "../build/contracts/EventEmitter.json";
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { customRequest, describeWithFrontierWs } from "./util";
+
+describeWithFrontierWs("Frontier RPC (Subscription Reorg)", (context) => {
+	let genesisHash;
+	let logs = [];
+	let removedLogs = [];
+	let heads = [];
+
+	// Creates a block on top of the given Substrate block, without finalizing it.
+	async function createBlock(parentHash: string) {
+		const response = await customRequest(context.web3, "engine_createBlock", [true, false, parentHash]);
+		await new Promise((resolve) => setTimeout(resolve, 500));
+		return response.result.hash;
+	}
+
+	step("should receive the logs and heads of the best chain", async function () {
+		this.timeout(20000);
+		genesisHash = (await customRequest(context.web3, "chain_getBlockHash", [0])).result;
+
+		const logSubscription = context.web3.eth.subscribe("logs", {}, function (error, result) {});
+		const headSubscription = context.web3.eth.subscribe("newBlockHeaders", function (error, result) {});
+		await Promise.all(
+			[logSubscription, headSubscription].map(
+				(subscription) => new Promise((resolve) => subscription.on("connected", resolve))
+			)
+		);
+		logSubscription.on("data", (log) => logs.push(log));
+		// web3 reports the logs marked as removed as changes.
+		logSubscription.on("changed", (log) => removedLogs.push(log));
+		headSubscription.on("data", (head) => heads.push(head));
+
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: EventEmitter.bytecode,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+		await createBlock(genesisHash);
+
+		expect(logs.length).to.be.equal(1);
+		expect(logs[0]).to.include({ blockNumber: 1, removed: false });
+		expect(heads.map((head) => head.number)).to.be.deep.equal([1]);
+	});
+
+	step("should receive the removed logs and the new heads on reorg", async function () {
+		this.timeout(20000);
+		// A longer fork of empty blocks becomes the best chain.
+		const forkHash = await createBlock(genesisHash);
+		await createBlock(forkHash);
+
+		expect(removedLogs.length).to.be.equal(1);
+		expect(removedLogs[0]).to.include({ blockHash: logs[0].blockHash, removed: true });
+		expect(heads.map((head) => head.number)).to.be.deep.equal([1, 1, 2]);
+		expect(heads[1].hash).to.not.be.equal(heads[0].hash);
+	});
+});
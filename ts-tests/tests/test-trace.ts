@@ -0,0 +1,76 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import Test from "../build/contracts/Test.json";
+import { FIRST_CONTRACT_ADDRESS, GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (Trace)", (context) => {
+	const TEST_CONTRACT_BYTECODE = Test.bytecode;
+	const TEST_CONTRACT_DEPLOYED_BYTECODE = Test.deployedBytecode;
+
+	let transactionHash;
+
+	step("should trace a contract creation", async function () {
+		this.timeout(15000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: TEST_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		transactionHash = (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+		await createAndFinalizeBlock(context.web3);
+
+		const traces = (await customRequest(context.web3, "trace_transaction", [transactionHash])).result;
+
+		expect(traces.length).to.be.equal(1);
+		expect(traces[0]).to.include({
+			type: "create",
+			subtraces: 0,
+			transactionHash,
+			transactionPosition: 0,
+			blockNumber: 1,
+		});
+		expect(traces[0].traceAddress).to.be.empty;
+		expect(traces[0].action.from).to.be.equal(GENESIS_ACCOUNT.toLowerCase());
+		expect(traces[0].action.creationMethod).to.be.equal("create");
+		expect(traces[0].result.address).to.be.equal(FIRST_CONTRACT_ADDRESS.toLowerCase());
+		expect(traces[0].result.code).to.be.equal(TEST_CONTRACT_DEPLOYED_BYTECODE);
+	});
+
+	step("should trace the block", async function () {
+		const traces = (await customRequest(context.web3, "trace_block", ["0x1"])).result;
+
+		expect(traces.length).to.be.equal(1);
+		expect(traces[0].transactionHash).to.be.equal(transactionHash);
+	});
+
+	step("should filter the traces by address", async function () {
+		const matching = (
+			await customRequest(context.web3, "trace_filter", [
+				{ fromBlock: "0x0", toBlock: "latest", fromAddress: [GENESIS_ACCOUNT] },
+			])
+		).result;
+		expect(matching.length).to.be.equal(1);
+
+		const others = (
+			await customRequest(context.web3, "trace_filter", [
+				{ fromBlock: "0x0", toBlock: "latest", toAddress: [GENESIS_ACCOUNT] },
+			])
+		).result;
+		expect(others).to.be.empty;
+	});
+
+	step("should return null for unknown transactions", async function () {
+		const result = await customRequest(context.web3, "trace_transaction", [
+			"0x0000000000000000000000000000000000000000000000000000000000000001",
+		]);
+
+		expect(result.result).to.be.null;
+	});
+});
@@ -0,0 +1,60 @@
+import { expect } from "chai";
+import { step } from "mocha-steps";
+
+import { GENESIS_ACCOUNT, GENESIS_ACCOUNT_PRIVATE_KEY } from "./config";
+import { createAndFinalizeBlock, customRequest, describeWithFrontier } from "./util";
+
+describeWithFrontier("Frontier RPC (TxPool)", (context) => {
+	const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+
+	let pendingHash;
+	let queuedHash;
+
+	const sendTransaction = async (nonce: number) => {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: TEST_ACCOUNT,
+				value: "0x200",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+				nonce,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		return (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+	};
+
+	step("should return the pool status", async function () {
+		pendingHash = await sendTransaction(0);
+		// Nonce 1 is missing, so this one waits in the future queue.
+		queuedHash = await sendTransaction(2);
+
+		const status = (await customRequest(context.web3, "txpool_status", [])).result;
+		expect(status).to.be.deep.equal({ pending: "0x1", queued: "0x1" });
+	});
+
+	step("should return the pool content by sender and nonce", async function () {
+		const content = (await customRequest(context.web3, "txpool_content", [])).result;
+		const sender = GENESIS_ACCOUNT.toLowerCase();
+
+		expect(Object.keys(content.pending)).to.be.deep.equal([sender]);
+		expect(content.pending[sender]["0"]).to.include({ hash: pendingHash, nonce: "0x0", blockHash: null });
+		expect(content.queued[sender]["2"]).to.include({ hash: queuedHash, nonce: "0x2" });
+	});
+
+	step("should return a summary of the pool", async function () {
+		const inspect = (await customRequest(context.web3, "txpool_inspect", [])).result;
+		const sender = GENESIS_ACCOUNT.toLowerCase();
+
+		expect(inspect.pending[sender]["0"]).to.be.equal(`${TEST_ACCOUNT}: 512 wei + 1048576 gas × 1000000000 wei`);
+	});
+
+	step("should empty the ready queue once the block is produced", async function () {
+		this.timeout(15000);
+		await createAndFinalizeBlock(context.web3);
+
+		const status = (await customRequest(context.web3, "txpool_status", [])).result;
+		expect(status).to.be.deep.equal({ pending: "0x0", queued: "0x1" });
+	});
+});
@@ -78,6 +78,7 @@ export async function startFrontierNode(provider?: string): Promise<{
 		`--sealing=Manual`,
 		`--no-grandpa`,
 		`--force-authoring`,
+		`--enable-tracing-api`, // Required by the debug and trace tests
 		`-l${FRONTIER_LOG}`,
 		`--port=${PORT}`,
 		`--rpc-port=${RPC_PORT}`,